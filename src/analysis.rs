@@ -0,0 +1,55 @@
+use alloc::collections::{BTreeSet, VecDeque};
+
+use netsblox_ast::compact_str::CompactString;
+
+use crate::*;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Analysis {
+    pub unreachable: BTreeSet<CompactString>,
+    pub dead_ends: BTreeSet<CompactString>,
+    pub placeholders: BTreeSet<CompactString>,
+}
+
+impl StateMachine {
+    fn exits<'a>(&'a self, state_name: &'a str) -> BTreeSet<&'a str> {
+        let mut res = BTreeSet::new();
+        let mut visited = BTreeSet::from([state_name]);
+        let mut queue = VecDeque::from([state_name]);
+        while let Some(source) = queue.pop_front() {
+            let Some(state) = self.states.get(source) else { continue };
+            for transition in state.transitions.iter() {
+                let target = transition.new_state.as_deref().unwrap_or(source);
+                match self.states.get(target).and_then(|x| x.parent.as_ref()) {
+                    Some(_) => if visited.insert(target) { queue.push_back(target) },
+                    None => { res.insert(target); }
+                }
+            }
+        }
+        res
+    }
+    pub fn analyze(&self) -> Analysis {
+        let mut reachable = BTreeSet::new();
+        let mut queue = self.initial_state.iter().chain(self.current_state.iter()).map(|x| x.as_str()).collect::<VecDeque<_>>();
+        let rooted = !queue.is_empty();
+        while let Some(state) = queue.pop_front() {
+            if reachable.insert(state) {
+                queue.extend(self.exits(state));
+            }
+        }
+
+        let mut res = Analysis::default();
+        for state_name in self.states.iter().filter(|x| x.1.parent.is_none()).map(|x| x.0) {
+            if rooted && !reachable.contains(state_name.as_str()) {
+                res.unreachable.insert(state_name.clone());
+            }
+            if self.exits(state_name).iter().all(|&x| x == state_name) {
+                res.dead_ends.insert(state_name.clone());
+            }
+            if self.placeholders.contains(state_name) {
+                res.placeholders.insert(state_name.clone());
+            }
+        }
+        res
+    }
+}
@@ -0,0 +1,341 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+use crate::*;
+use crate::condition::sample_points;
+
+pub(crate) const FALSE: usize = 0;
+pub(crate) const TRUE: usize = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Node {
+    var: usize,
+    low: usize,
+    high: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Op {
+    And, Or,
+}
+
+#[derive(Clone, Debug)]
+pub struct Bdd {
+    atoms: Vec<Expr>,
+    nodes: Vec<Node>,
+    root: usize,
+}
+
+pub(crate) struct Builder<'a> {
+    atoms: BTreeMap<&'a Expr, usize>,
+    nodes: Vec<Node>,
+    unique: BTreeMap<Node, usize>,
+    apply_cache: BTreeMap<(Op, usize, usize), usize>,
+    not_cache: BTreeMap<usize, usize>,
+}
+impl<'a> Builder<'a> {
+    pub(crate) fn new(atoms: &BTreeSet<&'a Expr>) -> Self {
+        let terminal = Node { var: usize::MAX, low: usize::MAX, high: usize::MAX };
+        Builder {
+            atoms: atoms.iter().enumerate().map(|(i, &x)| (x, i)).collect(),
+            nodes: vec![terminal, terminal],
+            unique: BTreeMap::new(),
+            apply_cache: BTreeMap::new(),
+            not_cache: BTreeMap::new(),
+        }
+    }
+    pub(crate) fn finish(&self, root: usize) -> Bdd {
+        fn visit(nodes: &[Node], id: usize, order: &mut BTreeMap<usize, usize>, res: &mut Vec<Node>) -> usize {
+            if id <= TRUE {
+                return id;
+            }
+            if let Some(&x) = order.get(&id) {
+                return x;
+            }
+            let Node { var, low, high } = nodes[id];
+            let (low, high) = (visit(nodes, low, order, res), visit(nodes, high, order, res));
+            res.push(Node { var, low, high });
+            order.insert(id, res.len() - 1);
+            res.len() - 1
+        }
+        let mut nodes = self.nodes[..2].to_vec();
+        let root = visit(&self.nodes, root, &mut BTreeMap::new(), &mut nodes);
+
+        let atoms = self.atoms.keys().copied().collect::<Vec<_>>();
+        let used = nodes[2..].iter().map(|x| x.var).collect::<BTreeSet<_>>();
+        let renames = used.iter().enumerate().map(|(i, &x)| (x, i)).collect::<BTreeMap<_,_>>();
+        for node in nodes[2..].iter_mut() {
+            node.var = renames[&node.var];
+        }
+        Bdd { atoms: used.iter().map(|&x| atoms[x].clone()).collect(), nodes, root }
+    }
+    fn node(&mut self, var: usize, low: usize, high: usize) -> usize {
+        if low == high {
+            return low;
+        }
+        let node = Node { var, low, high };
+        if let Some(&x) = self.unique.get(&node) {
+            return x;
+        }
+        self.nodes.push(node);
+        self.unique.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+    pub(crate) fn domain(&mut self) -> usize {
+        let mut vars: BTreeMap<&Expr, Vec<(usize, Comparison)>> = BTreeMap::new();
+        for (&atom, &var) in self.atoms.iter() {
+            if let Some(comparison) = Comparison::from_expr(atom) {
+                vars.entry(comparison.var).or_default().push((var, comparison));
+            }
+        }
+
+        let mut res = TRUE;
+        for comparisons in vars.values() {
+            let mut constants = comparisons.iter().map(|x| x.1.value).collect::<Vec<_>>();
+            constants.sort_by(f64::total_cmp);
+            constants.dedup();
+
+            let mut options = FALSE;
+            for point in sample_points(&constants) {
+                let mut option = TRUE;
+                for (var, comparison) in comparisons.iter() {
+                    let literal = self.literal(*var, comparison.holds(point));
+                    option = self.apply(Op::And, option, literal);
+                }
+                options = self.apply(Op::Or, options, option);
+            }
+            res = self.apply(Op::And, res, options);
+        }
+        res
+    }
+    pub(crate) fn literal(&mut self, var: usize, value: bool) -> usize {
+        match value {
+            true => self.node(var, FALSE, TRUE),
+            false => self.node(var, TRUE, FALSE),
+        }
+    }
+    pub(crate) fn cubes(&self, root: usize) -> Vec<BTreeMap<usize, bool>> {
+        fn visit(nodes: &[Node], id: usize, path: &mut BTreeMap<usize, bool>, res: &mut Vec<BTreeMap<usize, bool>>) {
+            match id {
+                FALSE => (),
+                TRUE => res.push(path.clone()),
+                _ => {
+                    let Node { var, low, high } = nodes[id];
+                    for (value, child) in [(false, low), (true, high)] {
+                        path.insert(var, value);
+                        visit(nodes, child, path, res);
+                    }
+                    path.remove(&var);
+                }
+            }
+        }
+        let mut res = vec![];
+        visit(&self.nodes, root, &mut BTreeMap::new(), &mut res);
+        res
+    }
+    pub(crate) fn not(&mut self, a: usize) -> usize {
+        match a {
+            FALSE => return TRUE,
+            TRUE => return FALSE,
+            _ => (),
+        }
+        if let Some(&x) = self.not_cache.get(&a) {
+            return x;
+        }
+        let Node { var, low, high } = self.nodes[a];
+        let (low, high) = (self.not(low), self.not(high));
+        let res = self.node(var, low, high);
+        self.not_cache.insert(a, res);
+        res
+    }
+    pub(crate) fn apply(&mut self, op: Op, a: usize, b: usize) -> usize {
+        let (absorbing, identity) = match op {
+            Op::And => (FALSE, TRUE),
+            Op::Or => (TRUE, FALSE),
+        };
+        if a == absorbing || b == absorbing {
+            return absorbing;
+        }
+        if a == identity || a == b {
+            return b;
+        }
+        if b == identity {
+            return a;
+        }
+
+        let key = (op, a.min(b), a.max(b));
+        if let Some(&x) = self.apply_cache.get(&key) {
+            return x;
+        }
+        let (x, y) = (self.nodes[a], self.nodes[b]);
+        let var = x.var.min(y.var);
+        let (a_low, a_high) = if x.var == var { (x.low, x.high) } else { (a, a) };
+        let (b_low, b_high) = if y.var == var { (y.low, y.high) } else { (b, b) };
+        let low = self.apply(op, a_low, b_low);
+        let high = self.apply(op, a_high, b_high);
+        let res = self.node(var, low, high);
+        self.apply_cache.insert(key, res);
+        res
+    }
+    pub(crate) fn build(&mut self, cond: &RawCondition) -> usize {
+        match cond {
+            RawCondition::Const(false) => FALSE,
+            RawCondition::Const(true) => TRUE,
+            RawCondition::Atom(x) => self.literal(self.atoms[x], true),
+            RawCondition::Not(x) => {
+                let x = self.build(x);
+                self.not(x)
+            }
+            RawCondition::And(a, b) | RawCondition::Or(a, b) => {
+                let op = if let RawCondition::And(_, _) = cond { Op::And } else { Op::Or };
+                let (a, b) = (self.build(a), self.build(b));
+                self.apply(op, a, b)
+            }
+        }
+    }
+}
+
+fn size(cond: &RawCondition) -> usize {
+    match cond {
+        RawCondition::Const(_) | RawCondition::Atom(_) => 1,
+        RawCondition::Not(x) => 1 + size(x),
+        RawCondition::And(a, b) | RawCondition::Or(a, b) => 1 + size(a) + size(b),
+    }
+}
+
+impl Bdd {
+    pub fn new(cond: &RawCondition) -> Self {
+        let mut builder = Builder::new(&cond.atoms());
+        let root = builder.build(cond);
+        builder.finish(root)
+    }
+    pub fn is_satisfiable(&self) -> bool {
+        self.root != FALSE
+    }
+    pub fn is_tautology(&self) -> bool {
+        self.root == TRUE
+    }
+    pub fn satisfying_assignment(&self) -> Option<Assignment> {
+        let mut res = Assignment::new();
+        let mut id = self.root;
+        while id > TRUE {
+            let Node { var, low, high } = self.nodes[id];
+            let value = low == FALSE;
+            res.insert(self.atoms[var].clone(), value);
+            id = if value { high } else { low };
+        }
+        (id == TRUE).then_some(res)
+    }
+    pub fn atoms(&self) -> &[Expr] {
+        &self.atoms
+    }
+
+    pub fn to_condition(&self) -> RawCondition {
+        fn expand(bdd: &Bdd, id: usize, negate: bool, cache: &mut BTreeMap<(usize, bool), RawCondition>) -> RawCondition {
+            match id {
+                FALSE => return RawCondition::Const(negate),
+                TRUE => return RawCondition::Const(!negate),
+                _ => (),
+            }
+            if let Some(x) = cache.get(&(id, negate)) {
+                return x.clone();
+            }
+
+            let Node { var, low, high } = bdd.nodes[id];
+            let atom = RawCondition::Atom(bdd.atoms[var].clone());
+            let res = match (expand(bdd, low, negate, cache), expand(bdd, high, negate, cache)) {
+                (RawCondition::Const(false), RawCondition::Const(true)) => atom,
+                (RawCondition::Const(true), RawCondition::Const(false)) => !atom,
+                (RawCondition::Const(false), high) => atom & high,
+                (low, RawCondition::Const(false)) => !atom & low,
+                (RawCondition::Const(true), high) => !atom | high,
+                (low, RawCondition::Const(true)) => atom | low,
+                (low, high) => (atom.clone() & high) | (!atom & low),
+            };
+            cache.insert((id, negate), res.clone());
+            res
+        }
+
+        let mut cache = BTreeMap::new();
+        let direct = RawCondition::from(Condition::from(expand(self, self.root, false, &mut cache)));
+        let negated = RawCondition::from(Condition::from(!expand(self, self.root, true, &mut cache)));
+        match size(&negated) < size(&direct) {
+            true => negated,
+            false => direct,
+        }
+    }
+}
+impl PartialEq for Bdd {
+    fn eq(&self, other: &Self) -> bool {
+        fn visit(a: &Bdd, x: usize, b: &Bdd, y: usize, seen: &mut BTreeSet<(usize, usize)>) -> bool {
+            if x <= TRUE || y <= TRUE {
+                return x == y;
+            }
+            if !seen.insert((x, y)) {
+                return true;
+            }
+            let (n, m) = (a.nodes[x], b.nodes[y]);
+            a.atoms[n.var] == b.atoms[m.var] && visit(a, n.low, b, m.low, seen) && visit(a, n.high, b, m.high, seen)
+        }
+        visit(self, self.root, other, other.root, &mut BTreeSet::new())
+    }
+}
+impl Eq for Bdd {}
+
+// these are for callers only: compile and the exporters never use them, so labels still come from simpl
+// and `==` on Condition stays syntactic (use equivalent to compare guards semantically)
+impl Condition {
+    pub fn to_bdd(&self) -> Bdd {
+        Bdd::new(self.raw())
+    }
+    pub fn equivalent(&self, other: &Condition) -> bool {
+        let atoms = self.atoms().into_iter().chain(other.atoms()).collect();
+        let mut builder = Builder::new(&atoms);
+        let domain = builder.domain();
+        let (a, b) = (builder.build(self.raw()), builder.build(other.raw()));
+        builder.apply(Op::And, a, domain) == builder.apply(Op::And, b, domain)
+    }
+    pub fn implies(&self, other: &Condition) -> bool {
+        !(self.clone() & !other.clone()).is_satisfiable()
+    }
+    pub fn is_satisfiable(&self) -> bool {
+        let mut builder = Builder::new(&self.atoms());
+        let domain = builder.domain();
+        let a = builder.build(self.raw());
+        builder.apply(Op::And, a, domain) != FALSE
+    }
+    pub fn minimized(&self) -> Condition {
+        Condition::from(self.to_bdd().to_condition())
+    }
+}
+
+#[test]
+fn test_bdd() {
+    let a = Condition::atom("a".parse().unwrap());
+    let b = Condition::atom("b".parse().unwrap());
+    let c = Condition::atom("c".parse().unwrap());
+
+    assert!((a.clone() & (b.clone() | c.clone())).equivalent(&((a.clone() & b.clone()) | (a.clone() & c.clone()))));
+    assert!((!(a.clone() & b.clone())).equivalent(&(!a.clone() | !b.clone())));
+    assert!((a.clone() | (!a.clone() & b.clone())).equivalent(&(a.clone() | b.clone())));
+    assert!(!(a.clone() | b.clone()).equivalent(&(a.clone() & b.clone())));
+    assert!((a.clone() & b.clone() & c.clone() & !c.clone()).equivalent(&Condition::constant(false)));
+
+    assert!((a.clone() & b.clone()).implies(&a));
+    assert!(a.implies(&(a.clone() | c.clone())));
+    assert!(!a.implies(&(a.clone() & b.clone())));
+    assert!(Condition::constant(false).implies(&b));
+
+    assert!(a.is_satisfiable());
+    assert!(!((a.clone() | b.clone()) & !a.clone() & !b.clone()).is_satisfiable());
+    assert!((a.clone() | !a.clone()).to_bdd().is_tautology());
+    assert_eq!((a.clone() & !b.clone()).to_bdd().satisfying_assignment(), Some([("a".parse().unwrap(), true), ("b".parse().unwrap(), false)].into_iter().collect()));
+    assert_eq!((a.clone() & !a.clone()).to_bdd().satisfying_assignment(), None);
+    assert_eq!(((a.clone() & b.clone()) | (a.clone() & !b.clone())).to_bdd().atoms(), ["a".parse().unwrap()]);
+
+    assert_eq!(((a.clone() & b.clone()) | (a.clone() & !b.clone())).minimized().to_string(), "a");
+    assert_eq!((a.clone() | (!a.clone() & b.clone())).minimized().to_string(), "a | b");
+    assert_eq!((!(a.clone() & b.clone()) & !(!a.clone() & c.clone())).minimized().to_string(), "~((a & b) | (c & ~a))");
+    assert_eq!(((a.clone() & b.clone()) | (b.clone() & a.clone() & c.clone())).minimized(), (b.clone() & a.clone()).minimized());
+    assert_eq!((!a.clone() & !b.clone() & !c.clone()).minimized().to_string(), "~(a | b | c)");
+}
@@ -0,0 +1,176 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::fmt::Write as _;
+
+use netsblox_ast as ast;
+use netsblox_ast::compact_str::{CompactString, format_compact};
+
+use crate::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CCode {
+    pub header_name: CompactString,
+    pub header: CompactString,
+    pub source: CompactString,
+}
+
+const C_DIALECT: Dialect<'static> = Dialect { variable_prefix: "s->", function_prefix: "sf_", timer: "s->sf_timer", state_time: "s->sf_state_time", ..Dialect::new(Language::C) };
+
+fn c_helper(func: Function) -> Option<&'static str> {
+    Some(match func {
+        Function::Sin => "static double sf_sind(double x) { return sin(x * (SF_PI / 180.0)); }",
+        Function::Cos => "static double sf_cosd(double x) { return cos(x * (SF_PI / 180.0)); }",
+        Function::Tan => "static double sf_tand(double x) { return tan(x * (SF_PI / 180.0)); }",
+        Function::Asin => "static double sf_asind(double x) { return asin(x) * (180.0 / SF_PI); }",
+        Function::Acos => "static double sf_acosd(double x) { return acos(x) * (180.0 / SF_PI); }",
+        Function::Atan => "static double sf_atand(double x) { return atan(x) * (180.0 / SF_PI); }",
+        Function::Atan2 => "static double sf_atan2d(double y, double x) { return atan2(y, x) * (180.0 / SF_PI); }",
+        Function::Sqrt => "static double sf_sqrt(double x) { return sqrt(x); }",
+        Function::Floor => "static double sf_floor(double x) { return floor(x); }",
+        Function::Ceil => "static double sf_ceil(double x) { return ceil(x); }",
+        Function::Round => "static double sf_round(double x) { return round(x); }",
+        Function::Sign => "static double sf_sign(double x) { return (x > 0.0) - (x < 0.0); }",
+        Function::Abs => "static double sf_abs(double x) { return fabs(x); }",
+        Function::Log => "static double sf_log(double x) { return log(x); }",
+        Function::Mod => "static double sf_mod(double a, double b) { return b == 0.0 ? a : a - floor(a / b) * b; }",
+        Function::Randi => "static double sf_randi(double a, double b) { a = ceil(a); b = floor(b); return a + floor((double)rand() / ((double)RAND_MAX + 1.0) * (b - a + 1.0)); }",
+        Function::After => return None,
+    })
+}
+
+fn write_c_actions(res: &mut CompactString, indent: &str, actions: &VecDeque<Stmt>) {
+    for action in actions.iter() {
+        writeln!(res, "{indent}{};", action.display(C_DIALECT)).unwrap();
+    }
+}
+
+impl Project {
+    pub fn to_c(&self) -> Result<CCode, CompileError> {
+        let mut rename_pool = RenamePool::new(ast::util::c_ident);
+        let mut rename = move |x: &str| rename_pool.rename(x);
+        let project_name = rename(&self.name)?;
+
+        // enum ids are uppercased inside the pool so that names differing only in case are reported as a conflict
+        let mut state_pool = RenamePool::new(|x| ast::util::c_ident(x).map(|x| x.to_uppercase()));
+        let mut rename_state = move |x: &str| state_pool.rename(x);
+
+        let mut functions = BTreeSet::new();
+        let mut uses_pow = false;
+        for (state_machine_name, state_machine) in self.state_machines.iter() {
+            check_numeric_exprs(state_machine_name, state_machine)?;
+            visit_exprs(state_machine, &mut |expr| match expr {
+                Expr::Call { func, .. } => { functions.insert(*func); }
+                Expr::Binary { op: BinaryOp::Pow, .. } => uses_pow = true,
+                _ => (),
+            });
+        }
+
+        let header_name = format_compact!("{project_name}.h");
+        let guard = format_compact!("{}_H", project_name.to_uppercase());
+
+        let mut header = CompactString::default();
+        writeln!(header, "#ifndef {guard}").unwrap();
+        writeln!(header, "#define {guard}").unwrap();
+
+        let mut source = CompactString::default();
+        writeln!(source, "#include <math.h>").unwrap();
+        writeln!(source, "#include <stdlib.h>").unwrap();
+        writeln!(source, "#include \"{header_name}\"").unwrap();
+
+        let helpers = functions.iter().flat_map(|&f| c_helper(f)).chain(uses_pow.then_some("static double sf_pow(double a, double b) { return pow(a, b); }")).collect::<Vec<_>>();
+        if !helpers.is_empty() {
+            source.push('\n');
+            if functions.iter().any(|f| matches!(f, Function::Sin | Function::Cos | Function::Tan | Function::Asin | Function::Acos | Function::Atan | Function::Atan2)) {
+                writeln!(source, "#define SF_PI 3.14159265358979323846").unwrap();
+            }
+            for helper in helpers {
+                writeln!(source, "{helper}").unwrap();
+            }
+        }
+
+        for (state_machine_name, state_machine) in self.state_machines.iter() {
+            let machine = rename(state_machine_name)?;
+            let state_ids = state_machine.states.keys().map(|state_name| Ok((state_name.as_str(), rename_state(&format_compact!("{state_machine_name} {state_name}"))?))).collect::<Result<BTreeMap<_,_>, CompileError>>()?;
+
+            header.push('\n');
+            writeln!(header, "typedef enum {{").unwrap();
+            for state_id in state_ids.values() {
+                writeln!(header, "    {state_id},").unwrap();
+            }
+            writeln!(header, "}} {machine}_state_t;").unwrap();
+            header.push('\n');
+            writeln!(header, "typedef struct {{").unwrap();
+            writeln!(header, "    {machine}_state_t sf_state;").unwrap();
+            writeln!(header, "    double sf_state_time;").unwrap();
+            writeln!(header, "    double sf_timer;").unwrap();
+            for (var, info) in state_machine.variables.iter() {
+                writeln!(header, "    double {}; /* {:?} */", Expr::Variable(var.clone()).display(Dialect::new(Language::C)), info.kind).unwrap();
+            }
+            writeln!(header, "}} {machine}_t;").unwrap();
+            header.push('\n');
+            writeln!(header, "void {machine}_init({machine}_t *s);").unwrap();
+            writeln!(header, "void {machine}_step({machine}_t *s, double dt);").unwrap();
+
+            let initial_state = state_machine.initial_state.as_deref().or(state_machine.current_state.as_deref()).or_else(|| state_machine.states.iter().find(|s| s.1.parent.is_none()).map(|s| s.0.as_str()));
+
+            source.push('\n');
+            writeln!(source, "void {machine}_init({machine}_t *s) {{").unwrap();
+            writeln!(source, "    *s = ({machine}_t){{0}};").unwrap();
+            if let Some(initial_state) = initial_state {
+                writeln!(source, "    s->sf_state = {};", state_ids[initial_state]).unwrap();
+            }
+            for (var, info) in state_machine.variables.iter() {
+                writeln!(source, "    {};", Stmt::Assign { var: var.clone(), value: info.init.clone() }.display(C_DIALECT)).unwrap();
+            }
+            writeln!(source, "}}").unwrap();
+
+            source.push('\n');
+            writeln!(source, "void {machine}_step({machine}_t *s, double dt) {{").unwrap();
+            writeln!(source, "    s->sf_timer += dt;").unwrap();
+            writeln!(source, "    s->sf_state_time += dt;").unwrap();
+            writeln!(source, "    for (int junctions = 0; junctions <= {}; ++junctions) {{", state_machine.states.len()).unwrap();
+            writeln!(source, "        switch (s->sf_state) {{").unwrap();
+            for (state_name, state) in state_machine.states.iter() {
+                writeln!(source, "            case {}:", state_ids[state_name.as_str()]).unwrap();
+                let mut total = false;
+                for transition in state.transitions.iter() {
+                    total = transition.ordered_condition == Condition::constant(true);
+                    let indent = match total {
+                        true => "                ",
+                        false => {
+                            writeln!(source, "                if ({}) {{", transition.ordered_condition.display(C_DIALECT)).unwrap();
+                            "                    "
+                        }
+                    };
+                    write_c_actions(&mut source, indent, &transition.actions);
+                    match transition.new_state.as_deref() {
+                        Some(new_state) => {
+                            writeln!(source, "{indent}s->sf_state = {};", state_ids[new_state]).unwrap();
+                            writeln!(source, "{indent}s->sf_state_time = 0.0;").unwrap();
+                            match state_machine.states[new_state].parent {
+                                Some(_) => writeln!(source, "{indent}continue;").unwrap(),
+                                None => writeln!(source, "{indent}return;").unwrap(),
+                            }
+                        }
+                        None => writeln!(source, "{indent}return;").unwrap(),
+                    }
+                    if total {
+                        break;
+                    }
+                    writeln!(source, "                }}").unwrap();
+                }
+                if !total {
+                    writeln!(source, "                return;").unwrap();
+                }
+            }
+            writeln!(source, "        }}").unwrap();
+            writeln!(source, "        return;").unwrap();
+            writeln!(source, "    }}").unwrap();
+            writeln!(source, "}}").unwrap();
+        }
+
+        header.push('\n');
+        writeln!(header, "#endif").unwrap();
+
+        Ok(CCode { header_name, header, source })
+    }
+}
@@ -0,0 +1,59 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use netsblox_ast::compact_str::CompactString;
+
+use crate::*;
+use crate::bdd::{Builder, Op};
+
+pub type Assignment = BTreeMap<Expr, bool>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Finding {
+    NonTotal { state: CompactString, counterexample: Assignment },
+    Overlap { state: CompactString, transitions: (usize, usize), counterexample: Assignment },
+    Mismatch { state: CompactString, transition: usize, counterexample: Assignment },
+}
+
+impl StateMachine {
+    pub fn check_completeness(&self) -> Vec<Finding> {
+        let mut res = vec![];
+        for (state_name, state) in self.states.iter() {
+            let atoms = state.transitions.iter().flat_map(|t| t.ordered_condition.atoms().into_iter().chain(t.unordered_condition.atoms())).collect();
+            let mut builder = Builder::new(&atoms);
+            let domain = builder.domain();
+            let ordered = state.transitions.iter().map(|t| builder.build(t.ordered_condition.raw())).collect::<Vec<_>>();
+            let unordered = state.transitions.iter().map(|t| builder.build(t.unordered_condition.raw())).collect::<Vec<_>>();
+
+            let any_ordered = ordered.iter().fold(builder.build(&RawCondition::Const(false)), |a, &b| builder.apply(Op::Or, a, b));
+            let none_ordered = builder.not(any_ordered);
+            let none_ordered = builder.apply(Op::And, none_ordered, domain);
+            if let Some(counterexample) = builder.finish(none_ordered).satisfying_assignment() {
+                res.push(Finding::NonTotal { state: state_name.clone(), counterexample });
+            }
+
+            for i in 0..unordered.len() {
+                for j in i + 1..unordered.len() {
+                    let both = builder.apply(Op::And, unordered[i], unordered[j]);
+                    let both = builder.apply(Op::And, both, domain);
+                    if let Some(counterexample) = builder.finish(both).satisfying_assignment() {
+                        res.push(Finding::Overlap { state: state_name.clone(), transitions: (i, j), counterexample });
+                    }
+                }
+            }
+
+            let mut untaken = domain;
+            for i in 0..ordered.len() {
+                let chosen = builder.apply(Op::And, untaken, ordered[i]);
+                let inactive = builder.not(unordered[i]);
+                let mismatch = builder.apply(Op::And, chosen, inactive);
+                if let Some(counterexample) = builder.finish(mismatch).satisfying_assignment() {
+                    res.push(Finding::Mismatch { state: state_name.clone(), transition: i, counterexample });
+                }
+                let not_taken = builder.not(ordered[i]);
+                untaken = builder.apply(Op::And, untaken, not_taken);
+            }
+        }
+        res
+    }
+}
@@ -1,4 +1,5 @@
 use core::ops::{BitAnd, BitOr, Not};
+use core::str::FromStr;
 use core::fmt;
 
 use alloc::vec::Vec;
@@ -6,12 +7,13 @@ use alloc::boxed::Box;
 use alloc::string::ToString;
 use alloc::collections::BTreeSet;
 
-use netsblox_ast::CompactString;
+use crate::{Expr, BinaryOp, Dialect, Language, ParseError};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RawCondition {
     Const(bool),
-    Atom(CompactString),
+    Atom(Expr),
     Not(Box<RawCondition>),
     And(Box<RawCondition>, Box<RawCondition>),
     Or(Box<RawCondition>, Box<RawCondition>),
@@ -56,6 +58,127 @@ impl fmt::Display for RawCondition {
     }
 }
 
+struct DisplayCondition<'a> {
+    cond: RawCondition,
+    dialect: Dialect<'a>,
+}
+impl fmt::Display for DisplayCondition<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.dialect.language {
+            Language::Matlab => write!(f, "{}", self.cond),
+            Language::Rust => match self.cond.to_expr() {
+                x if x.is_bool() => write!(f, "{}", x.display(self.dialect)),
+                x => write!(f, "{}", Expr::Binary { op: BinaryOp::Neq, left: Box::new(x), right: Box::new(Expr::Number("0".into())) }.display(self.dialect)),
+            }
+            _ => write!(f, "{}", self.cond.to_expr().display(self.dialect)),
+        }
+    }
+}
+
+impl RawCondition {
+    pub fn to_expr(&self) -> Expr {
+        match self {
+            RawCondition::Const(x) => Expr::Bool(*x),
+            RawCondition::Atom(x) => x.clone(),
+            RawCondition::Not(x) => Expr::Not { value: Box::new(x.to_expr()) },
+            RawCondition::And(a, b) => Expr::Binary { op: BinaryOp::And, left: Box::new(a.to_expr()), right: Box::new(b.to_expr()) },
+            RawCondition::Or(a, b) => Expr::Binary { op: BinaryOp::Or, left: Box::new(a.to_expr()), right: Box::new(b.to_expr()) },
+        }
+    }
+    pub fn display<'a>(&'a self, dialect: Dialect<'a>) -> impl fmt::Display + 'a {
+        DisplayCondition { cond: self.clone(), dialect }
+    }
+    pub fn atoms(&self) -> BTreeSet<&Expr> {
+        fn visit<'a>(cond: &'a RawCondition, res: &mut BTreeSet<&'a Expr>) {
+            match cond {
+                RawCondition::Const(_) => (),
+                RawCondition::Atom(x) => { res.insert(x); }
+                RawCondition::Not(x) => visit(x, res),
+                RawCondition::And(a, b) | RawCondition::Or(a, b) => { visit(a, res); visit(b, res); }
+            }
+        }
+        let mut res = BTreeSet::new();
+        visit(self, &mut res);
+        res
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison<'a> {
+    pub var: &'a Expr,
+    pub op: BinaryOp,
+    pub value: f64,
+}
+impl<'a> Comparison<'a> {
+    pub fn from_expr(expr: &'a Expr) -> Option<Self> {
+        let Expr::Binary { op, left, right } = expr else { return None };
+        negate_op(*op)?;
+        match (&**left, &**right) {
+            (var @ (Expr::Variable(_) | Expr::Timer), Expr::Number(value)) => Some(Comparison { var, op: *op, value: value.parse().ok()? }),
+            (Expr::Number(value), var @ (Expr::Variable(_) | Expr::Timer)) => Some(Comparison { var, op: swap_op(*op), value: value.parse().ok()? }),
+            _ => None,
+        }
+    }
+    pub fn negated(self) -> Self {
+        Comparison { op: negate_op(self.op).unwrap(), ..self }
+    }
+    pub fn holds(&self, x: f64) -> bool {
+        match self.op {
+            BinaryOp::Eq => x == self.value,
+            BinaryOp::Neq => x != self.value,
+            BinaryOp::Less => x < self.value,
+            BinaryOp::LessEq => x <= self.value,
+            BinaryOp::Greater => x > self.value,
+            BinaryOp::GreaterEq => x >= self.value,
+            _ => unreachable!(),
+        }
+    }
+}
+fn negate_op(op: BinaryOp) -> Option<BinaryOp> {
+    Some(match op {
+        BinaryOp::Eq => BinaryOp::Neq,
+        BinaryOp::Neq => BinaryOp::Eq,
+        BinaryOp::Less => BinaryOp::GreaterEq,
+        BinaryOp::LessEq => BinaryOp::Greater,
+        BinaryOp::Greater => BinaryOp::LessEq,
+        BinaryOp::GreaterEq => BinaryOp::Less,
+        _ => return None,
+    })
+}
+fn swap_op(op: BinaryOp) -> BinaryOp {
+    match op {
+        BinaryOp::Less => BinaryOp::Greater,
+        BinaryOp::LessEq => BinaryOp::GreaterEq,
+        BinaryOp::Greater => BinaryOp::Less,
+        BinaryOp::GreaterEq => BinaryOp::LessEq,
+        x => x,
+    }
+}
+
+impl RawCondition {
+    pub fn comparison(&self) -> Option<Comparison<'_>> {
+        match self {
+            RawCondition::Atom(x) => Comparison::from_expr(x),
+            RawCondition::Not(x) => match &**x {
+                RawCondition::Atom(x) => Comparison::from_expr(x).map(Comparison::negated),
+                _ => None,
+            }
+            _ => None,
+        }
+    }
+    fn rendered(&self) -> RawCondition {
+        match self {
+            RawCondition::Not(x) => match &**x {
+                RawCondition::Atom(expr @ Expr::Binary { op, left, right }) if Comparison::from_expr(expr).is_some() => RawCondition::Atom(Expr::Binary { op: negate_op(*op).unwrap(), left: left.clone(), right: right.clone() }),
+                x => !x.rendered(),
+            }
+            RawCondition::And(a, b) => a.rendered() & b.rendered(),
+            RawCondition::Or(a, b) => a.rendered() | b.rendered(),
+            x => x.clone(),
+        }
+    }
+}
+
 impl BitAnd for RawCondition {
     type Output = Self;
     fn bitand(self, rhs: Self) -> Self::Output {
@@ -75,14 +198,32 @@ impl Not for RawCondition {
     }
 }
 
+impl From<Expr> for RawCondition {
+    fn from(value: Expr) -> Self {
+        match value {
+            Expr::Bool(x) => RawCondition::Const(x),
+            Expr::Not { value } => !RawCondition::from(*value),
+            Expr::Binary { op: BinaryOp::And, left, right } => RawCondition::from(*left) & RawCondition::from(*right),
+            Expr::Binary { op: BinaryOp::Or, left, right } => RawCondition::from(*left) | RawCondition::from(*right),
+            x => RawCondition::Atom(x),
+        }
+    }
+}
+impl FromStr for RawCondition {
+    type Err = ParseError;
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        src.parse::<Expr>().map(RawCondition::from)
+    }
+}
+
 #[test]
 fn test_condition() {
-    let a = RawCondition::Atom("a".into());
-    let b = RawCondition::Atom("b".into());
-    let c = RawCondition::Atom("c".into());
-    let d = RawCondition::Atom("d".into());
-    let e = RawCondition::Atom("x < 10".into());
-    let f = RawCondition::Atom("y == x + 10".into());
+    let a = RawCondition::Atom("a".parse().unwrap());
+    let b = RawCondition::Atom("b".parse().unwrap());
+    let c = RawCondition::Atom("c".parse().unwrap());
+    let d = RawCondition::Atom("d".parse().unwrap());
+    let e = RawCondition::Atom("x < 10".parse().unwrap());
+    let f = RawCondition::Atom("y == (x + 10)".parse().unwrap());
 
     let bt = RawCondition::Const(true);
     let bf = RawCondition::Const(false);
@@ -92,7 +233,7 @@ fn test_condition() {
     assert_eq!(c.to_string(), "c");
     assert_eq!(d.to_string(), "d");
     assert_eq!(e.to_string(), "x < 10");
-    assert_eq!(f.to_string(), "y == x + 10");
+    assert_eq!(f.to_string(), "y == (x + 10)");
     assert_eq!(bt.to_string(), "true");
     assert_eq!(bf.to_string(), "false");
 
@@ -122,12 +263,12 @@ fn test_condition() {
     assert_eq!((!a.clone()).to_string(), "~a");
     assert_eq!((!c.clone()).to_string(), "~c");
     assert_eq!((!e.clone()).to_string(), "~(x < 10)");
-    assert_eq!((!f.clone()).to_string(), "~(y == x + 10)");
+    assert_eq!((!f.clone()).to_string(), "~(y == (x + 10))");
 
     assert_eq!((!!a.clone()).to_string(), "~(~a)");
     assert_eq!((!!c.clone()).to_string(), "~(~c)");
     assert_eq!((!!e.clone()).to_string(), "~(~(x < 10))");
-    assert_eq!((!!f.clone()).to_string(), "~(~(y == x + 10))");
+    assert_eq!((!!f.clone()).to_string(), "~(~(y == (x + 10)))");
 
     assert_eq!((bt.clone() & a.clone()).to_string(), "true & a");
     assert_eq!((a.clone() & bt.clone()).to_string(), "a & true");
@@ -154,6 +295,110 @@ fn test_condition() {
     assert_eq!((!!!!bf.clone()).to_string(), "~(~(~(~false)))");
 }
 
+// every comparison on a variable is constant between (and at) the constants it is compared against,
+// so one sample from each of those pieces of the number line is enough to reason about all values
+pub(crate) fn sample_points(constants: &[f64]) -> Vec<f64> {
+    let mut res = vec![constants[0] - 1.0];
+    for (i, &c) in constants.iter().enumerate() {
+        res.push(c);
+        res.push(match constants.get(i + 1) {
+            Some(&next) => (c + next) / 2.0,
+            None => c + 1.0,
+        });
+    }
+    res
+}
+
+fn interval_simpl(terms: &mut BTreeSet<RawCondition>, conjunction: bool) -> bool {
+    let members = |term: &RawCondition| -> Option<Vec<RawCondition>> {
+        let RawCondition::Not(group) = term else { return None };
+        let mut res = vec![];
+        match (&**group, conjunction) {
+            (RawCondition::And(_, _), true) => group.visit_and(&mut |x| res.push(x.clone())),
+            (RawCondition::Or(_, _), false) => group.visit_or(&mut |x| res.push(x.clone())),
+            _ => return None,
+        }
+        Some(res)
+    };
+
+    let mut vars: BTreeSet<Expr> = BTreeSet::new();
+    for term in terms.iter() {
+        match term.comparison() {
+            Some(x) => { vars.insert(x.var.clone()); }
+            None => for member in members(term).into_iter().flatten() {
+                if let Some(x) = member.comparison() { vars.insert(x.var.clone()); }
+            }
+        }
+    }
+
+    let mut changed = false;
+    for var in vars.iter() {
+        let mut constants = vec![];
+        for term in terms.iter() {
+            for cond in core::iter::once(term.clone()).chain(members(term).into_iter().flatten()) {
+                if let Some(x) = cond.comparison().filter(|x| x.var == var) { constants.push(x.value); }
+            }
+        }
+        constants.sort_by(f64::total_cmp);
+        constants.dedup();
+        let points = sample_points(&constants);
+        let set = |cond: &RawCondition| -> Option<Vec<bool>> {
+            let cmp = cond.comparison().filter(|x| x.var == var)?;
+            Some(points.iter().map(|&x| cmp.holds(x) == conjunction).collect())
+        };
+        let intersect = |sets: &mut dyn Iterator<Item = Vec<bool>>| sets.fold(vec![true; points.len()], |a, b| a.iter().zip(b).map(|(x, y)| *x && y).collect::<Vec<_>>());
+        let subset = |a: &[bool], b: &[bool]| a.iter().zip(b).all(|(x, y)| !x || *y);
+        let disjoint = |a: &[bool], b: &[bool]| a.iter().zip(b).all(|(x, y)| !(*x && *y));
+
+        let mut direct = terms.iter().filter_map(|t| set(t).map(|s| (t.clone(), s))).collect::<Vec<_>>();
+        let combined = intersect(&mut direct.iter().map(|x| x.1.clone()));
+        if combined.iter().all(|x| !x) {
+            terms.clear();
+            terms.insert(RawCondition::Const(!conjunction));
+            return true;
+        }
+        // drop the weakest redundant term first so that e.g. x >= 4 & x <= 4 & x == 4 keeps x == 4
+        loop {
+            let redundant = (0..direct.len()).filter(|&i| {
+                let others = intersect(&mut direct.iter().enumerate().filter(|x| x.0 != i).map(|x| x.1.1.clone()));
+                subset(&others, &direct[i].1)
+            }).max_by_key(|&i| (direct[i].1.iter().filter(|x| **x).count(), core::cmp::Reverse(i)));
+            match redundant {
+                Some(i) => {
+                    assert!(terms.remove(&direct.remove(i).0));
+                    changed = true;
+                }
+                None => break,
+            }
+        }
+
+        for term in terms.clone() {
+            let Some(group) = members(&term) else { continue };
+            let mut kept = vec![];
+            let mut dropped = false;
+            for member in group.iter() {
+                match set(member) {
+                    Some(x) if disjoint(&x, &combined) => dropped = true,
+                    Some(x) if subset(&combined, &x) => (),
+                    _ => kept.push(member.clone()),
+                }
+            }
+            if dropped || kept.len() != group.len() {
+                assert!(terms.remove(&term));
+                changed = true;
+                if !dropped {
+                    let rebuilt = match conjunction {
+                        true => kept.into_iter().reduce(|a, b| a & b).unwrap_or(RawCondition::Const(true)),
+                        false => kept.into_iter().reduce(|a, b| a | b).unwrap_or(RawCondition::Const(false)),
+                    };
+                    terms.insert((!rebuilt).simpl());
+                }
+            }
+        }
+    }
+    changed
+}
+
 impl RawCondition {
     fn visit_and<F: FnMut(&RawCondition)>(&self, f: &mut F) {
         match self {
@@ -219,6 +464,12 @@ impl RawCondition {
                 // !a & !( a & b) ---> !a
                 homo_simpl!(terms: And, visit_and, true);
 
+                //  x < 10 & x > 20 ---> false
+                //  x < 10 & x < 20 ---> x < 10
+                if interval_simpl(&mut terms, true) {
+                    return terms.into_iter().reduce(|a, b| a & b).unwrap_or(RawCondition::Const(true)).simpl();
+                }
+
                 terms.remove(&RawCondition::Const(true));
                 if terms.contains(&RawCondition::Const(false)) || terms.iter().any(|t| if let RawCondition::Not(t) = t { terms.contains(t) } else { false }) {
                     return RawCondition::Const(false);
@@ -239,6 +490,12 @@ impl RawCondition {
                 // !a | !( a | b) ---> !a
                 homo_simpl!(terms: Or, visit_or, false);
 
+                //  x < 10 | x >= 5 ---> true
+                //  x < 10 | x < 20 ---> x < 20
+                if interval_simpl(&mut terms, false) {
+                    return terms.into_iter().reduce(|a, b| a | b).unwrap_or(RawCondition::Const(false)).simpl();
+                }
+
                 terms.remove(&RawCondition::Const(false));
                 if terms.contains(&RawCondition::Const(true)) || terms.iter().any(|t| if let RawCondition::Not(t) = t { terms.contains(t) } else { false }) {
                     return RawCondition::Const(true);
@@ -258,12 +515,12 @@ impl RawCondition {
 
 #[test]
 fn test_simpl() {
-    let a = RawCondition::Atom("a".into());
-    let b = RawCondition::Atom("b".into());
-    let c = RawCondition::Atom("c".into());
-    let d = RawCondition::Atom("d".into());
-    let e = RawCondition::Atom("x < 10".into());
-    let f = RawCondition::Atom("y == x + 10".into());
+    let a = RawCondition::Atom("a".parse().unwrap());
+    let b = RawCondition::Atom("b".parse().unwrap());
+    let c = RawCondition::Atom("c".parse().unwrap());
+    let d = RawCondition::Atom("d".parse().unwrap());
+    let e = RawCondition::Atom("x < 10".parse().unwrap());
+    let f = RawCondition::Atom("y == (x + 10)".parse().unwrap());
 
     let bt = RawCondition::Const(true);
     let bf = RawCondition::Const(false);
@@ -280,10 +537,10 @@ fn test_simpl() {
     assert_eq!((a.clone() | ((c.clone() & c.clone()) | a.clone())).simpl().to_string(), "a | c");
     assert_eq!((a.clone() | ((c.clone() & c.clone() & c.clone()) | a.clone())).simpl().to_string(), "a | c");
 
-    assert_eq!((!f.clone()).simpl().to_string(), "~(y == x + 10)");
-    assert_eq!((!!f.clone()).simpl().to_string(), "y == x + 10");
-    assert_eq!((!!!f.clone()).simpl().to_string(), "~(y == x + 10)");
-    assert_eq!((!!!!f.clone()).simpl().to_string(), "y == x + 10");
+    assert_eq!((!f.clone()).simpl().to_string(), "~(y == (x + 10))");
+    assert_eq!((!!f.clone()).simpl().to_string(), "y == (x + 10)");
+    assert_eq!((!!!f.clone()).simpl().to_string(), "~(y == (x + 10))");
+    assert_eq!((!!!!f.clone()).simpl().to_string(), "y == (x + 10)");
 
     assert_eq!((!(a.clone() | a.clone())).simpl().to_string(), "~a");
     assert_eq!((!(a.clone() & a.clone())).simpl().to_string(), "~a");
@@ -357,22 +614,50 @@ fn test_simpl() {
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(from = "RawCondition", into = "RawCondition"))]
 pub struct Condition(RawCondition);
 impl Condition {
     pub fn raw(&self) -> &RawCondition {
         &self.0
     }
-    pub fn atom(v: CompactString) -> Self {
-        debug_assert!(v != "true" && v != "false" && !v.is_empty());
+    pub fn atom(v: Expr) -> Self {
+        debug_assert!(!matches!(v, Expr::Bool(_)));
         Condition(RawCondition::Atom(v))
     }
     pub fn constant(v: bool) -> Self {
         Condition(RawCondition::Const(v))
     }
 }
+impl Condition {
+    pub fn to_expr(&self) -> Expr {
+        self.0.rendered().to_expr()
+    }
+    pub fn display<'a>(&'a self, dialect: Dialect<'a>) -> impl fmt::Display + 'a {
+        DisplayCondition { cond: self.0.rendered(), dialect }
+    }
+    pub fn atoms(&self) -> BTreeSet<&Expr> {
+        self.0.atoms()
+    }
+}
+impl FromStr for Condition {
+    type Err = ParseError;
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        src.parse::<RawCondition>().map(Condition::from)
+    }
+}
+impl From<RawCondition> for Condition {
+    fn from(value: RawCondition) -> Self {
+        Condition(value.simpl())
+    }
+}
+impl From<Condition> for RawCondition {
+    fn from(value: Condition) -> Self {
+        value.0
+    }
+}
 impl fmt::Display for Condition {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.0.rendered())
     }
 }
 impl fmt::Debug for Condition {
@@ -404,3 +689,82 @@ impl Not for Condition {
         Condition(res)
     }
 }
+
+#[test]
+fn test_display_dialect() {
+    let a = RawCondition::Atom("a == 1".parse().unwrap());
+    let b = RawCondition::Atom("after(2, sec)".parse().unwrap());
+    let c = RawCondition::Atom("(x | y)".parse().unwrap());
+    let cond = (a & !b) | c | RawCondition::Const(false);
+
+    assert_eq!(cond.display(Dialect::MATLAB).to_string(), "(a == 1 & ~(after(2, sec))) | (x | y) | false");
+    assert_eq!(cond.display(Dialect::new(Language::C)).to_string(), "a == 1.0 && !(state_time >= 2.0) || (x || y) || false");
+    assert_eq!(cond.display(Dialect::new(Language::Python)).to_string(), "a == 1 and not state_time >= 2 or (x or y) or False");
+    assert_eq!(cond.display(Dialect::new(Language::Rust)).to_string(), "a == 1.0 && !(state_time >= 2.0) || (x != 0.0 || y != 0.0) || false");
+    assert_eq!(RawCondition::Atom("x".parse().unwrap()).display(Dialect::new(Language::Rust)).to_string(), "x != 0.0");
+    assert_eq!(cond.to_expr().to_string(), "((a == 1 & ~(after(2, sec)) | (x | y)) | false)");
+}
+
+#[test]
+fn test_parse_condition() {
+    let a = RawCondition::Atom("a".parse().unwrap());
+    let b = RawCondition::Atom("b".parse().unwrap());
+    let c = RawCondition::Atom("c".parse().unwrap());
+    let e = RawCondition::Atom("x < 10".parse().unwrap());
+    let f = RawCondition::Atom("y == (x + 10)".parse().unwrap());
+
+    assert_eq!("a".parse::<RawCondition>().unwrap(), a);
+    assert_eq!("true".parse::<RawCondition>().unwrap(), RawCondition::Const(true));
+    assert_eq!("a & b | c".parse::<RawCondition>().unwrap(), (a.clone() & b.clone()) | c.clone());
+    assert_eq!("a | b & c".parse::<RawCondition>().unwrap(), a.clone() | (b.clone() & c.clone()));
+    assert_eq!("(a | b) & c".parse::<RawCondition>().unwrap(), (a.clone() | b.clone()) & c.clone());
+    assert_eq!("~a & b".parse::<RawCondition>().unwrap(), !a.clone() & b.clone());
+    assert_eq!("~(a & b)".parse::<RawCondition>().unwrap(), !(a.clone() & b.clone()));
+    assert_eq!("x < 10 & a".parse::<RawCondition>().unwrap(), e.clone() & a.clone());
+    assert_eq!("~(x < 10) | y == (x + 10)".parse::<RawCondition>().unwrap(), !e.clone() | f.clone());
+    assert_eq!("after(2, sec) & ~false".parse::<RawCondition>().unwrap(), RawCondition::Atom("after(2, sec)".parse().unwrap()) & !RawCondition::Const(false));
+    assert!("a & ".parse::<RawCondition>().is_err());
+    assert!("a $ b".parse::<RawCondition>().is_err());
+
+    for cond in [
+        (a.clone() & b.clone()) | c.clone(),
+        c.clone() & (a.clone() | b.clone()),
+        !(e.clone() & !f.clone()) | !!a.clone(),
+        (a.clone() & e.clone()) | !(b.clone() | RawCondition::Const(true)),
+    ] {
+        assert_eq!(cond.to_string().parse::<RawCondition>().unwrap(), cond);
+    }
+
+    let cond = "(a & ~b) | (~a & b) | false".parse::<Condition>().unwrap();
+    assert_eq!(cond, Condition::from((a.clone() & !b.clone()) | (!a.clone() & b.clone())));
+    assert_eq!(cond.to_string().parse::<Condition>().unwrap(), cond);
+}
+
+#[test]
+fn test_comparison_condition() {
+    let cond = |x: &str| x.parse::<Condition>().unwrap();
+
+    assert_eq!(Comparison::from_expr(&"x < 10".parse().unwrap()).map(|x| (x.op, x.value)), Some((BinaryOp::Less, 10.0)));
+    assert_eq!(Comparison::from_expr(&"10 < x".parse().unwrap()).map(|x| (x.op, x.value)), Some((BinaryOp::Greater, 10.0)));
+    assert_eq!(Comparison::from_expr(&"x < y".parse().unwrap()), None);
+
+    assert_eq!(cond("x < 10 & x > 20"), Condition::constant(false));
+    assert_eq!(cond("x < 10 & x < 20").to_string(), "x < 10");
+    assert_eq!(cond("x < 10 | x < 20").to_string(), "x < 20");
+    assert_eq!(cond("x < 10 | x >= 5"), Condition::constant(true));
+    assert_eq!(cond("x == 3 & x > 2 & a").to_string(), "a & x == 3");
+    assert_eq!(cond("~(x < 4) & ~(x > 4)").to_string(), "x >= 4 & x <= 4");
+    assert_eq!(cond("~(x < 4) & ~(x > 4) & x == 4").to_string(), "x == 4");
+    assert_eq!(cond("x > 10 & ~(x > 5 & a)").to_string(), "x > 10 & ~a");
+    assert_eq!(cond("x > 10 & ~(x < 5 & a)").to_string(), "x > 10");
+    assert_eq!(cond("x < 10 & y > 20 & ~(x < 10)"), Condition::constant(false));
+
+    assert_eq!(cond("~(x < 10)").to_string(), "x >= 10");
+    assert_eq!(cond("~(x == 10) & ~(t > 2)").to_string(), "x ~= 10 & t <= 2");
+    assert_eq!(cond("~(x < 10)").raw().to_string(), "~(x < 10)");
+
+    assert!(cond("x > 10").implies(&cond("x > 5")));
+    assert!(!cond("x > 5").implies(&cond("x > 10")));
+    assert!(cond("x > 5 | x <= 5").equivalent(&Condition::constant(true)));
+    assert!(!(cond("x > 5") & cond("a") & cond("x < 2")).is_satisfiable());
+}
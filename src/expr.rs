@@ -0,0 +1,992 @@
+use core::iter::Peekable;
+use core::str::{CharIndices, FromStr};
+use core::fmt;
+
+use alloc::vec::Vec;
+use alloc::boxed::Box;
+#[cfg(test)]
+use alloc::string::ToString;
+use alloc::collections::{BTreeMap, BTreeSet};
+
+use netsblox_ast::compact_str::{CompactString, format_compact};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Function {
+    Sin, Cos, Tan, Asin, Acos, Atan, Atan2,
+    Sqrt, Floor, Ceil, Round, Sign, Abs, Log, Mod,
+    Randi, After,
+}
+impl Function {
+    pub fn name(self) -> &'static str {
+        match self {
+            Function::Sin => "sind",
+            Function::Cos => "cosd",
+            Function::Tan => "tand",
+            Function::Asin => "asind",
+            Function::Acos => "acosd",
+            Function::Atan => "atand",
+            Function::Atan2 => "atan2d",
+            Function::Sqrt => "sqrt",
+            Function::Floor => "floor",
+            Function::Ceil => "ceil",
+            Function::Round => "round",
+            Function::Sign => "sign",
+            Function::Abs => "abs",
+            Function::Log => "log",
+            Function::Mod => "mod",
+            Function::Randi => "randi",
+            Function::After => "after",
+        }
+    }
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "sind" => Function::Sin,
+            "cosd" => Function::Cos,
+            "tand" => Function::Tan,
+            "asind" => Function::Asin,
+            "acosd" => Function::Acos,
+            "atand" => Function::Atan,
+            "atan2d" => Function::Atan2,
+            "sqrt" => Function::Sqrt,
+            "floor" => Function::Floor,
+            "ceil" => Function::Ceil,
+            "round" => Function::Round,
+            "sign" => Function::Sign,
+            "abs" => Function::Abs,
+            "log" => Function::Log,
+            "mod" => Function::Mod,
+            "randi" => Function::Randi,
+            "after" => Function::After,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BinaryOp {
+    Sub, Div, Pow,
+    Eq, Neq, Less, LessEq, Greater, GreaterEq,
+    And, Or,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Expr {
+    Number(CompactString),
+    Bool(bool),
+    String(CompactString),
+    Variable(CompactString),
+    Timer,
+    Unknown,
+    Neg { value: Box<Expr> },
+    Not { value: Box<Expr> },
+    Add { values: Vec<Expr> },
+    Mul { values: Vec<Expr> },
+    Binary { op: BinaryOp, left: Box<Expr>, right: Box<Expr> },
+    Call { func: Function, args: Vec<Expr> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Stmt {
+    Assign { var: CompactString, value: Expr },
+    AddAssign { var: CompactString, value: Expr },
+    ResetTimer,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    UnexpectedChar { pos: usize, ch: char },
+    UnexpectedToken { pos: usize, token: CompactString },
+    UnexpectedEnd,
+    UnknownFunction { name: CompactString },
+    WrongArgCount { name: CompactString, got: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(CompactString),
+    Ident(CompactString),
+    Punct(&'static str),
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, chars: src.char_indices().peekable() }
+    }
+    fn take_while<F: Fn(char) -> bool>(&mut self, start: usize, f: F) -> usize {
+        let mut end = start;
+        while let Some(&(i, ch)) = self.chars.peek() {
+            if !f(ch) { break }
+            end = i + ch.len_utf8();
+            self.chars.next();
+        }
+        end
+    }
+    fn next_token(&mut self) -> Result<Option<(usize, Token)>, ParseError> {
+        while let Some((_, ' ' | '\t' | '\n' | '\r')) = self.chars.peek() {
+            self.chars.next();
+        }
+        let (pos, ch) = match self.chars.next() {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        let token = match ch {
+            '0'..='9' | '.' => {
+                let mut end = self.take_while(pos, |c| c.is_ascii_digit() || c == '.');
+                if let Some((_, 'e' | 'E')) = self.chars.peek() {
+                    let mut lookahead = self.chars.clone();
+                    lookahead.next();
+                    if let Some((_, '+' | '-')) = lookahead.peek() { lookahead.next(); }
+                    if let Some((_, '0'..='9')) = lookahead.peek() {
+                        self.chars = lookahead;
+                        end = self.take_while(pos, |c| c.is_ascii_digit());
+                    }
+                }
+                let text = &self.src[pos..end.max(pos + 1)];
+                if text.parse::<f64>().is_err() {
+                    return Err(ParseError::UnexpectedChar { pos, ch });
+                }
+                Token::Number(text.into())
+            }
+            'a'..='z' | 'A'..='Z' | '_' => {
+                let end = self.take_while(pos + 1, |c| c.is_ascii_alphanumeric() || c == '_');
+                Token::Ident(self.src[pos..end].into())
+            }
+            '=' | '~' | '<' | '>' => match (ch, self.chars.peek().map(|x| x.1)) {
+                ('=', Some('=')) => { self.chars.next(); Token::Punct("==") }
+                ('~', Some('=')) => { self.chars.next(); Token::Punct("~=") }
+                ('<', Some('=')) => { self.chars.next(); Token::Punct("<=") }
+                ('>', Some('=')) => { self.chars.next(); Token::Punct(">=") }
+                ('=', _) => Token::Punct("="),
+                ('~', _) => Token::Punct("~"),
+                ('<', _) => Token::Punct("<"),
+                _ => Token::Punct(">"),
+            }
+            '+' => Token::Punct("+"),
+            '-' => Token::Punct("-"),
+            '*' => Token::Punct("*"),
+            '/' => Token::Punct("/"),
+            '^' => Token::Punct("^"),
+            '(' => Token::Punct("("),
+            ')' => Token::Punct(")"),
+            '[' => Token::Punct("["),
+            ']' => Token::Punct("]"),
+            ',' => Token::Punct(","),
+            '&' => Token::Punct("&"),
+            '|' => Token::Punct("|"),
+            '?' => Token::Punct("?"),
+            _ => return Err(ParseError::UnexpectedChar { pos, ch }),
+        };
+        Ok(Some((pos, token)))
+    }
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+}
+impl Parser {
+    fn new(src: &str) -> Result<Self, ParseError> {
+        let mut lexer = Lexer::new(src);
+        let mut tokens = vec![];
+        while let Some(token) = lexer.next_token()? {
+            tokens.push(token);
+        }
+        Ok(Self { tokens, pos: 0 })
+    }
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|x| &x.1)
+    }
+    fn peek_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punct(x)) if *x == punct)
+    }
+    fn next(&mut self) -> Result<Token, ParseError> {
+        let res = self.tokens.get(self.pos).map(|x| x.1.clone()).ok_or(ParseError::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(res)
+    }
+    fn unexpected(&self) -> ParseError {
+        match self.tokens.get(self.pos) {
+            Some((pos, token)) => ParseError::UnexpectedToken { pos: *pos, token: match token {
+                Token::Number(x) | Token::Ident(x) => x.clone(),
+                Token::Punct(x) => (*x).into(),
+            } },
+            None => ParseError::UnexpectedEnd,
+        }
+    }
+    fn expect_punct(&mut self, punct: &str) -> Result<(), ParseError> {
+        match self.peek_punct(punct) {
+            true => { self.pos += 1; Ok(()) }
+            false => Err(self.unexpected()),
+        }
+    }
+    fn finish(&self) -> Result<(), ParseError> {
+        match self.pos < self.tokens.len() {
+            true => Err(self.unexpected()),
+            false => Ok(()),
+        }
+    }
+
+    fn parse_binary(&mut self, level: usize) -> Result<Expr, ParseError> {
+        const LEVELS: &[&[(&str, Option<BinaryOp>)]] = &[
+            &[("|", Some(BinaryOp::Or))],
+            &[("&", Some(BinaryOp::And))],
+            &[("==", Some(BinaryOp::Eq)), ("~=", Some(BinaryOp::Neq)), ("<", Some(BinaryOp::Less)), ("<=", Some(BinaryOp::LessEq)), (">", Some(BinaryOp::Greater)), (">=", Some(BinaryOp::GreaterEq))],
+            &[("+", None), ("-", Some(BinaryOp::Sub))],
+            &[("*", None), ("/", Some(BinaryOp::Div))],
+        ];
+        if level >= LEVELS.len() {
+            return self.parse_unary();
+        }
+
+        let mut res = self.parse_binary(level + 1)?;
+        let mut variadic = false;
+        while let Some(&(punct, op)) = LEVELS[level].iter().find(|x| self.peek_punct(x.0)) {
+            self.pos += 1;
+            let right = self.parse_binary(level + 1)?;
+            res = match (op, punct, res) {
+                (Some(op), _, left) => { variadic = false; Expr::Binary { op, left: Box::new(left), right: Box::new(right) } }
+                (None, "+", Expr::Add { mut values }) if variadic => { values.push(right); Expr::Add { values } }
+                (None, "*", Expr::Mul { mut values }) if variadic => { values.push(right); Expr::Mul { values } }
+                (None, "+", left) => { variadic = true; Expr::Add { values: vec![left, right] } }
+                (None, _, left) => { variadic = true; Expr::Mul { values: vec![left, right] } }
+            };
+        }
+        Ok(res)
+    }
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        if self.peek_punct("-") {
+            self.pos += 1;
+            return Ok(match (self.peek(), self.tokens.get(self.pos + 1).map(|x| &x.1)) {
+                (Some(Token::Number(x)), next) if !matches!(next, Some(Token::Punct("^"))) => {
+                    let res = Expr::Number(format_compact!("-{x}"));
+                    self.pos += 1;
+                    res
+                }
+                _ => Expr::Neg { value: Box::new(self.parse_unary()?) },
+            });
+        }
+        if self.peek_punct("~") {
+            self.pos += 1;
+            return Ok(Expr::Not { value: Box::new(self.parse_unary()?) });
+        }
+        self.parse_power()
+    }
+    fn parse_power(&mut self) -> Result<Expr, ParseError> {
+        let mut res = self.parse_primary()?;
+        while self.peek_punct("^") {
+            self.pos += 1;
+            let right = match self.peek_punct("-") {
+                true => self.parse_unary()?,
+                false => self.parse_primary()?,
+            };
+            res = Expr::Binary { op: BinaryOp::Pow, left: Box::new(res), right: Box::new(right) };
+        }
+        Ok(res)
+    }
+    fn parse_primary(&mut self) -> Result<Expr, ParseError> {
+        match self.next()? {
+            Token::Number(x) => Ok(Expr::Number(x)),
+            Token::Punct("?") => Ok(Expr::Unknown),
+            Token::Punct("(") => {
+                let res = self.parse_binary(0)?;
+                self.expect_punct(")")?;
+                Ok(res)
+            }
+            Token::Ident(x) => match x.as_str() {
+                "true" => Ok(Expr::Bool(true)),
+                "false" => Ok(Expr::Bool(false)),
+                "t" => Ok(Expr::Timer),
+                _ if self.peek_punct("(") => {
+                    self.pos += 1;
+                    let func = Function::from_name(&x).ok_or_else(|| ParseError::UnknownFunction { name: x.clone() })?;
+                    let args = match func {
+                        Function::Randi if self.peek_punct("[") => {
+                            self.pos += 1;
+                            let a = self.parse_binary(0)?;
+                            self.expect_punct(",")?;
+                            let b = self.parse_binary(0)?;
+                            self.expect_punct("]")?;
+                            vec![a, b]
+                        }
+                        Function::After => {
+                            let duration = self.parse_binary(0)?;
+                            self.expect_punct(",")?;
+                            match self.next()? {
+                                Token::Ident(unit) if unit == "sec" => (),
+                                _ => { self.pos -= 1; return Err(self.unexpected()) }
+                            }
+                            vec![duration]
+                        }
+                        _ => {
+                            let mut args = vec![self.parse_binary(0)?];
+                            while self.peek_punct(",") {
+                                self.pos += 1;
+                                args.push(self.parse_binary(0)?);
+                            }
+                            args
+                        }
+                    };
+                    self.expect_punct(")")?;
+
+                    let expected = match func {
+                        Function::Atan2 | Function::Mod => 2,
+                        _ => 1,
+                    };
+                    if args.len() != expected && !(func == Function::Randi && args.len() == 2) {
+                        return Err(ParseError::WrongArgCount { name: x, got: args.len() });
+                    }
+                    Ok(Expr::Call { func, args })
+                }
+                _ => Ok(Expr::Variable(x)),
+            }
+            _ => { self.pos -= 1; Err(self.unexpected()) }
+        }
+    }
+    fn parse_stmt(&mut self) -> Result<Stmt, ParseError> {
+        match self.next()? {
+            Token::Punct("?") => Ok(Stmt::Unknown),
+            Token::Ident(var) => {
+                self.expect_punct("=")?;
+                if var == "t" {
+                    return match self.next()? {
+                        Token::Number(x) if x == "0" => Ok(Stmt::ResetTimer),
+                        _ => { self.pos -= 1; Err(self.unexpected()) }
+                    };
+                }
+                if matches!(self.peek(), Some(Token::Ident(x)) if *x == var) && matches!(self.tokens.get(self.pos + 1), Some((_, Token::Punct("+")))) {
+                    self.pos += 2;
+                    return Ok(Stmt::AddAssign { var, value: self.parse_binary(0)? });
+                }
+                Ok(Stmt::Assign { var, value: self.parse_binary(0)? })
+            }
+            _ => { self.pos -= 1; Err(self.unexpected()) }
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ParseError;
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(src)?;
+        let res = parser.parse_binary(0)?;
+        parser.finish()?;
+        Ok(res)
+    }
+}
+impl FromStr for Stmt {
+    type Err = ParseError;
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(src)?;
+        let res = parser.parse_stmt()?;
+        parser.finish()?;
+        Ok(res)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    UndefinedVariable { name: CompactString },
+    UnknownValue,
+    InvalidNumber { value: CompactString },
+    Unsupported { func: Function },
+    BadArity { func: Function, got: usize },
+}
+
+pub trait Environment {
+    fn get(&self, var: &str) -> Option<f64>;
+    fn set(&mut self, var: &str, value: f64) -> Result<(), EvalError>;
+    fn timer(&self) -> Result<f64, EvalError> {
+        self.get("t").ok_or_else(|| EvalError::UndefinedVariable { name: "t".into() })
+    }
+    fn reset_timer(&mut self) -> Result<(), EvalError> {
+        self.set("t", 0.0)
+    }
+    fn state_time(&self) -> Result<f64, EvalError> {
+        Err(EvalError::Unsupported { func: Function::After })
+    }
+    fn random(&mut self, _a: f64, _b: f64) -> Result<f64, EvalError> {
+        Err(EvalError::Unsupported { func: Function::Randi })
+    }
+}
+impl Environment for BTreeMap<CompactString, f64> {
+    fn get(&self, var: &str) -> Option<f64> {
+        BTreeMap::get(self, var).copied()
+    }
+    fn set(&mut self, var: &str, value: f64) -> Result<(), EvalError> {
+        self.insert(var.into(), value);
+        Ok(())
+    }
+}
+
+fn truthy(x: f64) -> bool {
+    x != 0.0
+}
+fn from_bool(x: bool) -> f64 {
+    if x { 1.0 } else { 0.0 }
+}
+
+impl Expr {
+    pub fn eval<E: Environment>(&self, env: &mut E) -> Result<f64, EvalError> {
+        Ok(match self {
+            Expr::Number(x) => x.parse::<f64>().map_err(|_| EvalError::InvalidNumber { value: x.clone() })?,
+            Expr::Bool(x) => from_bool(*x),
+            Expr::String(x) => x.parse::<f64>().map_err(|_| EvalError::InvalidNumber { value: x.clone() })?,
+            Expr::Variable(x) => env.get(x).ok_or_else(|| EvalError::UndefinedVariable { name: x.clone() })?,
+            Expr::Timer => env.timer()?,
+            Expr::Unknown => return Err(EvalError::UnknownValue),
+            Expr::Neg { value } => -value.eval(env)?,
+            Expr::Not { value } => from_bool(!truthy(value.eval(env)?)),
+            Expr::Add { values } => values.iter().try_fold(0.0, |a, b| Ok(a + b.eval(env)?))?,
+            Expr::Mul { values } => values.iter().try_fold(1.0, |a, b| Ok(a * b.eval(env)?))?,
+            Expr::Binary { op: BinaryOp::And, left, right } => from_bool(truthy(left.eval(env)?) && truthy(right.eval(env)?)),
+            Expr::Binary { op: BinaryOp::Or, left, right } => from_bool(truthy(left.eval(env)?) || truthy(right.eval(env)?)),
+            Expr::Binary { op, left, right } => {
+                let (a, b) = (left.eval(env)?, right.eval(env)?);
+                match op {
+                    BinaryOp::Sub => a - b,
+                    BinaryOp::Div => a / b,
+                    BinaryOp::Pow => libm::pow(a, b),
+                    BinaryOp::Eq => from_bool(a == b),
+                    BinaryOp::Neq => from_bool(a != b),
+                    BinaryOp::Less => from_bool(a < b),
+                    BinaryOp::LessEq => from_bool(a <= b),
+                    BinaryOp::Greater => from_bool(a > b),
+                    BinaryOp::GreaterEq => from_bool(a >= b),
+                    BinaryOp::And | BinaryOp::Or => unreachable!(),
+                }
+            }
+            Expr::Call { func, args } => {
+                let args = args.iter().map(|x| x.eval(env)).collect::<Result<Vec<_>, _>>()?;
+                match (func, args.as_slice()) {
+                    (Function::Sin, [x]) => libm::sin(x.to_radians()),
+                    (Function::Cos, [x]) => libm::cos(x.to_radians()),
+                    (Function::Tan, [x]) => libm::tan(x.to_radians()),
+                    (Function::Asin, [x]) => libm::asin(*x).to_degrees(),
+                    (Function::Acos, [x]) => libm::acos(*x).to_degrees(),
+                    (Function::Atan, [x]) => libm::atan(*x).to_degrees(),
+                    (Function::Atan2, [y, x]) => libm::atan2(*y, *x).to_degrees(),
+                    (Function::Sqrt, [x]) => libm::sqrt(*x),
+                    (Function::Floor, [x]) => libm::floor(*x),
+                    (Function::Ceil, [x]) => libm::ceil(*x),
+                    (Function::Round, [x]) => libm::round(*x),
+                    (Function::Sign, [x]) => if *x > 0.0 { 1.0 } else if *x < 0.0 { -1.0 } else { 0.0 },
+                    (Function::Abs, [x]) => libm::fabs(*x),
+                    (Function::Log, [x]) => libm::log(*x),
+                    (Function::Mod, [a, b]) => if *b == 0.0 { *a } else { a - libm::floor(a / b) * b },
+                    (Function::Randi, [b]) => env.random(1.0, *b)?,
+                    (Function::Randi, [a, b]) => env.random(*a, *b)?,
+                    (Function::After, [x]) => from_bool(env.state_time()? >= *x),
+                    (func, args) => return Err(EvalError::BadArity { func: *func, got: args.len() }),
+                }
+            }
+        })
+    }
+}
+impl Stmt {
+    pub fn exec<E: Environment>(&self, env: &mut E) -> Result<(), EvalError> {
+        match self {
+            Stmt::Assign { var, value } => {
+                let value = value.eval(env)?;
+                env.set(var, value)
+            }
+            Stmt::AddAssign { var, value } => {
+                let value = env.get(var).ok_or_else(|| EvalError::UndefinedVariable { name: var.clone() })? + value.eval(env)?;
+                env.set(var, value)
+            }
+            Stmt::ResetTimer => env.reset_timer(),
+            Stmt::Unknown => Err(EvalError::UnknownValue),
+        }
+    }
+}
+
+impl Expr {
+    pub fn visit<'a, F: FnMut(&'a Expr)>(&'a self, f: &mut F) {
+        f(self);
+        match self {
+            Expr::Number(_) | Expr::Bool(_) | Expr::String(_) | Expr::Variable(_) | Expr::Timer | Expr::Unknown => (),
+            Expr::Neg { value } | Expr::Not { value } => value.visit(f),
+            Expr::Add { values } | Expr::Mul { values } | Expr::Call { func: _, args: values } => for value in values { value.visit(f) },
+            Expr::Binary { op: _, left, right } => { left.visit(f); right.visit(f); }
+        }
+    }
+    pub(crate) fn is_bool(&self) -> bool {
+        match self {
+            Expr::Bool(_) | Expr::Not { .. } | Expr::Call { func: Function::After, .. } => true,
+            Expr::Binary { op, .. } => !matches!(op, BinaryOp::Sub | BinaryOp::Div | BinaryOp::Pow),
+            Expr::Add { values } | Expr::Mul { values } if values.len() == 1 => values[0].is_bool(),
+            _ => false,
+        }
+    }
+    pub fn variables(&self) -> BTreeSet<&str> {
+        let mut res = BTreeSet::new();
+        self.visit(&mut |x| if let Expr::Variable(x) = x { res.insert(x.as_str()); });
+        res
+    }
+    pub fn display<'a>(&'a self, dialect: Dialect<'a>) -> impl fmt::Display + 'a {
+        Printer { value: self, dialect }
+    }
+}
+impl Stmt {
+    pub fn value(&self) -> Option<&Expr> {
+        match self {
+            Stmt::Assign { var: _, value } | Stmt::AddAssign { var: _, value } => Some(value),
+            Stmt::ResetTimer | Stmt::Unknown => None,
+        }
+    }
+    pub fn variables(&self) -> BTreeSet<&str> {
+        match self {
+            Stmt::Assign { var, value } | Stmt::AddAssign { var, value } => {
+                let mut res = value.variables();
+                res.insert(var.as_str());
+                res
+            }
+            Stmt::ResetTimer | Stmt::Unknown => BTreeSet::new(),
+        }
+    }
+    pub fn display<'a>(&'a self, dialect: Dialect<'a>) -> impl fmt::Display + 'a {
+        Printer { value: self, dialect }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Matlab, C, Python, JavaScript, Rust,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dialect<'a> {
+    pub language: Language,
+    pub variable_prefix: &'a str,
+    pub function_prefix: &'a str,
+    pub timer: &'a str,
+    pub state_time: &'a str,
+}
+impl Dialect<'static> {
+    pub const MATLAB: Dialect<'static> = Dialect::new(Language::Matlab);
+    pub const fn new(language: Language) -> Self {
+        Dialect { language, variable_prefix: "", function_prefix: "", timer: "t", state_time: "state_time" }
+    }
+}
+
+struct Printer<'a, T> {
+    value: &'a T,
+    dialect: Dialect<'a>,
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+    "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type", "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+    "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+const C_KEYWORDS: &[&str] = &[
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum", "extern", "float", "for", "goto", "if", "inline", "int", "long", "register",
+    "restrict", "return", "short", "signed", "sizeof", "static", "struct", "switch", "typedef", "union", "unsigned", "void", "volatile", "while", "_Bool", "_Complex", "_Imaginary",
+];
+
+const PREC_OR: u8 = 2;
+const PREC_AND: u8 = 4;
+const PREC_NOT_WORD: u8 = 5;
+const PREC_CMP: u8 = 6;
+const PREC_ADD: u8 = 8;
+const PREC_MUL: u8 = 10;
+const PREC_UNARY: u8 = 12;
+const PREC_POW: u8 = 14;
+const PREC_ATOM: u8 = 16;
+
+impl Dialect<'_> {
+    fn precedence(&self, expr: &Expr) -> u8 {
+        match expr {
+            Expr::Number(x) if x.starts_with('-') => PREC_UNARY,
+            Expr::Call { func: Function::After, .. } => PREC_CMP,
+            Expr::Number(_) | Expr::Bool(_) | Expr::String(_) | Expr::Variable(_) | Expr::Timer | Expr::Unknown | Expr::Call { .. } => PREC_ATOM,
+            Expr::Add { values } | Expr::Mul { values } if values.len() < 2 => values.first().map(|x| self.precedence(x)).unwrap_or(PREC_ATOM),
+            Expr::Neg { .. } => PREC_UNARY,
+            Expr::Not { .. } => if self.language == Language::Python { PREC_NOT_WORD } else { PREC_UNARY },
+            Expr::Add { .. } => PREC_ADD,
+            Expr::Mul { .. } => PREC_MUL,
+            Expr::Binary { op, .. } => match op {
+                BinaryOp::Sub => PREC_ADD,
+                BinaryOp::Div => PREC_MUL,
+                BinaryOp::Pow => if self.language == Language::Python { PREC_POW } else { PREC_ATOM },
+                BinaryOp::Eq | BinaryOp::Neq | BinaryOp::Less | BinaryOp::LessEq | BinaryOp::Greater | BinaryOp::GreaterEq => PREC_CMP,
+                BinaryOp::And => PREC_AND,
+                BinaryOp::Or => PREC_OR,
+            }
+        }
+    }
+    fn write_call(&self, f: &mut fmt::Formatter<'_>, prefix: &str, name: &str, args: &[&Expr]) -> fmt::Result {
+        match self.language {
+            Language::Rust if RUST_KEYWORDS.contains(&name) => write!(f, "{prefix}r#{name}(")?,
+            _ => write!(f, "{prefix}{name}(")?,
+        }
+        for (i, arg) in args.iter().enumerate() {
+            if i != 0 { write!(f, ", ")?; }
+            self.write_operand(f, arg, 0, false)?;
+        }
+        write!(f, ")")
+    }
+    fn write_variable(&self, f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+        match (self.language, name) {
+            (Language::Rust, "self" | "Self" | "super" | "crate") => write!(f, "{}{name}_", self.variable_prefix),
+            (Language::Rust, _) if RUST_KEYWORDS.contains(&name) => write!(f, "{}r#{name}", self.variable_prefix),
+            (Language::Python, _) if PYTHON_KEYWORDS.contains(&name) => write!(f, "{}{name}_", self.variable_prefix),
+            (Language::C, _) if C_KEYWORDS.contains(&name) => write!(f, "{}{name}_", self.variable_prefix),
+            _ => write!(f, "{}{name}", self.variable_prefix),
+        }
+    }
+    fn write_operand(&self, f: &mut fmt::Formatter<'_>, expr: &Expr, min_prec: u8, want_bool: bool) -> fmt::Result {
+        // rust has no implicit conversions between numbers and booleans, so they have to be spelled out
+        match (self.language, want_bool, expr.is_bool()) {
+            (Language::Rust, true, false) => {
+                if min_prec > PREC_CMP { write!(f, "(")?; }
+                self.write_expr(f, expr, PREC_CMP + 1)?;
+                write!(f, " != 0.0")?;
+                if min_prec > PREC_CMP { write!(f, ")")?; }
+                Ok(())
+            }
+            (Language::Rust, false, true) => {
+                write!(f, "f64::from(")?;
+                self.write_expr(f, expr, 0)?;
+                write!(f, ")")
+            }
+            _ => self.write_expr(f, expr, min_prec),
+        }
+    }
+    fn write_matlab(&self, f: &mut fmt::Formatter<'_>, expr: &Expr) -> fmt::Result {
+        fn punctuate(dialect: &Dialect<'_>, f: &mut fmt::Formatter<'_>, values: &[Expr], sep: &str) -> fmt::Result {
+            for (i, value) in values.iter().enumerate() {
+                if i != 0 { write!(f, "{sep}")?; }
+                dialect.write_matlab(f, value)?;
+            }
+            Ok(())
+        }
+
+        match expr {
+            Expr::Number(x) | Expr::String(x) => write!(f, "{x}"),
+            Expr::Bool(x) => write!(f, "{x}"),
+            Expr::Variable(x) => write!(f, "{}{x}", self.variable_prefix),
+            Expr::Timer => write!(f, "{}", self.timer),
+            Expr::Unknown => write!(f, "?"),
+            Expr::Neg { value } => { write!(f, "-")?; self.write_matlab(f, value) }
+            Expr::Not { value } => { write!(f, "~(")?; self.write_matlab(f, value)?; write!(f, ")") }
+            Expr::Add { values } if values.is_empty() => write!(f, "0"),
+            Expr::Mul { values } if values.is_empty() => write!(f, "1"),
+            Expr::Add { values } => { write!(f, "(")?; punctuate(self, f, values, " + ")?; write!(f, ")") }
+            Expr::Mul { values } => { write!(f, "(")?; punctuate(self, f, values, " * ")?; write!(f, ")") }
+            Expr::Binary { op, left, right } => {
+                let (op, grouped) = match op {
+                    BinaryOp::Sub => (" - ", true),
+                    BinaryOp::Div => (" / ", true),
+                    BinaryOp::Pow => (" ^ ", true),
+                    BinaryOp::Eq => (" == ", false),
+                    BinaryOp::Neq => (" ~= ", false),
+                    BinaryOp::Less => (" < ", false),
+                    BinaryOp::LessEq => (" <= ", false),
+                    BinaryOp::Greater => (" > ", false),
+                    BinaryOp::GreaterEq => (" >= ", false),
+                    BinaryOp::And => (" & ", false),
+                    BinaryOp::Or => (" | ", true),
+                };
+                if grouped { write!(f, "(")?; }
+                self.write_matlab(f, left)?;
+                write!(f, "{op}")?;
+                self.write_matlab(f, right)?;
+                if grouped { write!(f, ")")?; }
+                Ok(())
+            }
+            Expr::Call { func: Function::Randi, args } if args.len() == 2 => {
+                write!(f, "{}randi([", self.function_prefix)?;
+                punctuate(self, f, args, ", ")?;
+                write!(f, "])")
+            }
+            Expr::Call { func: Function::After, args } => {
+                write!(f, "{}after(", self.function_prefix)?;
+                punctuate(self, f, args, ", ")?;
+                write!(f, ", sec)")
+            }
+            Expr::Call { func, args } => {
+                write!(f, "{}{}(", self.function_prefix, func.name())?;
+                punctuate(self, f, args, ", ")?;
+                write!(f, ")")
+            }
+        }
+    }
+    fn write_expr(&self, f: &mut fmt::Formatter<'_>, expr: &Expr, min_prec: u8) -> fmt::Result {
+        if self.language == Language::Matlab {
+            return self.write_matlab(f, expr);
+        }
+
+        let prec = self.precedence(expr);
+        if prec < min_prec {
+            write!(f, "(")?;
+            self.write_expr(f, expr, 0)?;
+            return write!(f, ")");
+        }
+
+        match expr {
+            Expr::Number(x) => match self.language {
+                Language::C | Language::Rust if x.bytes().all(|c| c.is_ascii_digit() || c == b'-') => write!(f, "{x}.0"),
+                _ => write!(f, "{x}"),
+            }
+            Expr::Bool(x) => match (self.language, x) {
+                (Language::Python, true) => write!(f, "True"),
+                (Language::Python, false) => write!(f, "False"),
+                _ => write!(f, "{x}"),
+            }
+            Expr::String(x) => match (self.language, x.parse::<f64>()) {
+                (Language::C | Language::Rust | Language::Python, Ok(value)) if value.is_finite() => write!(f, "{value:?}"),
+                _ => write!(f, "{x:?}"),
+            }
+            Expr::Variable(x) => self.write_variable(f, x),
+            Expr::Timer => write!(f, "{}", self.timer),
+            Expr::Unknown => match self.language {
+                Language::JavaScript => write!(f, "undefined"),
+                _ => write!(f, "?"),
+            }
+            Expr::Neg { value } => { write!(f, "-")?; self.write_operand(f, value, PREC_UNARY + 1, false) }
+            Expr::Not { value } => match self.language {
+                Language::Python => { write!(f, "not ")?; self.write_expr(f, value, PREC_NOT_WORD) }
+                _ => { write!(f, "!")?; self.write_operand(f, value, PREC_UNARY + 1, true) }
+            }
+            Expr::Add { values } | Expr::Mul { values } if values.len() < 2 => match values.first() {
+                Some(x) => self.write_expr(f, x, min_prec),
+                None => self.write_expr(f, &Expr::Number(if matches!(expr, Expr::Add { .. }) { "0" } else { "1" }.into()), min_prec),
+            }
+            Expr::Add { values } | Expr::Mul { values } => {
+                let sep = if matches!(expr, Expr::Add { .. }) { " + " } else { " * " };
+                for (i, value) in values.iter().enumerate() {
+                    if i != 0 { write!(f, "{sep}")?; }
+                    self.write_operand(f, value, if i == 0 { prec } else { prec + 1 }, false)?;
+                }
+                Ok(())
+            }
+            Expr::Binary { op: BinaryOp::Pow, left, right } => match self.language {
+                Language::Python => {
+                    self.write_expr(f, left, PREC_POW + 1)?;
+                    write!(f, " ** ")?;
+                    self.write_expr(f, right, PREC_UNARY)
+                }
+                Language::JavaScript => self.write_call(f, "", "Math.pow", &[left, right]),
+                _ => self.write_call(f, self.function_prefix, "pow", &[left, right]),
+            }
+            Expr::Binary { op, left, right } => {
+                let want_bool = match op {
+                    BinaryOp::And | BinaryOp::Or => true,
+                    BinaryOp::Eq | BinaryOp::Neq => left.is_bool() && right.is_bool(),
+                    _ => false,
+                };
+                let op = match (op, self.language) {
+                    (BinaryOp::Sub, _) => " - ",
+                    (BinaryOp::Div, _) => " / ",
+                    (BinaryOp::Eq, _) => " == ",
+                    (BinaryOp::Neq, _) => " != ",
+                    (BinaryOp::Less, _) => " < ",
+                    (BinaryOp::LessEq, _) => " <= ",
+                    (BinaryOp::Greater, _) => " > ",
+                    (BinaryOp::GreaterEq, _) => " >= ",
+                    (BinaryOp::And, Language::Python) => " and ",
+                    (BinaryOp::Or, Language::Python) => " or ",
+                    (BinaryOp::And, _) => " && ",
+                    (BinaryOp::Or, _) => " || ",
+                    (BinaryOp::Pow, _) => unreachable!(),
+                };
+                let left_prec = if prec == PREC_CMP { prec + 1 } else { prec };
+                self.write_operand(f, left, left_prec, want_bool)?;
+                write!(f, "{op}")?;
+                self.write_operand(f, right, prec + 1, want_bool)
+            }
+            Expr::Call { func: Function::After, args } if args.len() == 1 => {
+                write!(f, "{} >= ", self.state_time)?;
+                self.write_operand(f, &args[0], PREC_CMP + 1, false)
+            }
+            Expr::Call { func: Function::Randi, args } if args.len() == 1 => self.write_call(f, self.function_prefix, "randi", &[&Expr::Number("1".into()), &args[0]]),
+            Expr::Call { func, args } => self.write_call(f, self.function_prefix, func.name(), &args.iter().collect::<Vec<_>>()),
+        }
+    }
+}
+impl fmt::Display for Printer<'_, Expr> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.dialect.write_expr(f, self.value, 0)
+    }
+}
+impl fmt::Display for Printer<'_, Stmt> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dialect = &self.dialect;
+        match (self.value, dialect.language) {
+            (Stmt::Assign { var, value }, _) => {
+                dialect.write_variable(f, var)?;
+                write!(f, " = ")?;
+                dialect.write_operand(f, value, 0, false)
+            }
+            (Stmt::AddAssign { var, value }, Language::Matlab) => {
+                write!(f, "{0}{var} = {0}{var} + ", dialect.variable_prefix)?;
+                dialect.write_expr(f, value, 0)
+            }
+            (Stmt::AddAssign { var, value }, _) => {
+                dialect.write_variable(f, var)?;
+                write!(f, " += ")?;
+                dialect.write_operand(f, value, 0, false)
+            }
+            (Stmt::ResetTimer, _) => {
+                write!(f, "{} = ", dialect.timer)?;
+                dialect.write_expr(f, &Expr::Number("0".into()), 0)
+            }
+            (Stmt::Unknown, _) => write!(f, "?"),
+        }
+    }
+}
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display(Dialect::MATLAB))
+    }
+}
+impl fmt::Display for Stmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display(Dialect::MATLAB))
+    }
+}
+
+#[test]
+fn test_parse_expr() {
+    fn num(x: &str) -> Expr { Expr::Number(x.into()) }
+    fn var(x: &str) -> Expr { Expr::Variable(x.into()) }
+    fn bin(op: BinaryOp, left: Expr, right: Expr) -> Expr { Expr::Binary { op, left: Box::new(left), right: Box::new(right) } }
+
+    assert_eq!("12".parse::<Expr>().unwrap(), num("12"));
+    assert_eq!("-4".parse::<Expr>().unwrap(), num("-4"));
+    assert_eq!("3.1415".parse::<Expr>().unwrap(), num("3.1415"));
+    assert_eq!("2.718281828459045".parse::<Expr>().unwrap(), num("2.718281828459045"));
+    assert_eq!("foo_3".parse::<Expr>().unwrap(), var("foo_3"));
+    assert_eq!("t".parse::<Expr>().unwrap(), Expr::Timer);
+    assert_eq!("?".parse::<Expr>().unwrap(), Expr::Unknown);
+    assert_eq!("t > 10".parse::<Expr>().unwrap(), bin(BinaryOp::Greater, Expr::Timer, num("10")));
+    assert_eq!("(foo + 7 + bar)".parse::<Expr>().unwrap(), Expr::Add { values: vec![var("foo"), num("7"), var("bar")] });
+    assert_eq!("((a + b) + c)".parse::<Expr>().unwrap(), Expr::Add { values: vec![Expr::Add { values: vec![var("a"), var("b")] }, var("c")] });
+    assert_eq!("(a * b) > 100".parse::<Expr>().unwrap(), bin(BinaryOp::Greater, Expr::Mul { values: vec![var("a"), var("b")] }, num("100")));
+    assert_eq!("(log(3.1415) / log(2.0))".parse::<Expr>().unwrap(), bin(BinaryOp::Div, Expr::Call { func: Function::Log, args: vec![num("3.1415")] }, Expr::Call { func: Function::Log, args: vec![num("2.0")] }));
+    assert_eq!("randi(6)".parse::<Expr>().unwrap(), Expr::Call { func: Function::Randi, args: vec![num("6")] });
+    assert_eq!("randi([a, 6])".parse::<Expr>().unwrap(), Expr::Call { func: Function::Randi, args: vec![var("a"), num("6")] });
+    assert_eq!("after(3, sec)".parse::<Expr>().unwrap(), Expr::Call { func: Function::After, args: vec![num("3")] });
+    assert_eq!("atan2d(foo, bar)".parse::<Expr>().unwrap(), Expr::Call { func: Function::Atan2, args: vec![var("foo"), var("bar")] });
+    assert_eq!("a == 1 & b ~= 2".parse::<Expr>().unwrap(), bin(BinaryOp::And, bin(BinaryOp::Eq, var("a"), num("1")), bin(BinaryOp::Neq, var("b"), num("2"))));
+    assert_eq!("~(a | b)".parse::<Expr>().unwrap(), Expr::Not { value: Box::new(bin(BinaryOp::Or, var("a"), var("b"))) });
+    assert_eq!("-x".parse::<Expr>().unwrap(), Expr::Neg { value: Box::new(var("x")) });
+    assert_eq!("(2 ^ 3 ^ 2)".parse::<Expr>().unwrap(), bin(BinaryOp::Pow, bin(BinaryOp::Pow, num("2"), num("3")), num("2")));
+
+    assert_eq!("(a + b".parse::<Expr>().unwrap_err(), ParseError::UnexpectedEnd);
+    assert_eq!("a + b)".parse::<Expr>().unwrap_err(), ParseError::UnexpectedToken { pos: 5, token: ")".into() });
+    assert_eq!("foo(1)".parse::<Expr>().unwrap_err(), ParseError::UnknownFunction { name: "foo".into() });
+    assert_eq!("mod(1)".parse::<Expr>().unwrap_err(), ParseError::WrongArgCount { name: "mod".into(), got: 1 });
+    assert_eq!("after(1, min)".parse::<Expr>().unwrap_err(), ParseError::UnexpectedToken { pos: 9, token: "min".into() });
+    assert_eq!("a $ b".parse::<Expr>().unwrap_err(), ParseError::UnexpectedChar { pos: 2, ch: '$' });
+}
+#[test]
+fn test_parse_stmt() {
+    assert_eq!("t = 0".parse::<Stmt>().unwrap(), Stmt::ResetTimer);
+    assert_eq!("?".parse::<Stmt>().unwrap(), Stmt::Unknown);
+    assert_eq!("derp = ?".parse::<Stmt>().unwrap(), Stmt::Assign { var: "derp".into(), value: Expr::Unknown });
+    assert_eq!("x = (x + 1)".parse::<Stmt>().unwrap(), Stmt::Assign { var: "x".into(), value: Expr::Add { values: vec![Expr::Variable("x".into()), Expr::Number("1".into())] } });
+    assert_eq!("bar_5 = bar_5 + (1 + -4)".parse::<Stmt>().unwrap(), Stmt::AddAssign { var: "bar_5".into(), value: Expr::Add { values: vec![Expr::Number("1".into()), Expr::Number("-4".into())] } });
+    assert_eq!("x = y + 1".parse::<Stmt>().unwrap(), Stmt::Assign { var: "x".into(), value: Expr::Add { values: vec![Expr::Variable("y".into()), Expr::Number("1".into())] } });
+    assert!("x == 1".parse::<Stmt>().is_err());
+    assert!("t = 5".parse::<Stmt>().is_err());
+}
+#[test]
+fn test_display() {
+    for src in ["t > 10", "after(3, sec)", "(a + b) > 10", "(foo + 7 + bar)", "((a + b) + c)", "(log(3.1415) / log(2.718281828459045))", "randi(6)", "randi([a, 9])",
+        "atan2d(foo, bar)", "(2.0 ^ 3.1415)", "-3.1415", "-x", "~(x == 1)", "a == 1 & b ~= 2", "(a | b)", "mod(bar, foo)", "?", "true"] {
+        assert_eq!(src.parse::<Expr>().unwrap().to_string(), src);
+    }
+    for src in ["t = 0", "?", "derp = ?", "foo = (foo * 5 * bar)", "bar_5 = bar_5 + (1 + -4)", "b = randi(6)"] {
+        assert_eq!(src.parse::<Stmt>().unwrap().to_string(), src);
+    }
+
+    let c = Dialect { variable_prefix: "s->", function_prefix: "sf_", timer: "s->t", state_time: "s->state_time", ..Dialect::new(Language::C) };
+    let py = Dialect { variable_prefix: "self.", timer: "self.t", ..Dialect::new(Language::Python) };
+    let js = Dialect::new(Language::JavaScript);
+    for (src, c_res, py_res, js_res) in [
+        ("t > 10", "s->t > 10.0", "self.t > 10", "t > 10"),
+        ("(foo + 7 + bar) * 2", "(s->foo + 7.0 + s->bar) * 2.0", "(self.foo + 7 + self.bar) * 2", "(foo + 7 + bar) * 2"),
+        ("((a - b) - (c - d))", "s->a - s->b - (s->c - s->d)", "self.a - self.b - (self.c - self.d)", "a - b - (c - d)"),
+        ("(a ^ -b)", "sf_pow(s->a, -s->b)", "self.a ** -self.b", "Math.pow(a, -b)"),
+        ("-(a ^ 2)", "-sf_pow(s->a, 2.0)", "-self.a ** 2", "-Math.pow(a, 2)"),
+        ("(-a ^ 2)", "-sf_pow(s->a, 2.0)", "-self.a ** 2", "-Math.pow(a, 2)"),
+        ("((-2) ^ 2)", "sf_pow(-2.0, 2.0)", "(-2) ** 2", "Math.pow(-2, 2)"),
+        ("--a", "-(-s->a)", "-(-self.a)", "-(-a)"),
+        ("~(a == b) & (c | d)", "!(s->a == s->b) && (s->c || s->d)", "not self.a == self.b and (self.c or self.d)", "!(a == b) && (c || d)"),
+        ("~(a) == b", "!s->a == s->b", "(not self.a) == self.b", "!a == b"),
+        ("(a < b) == (c < d)", "(s->a < s->b) == (s->c < s->d)", "(self.a < self.b) == (self.c < self.d)", "(a < b) == (c < d)"),
+        ("randi(6) + randi([a, 9])", "sf_randi(1.0, 6.0) + sf_randi(s->a, 9.0)", "randi(1, 6) + randi(self.a, 9)", "randi(1, 6) + randi(a, 9)"),
+        ("after(2.5, sec) & true", "s->state_time >= 2.5 && true", "state_time >= 2.5 and True", "state_time >= 2.5 && true"),
+        ("~(after(1, sec)) | ?", "!(s->state_time >= 1.0) || ?", "not state_time >= 1 or ?", "!(state_time >= 1) || undefined"),
+    ] {
+        let expr = src.parse::<Expr>().unwrap();
+        assert_eq!(expr.display(c).to_string(), c_res);
+        assert_eq!(expr.display(py).to_string(), py_res);
+        assert_eq!(expr.display(js).to_string(), js_res);
+    }
+    assert_eq!(Expr::Call { func: Function::After, args: vec![] }.display(c).to_string(), "sf_after()");
+
+    let rs = Dialect { variable_prefix: "self.v.", function_prefix: "self.", ..Dialect::new(Language::Rust) };
+    for (src, rs_res) in [
+        ("(a ^ -b)", "self.pow(self.v.a, -self.v.b)"),
+        ("~(a) == b", "f64::from(!(self.v.a != 0.0)) == self.v.b"),
+        ("(a < b) + (c | d)", "f64::from(self.v.a < self.v.b) + f64::from(self.v.c != 0.0 || self.v.d != 0.0)"),
+        ("(a < b) == c", "f64::from(self.v.a < self.v.b) == self.v.c"),
+        ("-(a == 2)", "-f64::from(self.v.a == 2.0)"),
+        ("type + 1", "self.v.r#type + 1.0"),
+        ("mod(a, 3)", "self.r#mod(self.v.a, 3.0)"),
+    ] {
+        assert_eq!(src.parse::<Expr>().unwrap().display(rs).to_string(), rs_res);
+    }
+    assert_eq!("self = (x < 4)".parse::<Stmt>().unwrap().display(rs).to_string(), "self.v.self_ = f64::from(self.v.x < 4.0)");
+    assert_eq!("t = 0".parse::<Stmt>().unwrap().display(rs).to_string(), "t = 0.0");
+
+    let stmt = "x = x + (y / 2)".parse::<Stmt>().unwrap();
+    assert_eq!(stmt.display(c).to_string(), "s->x += s->y / 2.0");
+    assert_eq!("int = (double + 1)".parse::<Stmt>().unwrap().display(c).to_string(), "s->int_ = s->double_ + 1.0");
+    assert_eq!(stmt.display(py).to_string(), "self.x += self.y / 2");
+    assert_eq!("t = 0".parse::<Stmt>().unwrap().display(py).to_string(), "self.t = 0");
+    assert_eq!("from = (lambda + 1)".parse::<Stmt>().unwrap().display(py).to_string(), "self.from_ = self.lambda_ + 1");
+}
+#[test]
+fn test_eval() {
+    let mut env: BTreeMap<CompactString, f64> = [("a".into(), 3.0), ("b".into(), -2.0), ("t".into(), 12.5)].into_iter().collect();
+    for (src, res) in [
+        ("(a + b + 1)", 2.0), ("(a * b * 2)", -12.0), ("(a - b)", 5.0), ("(a / b)", -1.5), ("(a ^ 2)", 9.0), ("-a", -3.0),
+        ("a == 3", 1.0), ("a ~= 3", 0.0), ("a < b", 0.0), ("a >= b", 1.0), ("a > 2 & b > 2", 0.0), ("(a > 2 | b > 2)", 1.0), ("~(a)", 0.0),
+        ("mod(a, 2)", 1.0), ("mod(b, 3)", 1.0), ("mod(a, 0)", 3.0), ("abs(b)", 2.0), ("sign(b)", -1.0), ("sqrt(9)", 3.0), ("round(2.5)", 3.0), ("round(-2.5)", -3.0),
+        ("floor(-1.5)", -2.0), ("ceil(1.2)", 2.0), ("sind(90)", 1.0), ("atan2d(1, 1)", 45.0), ("(log(8) / log(2))", 3.0), ("t > 10", 1.0), ("true", 1.0),
+    ] {
+        let value = src.parse::<Expr>().unwrap().eval(&mut env).unwrap();
+        assert!((value - res).abs() < 1e-9, "{src} => {value} (expected {res})");
+    }
+
+    assert_eq!("c + 1".parse::<Expr>().unwrap().eval(&mut env), Err(EvalError::UndefinedVariable { name: "c".into() }));
+    assert_eq!("?".parse::<Expr>().unwrap().eval(&mut env), Err(EvalError::UnknownValue));
+    assert_eq!("randi(6)".parse::<Expr>().unwrap().eval(&mut env), Err(EvalError::Unsupported { func: Function::Randi }));
+    assert_eq!("after(1, sec)".parse::<Expr>().unwrap().eval(&mut env), Err(EvalError::Unsupported { func: Function::After }));
+    assert_eq!(Expr::Call { func: Function::Mod, args: vec![Expr::Number("1".into())] }.eval(&mut env), Err(EvalError::BadArity { func: Function::Mod, got: 1 }));
+
+    "c = a + 1".parse::<Stmt>().unwrap().exec(&mut env).unwrap();
+    "a = a + 1".parse::<Stmt>().unwrap().exec(&mut env).unwrap();
+    "t = 0".parse::<Stmt>().unwrap().exec(&mut env).unwrap();
+    assert_eq!(env, [("a".into(), 4.0), ("b".into(), -2.0), ("c".into(), 4.0), ("t".into(), 0.0)].into_iter().collect());
+
+    assert_eq!("x = (x + y * sind(z)) & t".parse::<Stmt>().unwrap().variables(), ["x", "y", "z"].into_iter().collect());
+}
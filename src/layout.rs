@@ -0,0 +1,205 @@
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::vec::Vec;
+
+use netsblox_ast::compact_str::CompactString;
+
+use crate::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LayoutSettings {
+    pub state_size: (usize, usize),
+    pub junction_size: (usize, usize),
+    pub padding: (usize, usize),
+    pub transition_spacing: usize,
+    pub crossing_passes: usize,
+}
+impl Default for LayoutSettings {
+    fn default() -> Self {
+        Self {
+            state_size: (100, 100),
+            junction_size: (100, 20),
+            padding: (100, 100),
+            transition_spacing: 20,
+            crossing_passes: 8,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Layout {
+    pub states: BTreeMap<CompactString, (usize, usize, usize, usize)>,
+    pub transitions: BTreeMap<(CompactString, usize), (f64, f64)>,
+}
+
+fn count_crossings(layers: &[Vec<usize>], edges: &BTreeSet<(usize, usize)>, layer_of: &[usize]) -> usize {
+    let mut positions = vec![0; layer_of.len()];
+    for layer in layers {
+        for (i, &node) in layer.iter().enumerate() {
+            positions[node] = i;
+        }
+    }
+
+    let mut res = 0;
+    for l in 0..layers.len().saturating_sub(1) {
+        let between = edges.iter().filter_map(|&(a, b)| match (layer_of[a], layer_of[b]) {
+            (x, y) if x == l && y == l + 1 => Some((positions[a], positions[b])),
+            (x, y) if x == l + 1 && y == l => Some((positions[b], positions[a])),
+            _ => None,
+        }).collect::<Vec<_>>();
+        for (i, a) in between.iter().enumerate() {
+            for b in between[i + 1..].iter() {
+                if (a.0 < b.0 && a.1 > b.1) || (a.0 > b.0 && a.1 < b.1) {
+                    res += 1;
+                }
+            }
+        }
+    }
+    res
+}
+
+fn reorder_layer(layers: &mut [Vec<usize>], target: usize, reference: usize, neighbors: &[BTreeSet<usize>]) {
+    let reference_positions = layers[reference].iter().enumerate().map(|(i, &x)| (x, i)).collect::<BTreeMap<_,_>>();
+    let mut keyed = layers[target].iter().enumerate().map(|(i, &node)| {
+        let positions = neighbors[node].iter().filter_map(|x| reference_positions.get(x)).collect::<Vec<_>>();
+        let key = match positions.is_empty() {
+            true => i as f64,
+            false => positions.iter().map(|&&x| x as f64).sum::<f64>() / positions.len() as f64,
+        };
+        (key, node)
+    }).collect::<Vec<_>>();
+    keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    layers[target] = keyed.into_iter().map(|x| x.1).collect();
+}
+
+impl StateMachine {
+    pub fn layout(&self, settings: LayoutSettings) -> Layout {
+        let names = self.states.keys().collect::<Vec<_>>();
+        let numbers = names.iter().enumerate().map(|(i, &x)| (x.as_str(), i)).collect::<BTreeMap<_,_>>();
+        // targets are kept by transition index, with None for transitions to states that do not exist (which are not laid out)
+        let targets = self.states.iter().map(|(state_name, state)| {
+            state.transitions.iter().map(|t| numbers.get(t.new_state.as_deref().unwrap_or(state_name)).copied()).collect::<Vec<_>>()
+        }).collect::<Vec<_>>();
+
+        let mut edges = BTreeSet::new();
+        let mut neighbors = vec![BTreeSet::new(); names.len()];
+        for (source, targets) in targets.iter().enumerate() {
+            for target in targets.iter().flatten().copied().filter(|&x| x != source) {
+                edges.insert((source, target));
+                neighbors[source].insert(target);
+                neighbors[target].insert(source);
+            }
+        }
+
+        let root = self.initial_state.as_deref().or(self.current_state.as_deref()).or_else(|| self.states.iter().find(|s| s.1.parent.is_none()).map(|s| s.0.as_str())).and_then(|x| numbers.get(x).copied());
+        let mut layer_of = vec![usize::MAX; names.len()];
+        let mut layers: Vec<Vec<usize>> = vec![];
+        for start in root.into_iter().chain(0..names.len()) {
+            if layer_of[start] != usize::MAX {
+                continue;
+            }
+            layer_of[start] = 0;
+            let mut queue = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                if layers.len() <= layer_of[node] {
+                    layers.push(vec![]);
+                }
+                layers[layer_of[node]].push(node);
+                for &target in targets[node].iter().flatten() {
+                    if layer_of[target] == usize::MAX {
+                        layer_of[target] = layer_of[node] + 1;
+                        queue.push_back(target);
+                    }
+                }
+            }
+        }
+
+        let mut best = (count_crossings(&layers, &edges, &layer_of), layers.clone());
+        for _ in 0..settings.crossing_passes {
+            if best.0 == 0 {
+                break;
+            }
+            for l in 1..layers.len() {
+                reorder_layer(&mut layers, l, l - 1, &neighbors);
+            }
+            for l in (0..layers.len().saturating_sub(1)).rev() {
+                reorder_layer(&mut layers, l, l + 1, &neighbors);
+            }
+            let crossings = count_crossings(&layers, &edges, &layer_of);
+            if crossings < best.0 {
+                best = (crossings, layers.clone());
+            }
+        }
+        let layers = best.1;
+
+        let cell = (settings.state_size.0 + settings.padding.0, settings.state_size.1 + settings.padding.1);
+        let widest = layers.iter().map(|x| x.len()).max().unwrap_or(0);
+        let mut boxes = vec![(0, 0, 0, 0); names.len()];
+        for (l, layer) in layers.iter().enumerate() {
+            for (i, &node) in layer.iter().enumerate() {
+                let (x, y) = (i * cell.0 + (widest - layer.len()) * cell.0 / 2, l * cell.1);
+                boxes[node] = match self.states[names[node]].parent {
+                    Some(_) => (x + settings.state_size.0.saturating_sub(settings.junction_size.0) / 2, y + settings.state_size.1.saturating_sub(settings.junction_size.1) / 2, settings.junction_size.0, settings.junction_size.1),
+                    None => (x, y, settings.state_size.0, settings.state_size.1),
+                };
+            }
+        }
+        let center = |node: usize| {
+            let (x, y, w, h) = boxes[node];
+            (x as f64 + w as f64 / 2.0, y as f64 + h as f64 / 2.0)
+        };
+
+        let mut pair_counts: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for (source, targets) in targets.iter().enumerate() {
+            for &target in targets.iter().flatten() {
+                *pair_counts.entry((source.min(target), source.max(target))).or_default() += 1;
+            }
+        }
+
+        let mut transitions = BTreeMap::new();
+        let mut pair_indices: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        let spacing = settings.transition_spacing as f64;
+        for (source, targets) in targets.iter().enumerate() {
+            let state = &self.states[names[source]];
+            for (i, target) in targets.iter().enumerate() {
+                let target = match target {
+                    Some(x) => *x,
+                    None => continue,
+                };
+                let transition = &state.transitions[i];
+                if source == target && transition.actions.is_empty() && transition.ordered_condition == Condition::constant(true) {
+                    continue;
+                }
+                let pair = (source.min(target), source.max(target));
+                let index = pair_indices.entry(pair).or_default();
+                let midpoint = match source == target {
+                    true => {
+                        let (x, y, w, h) = boxes[source];
+                        ((x + w) as f64 + spacing * (*index + 1) as f64, y as f64 + h as f64 / 2.0)
+                    }
+                    false => {
+                        let (a, b) = (center(pair.0), center(pair.1));
+                        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+                        let length = libm::sqrt(dx * dx + dy * dy).max(1.0);
+                        let mut offset = (*index as f64 - (pair_counts[&pair] - 1) as f64 / 2.0) * spacing;
+                        let mut midpoint = ((a.0 + b.0) / 2.0 - dy / length * offset, (a.1 + b.1) / 2.0 + dx / length * offset);
+                        for _ in 0..names.len() {
+                            if !boxes.iter().enumerate().any(|(n, &(x, y, w, h))| n != source && n != target && (x as f64..=(x + w) as f64).contains(&midpoint.0) && (y as f64..=(y + h) as f64).contains(&midpoint.1)) {
+                                break;
+                            }
+                            offset += if dy <= 0.0 { 1.0 } else { -1.0 } * cell.0 as f64 / 2.0;
+                            midpoint = ((a.0 + b.0) / 2.0 - dy / length * offset, (a.1 + b.1) / 2.0 + dx / length * offset);
+                        }
+                        (libm::round(midpoint.0), libm::round(midpoint.1))
+                    }
+                };
+                *index += 1;
+                transitions.insert((names[source].clone(), i), midpoint);
+            }
+        }
+
+        Layout {
+            states: names.iter().enumerate().map(|(i, &x)| (x.clone(), boxes[i])).collect(),
+            transitions,
+        }
+    }
+}
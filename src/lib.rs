@@ -4,16 +4,20 @@
 #[macro_use]
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 use netsblox_ast as ast;
 use netsblox_ast::compact_str::{CompactString, ToCompactString, format_compact};
 
 use graphviz_rust::dot_structures as dot;
 
-use alloc::collections::{VecDeque, BTreeMap};
+use alloc::collections::{VecDeque, BTreeMap, BTreeSet};
 use alloc::fmt::Write as _;
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use alloc::string::{ToString, String};
+use core::fmt;
 
 pub use graphviz_rust as graphviz;
 
@@ -31,7 +35,31 @@ macro_rules! deque {
 }
 
 mod condition;
+mod expr;
+mod sim;
+mod scxml;
+mod mermaid;
+mod plantuml;
+mod c;
+mod rust;
+mod python;
+mod layout;
+mod stateflow;
+mod netsblox;
+mod analysis;
+mod completeness;
+mod bdd;
+mod normal_form;
+
 pub use condition::*;
+pub use expr::*;
+pub use sim::*;
+pub use c::CCode;
+pub use layout::*;
+pub use analysis::*;
+pub use completeness::*;
+pub use bdd::Bdd;
+pub use stateflow::StateflowSettings;
 
 trait VecDequeUtil<T> {
     fn extend_front<I: Iterator<Item = T> + DoubleEndedIterator>(&mut self, iter: I);
@@ -44,17 +72,6 @@ impl<T> VecDequeUtil<T> for VecDeque<T> {
     }
 }
 
-fn punctuate<'a, I: Iterator<Item = &'a str>>(mut values: I, sep: &str) -> Option<(CompactString, usize)> {
-    let mut res = CompactString::new(values.next()?);
-    let mut separators = 0;
-    for x in values {
-        res.push_str(sep);
-        res.push_str(x);
-        separators += 1;
-    }
-    Some((res, separators))
-}
-
 fn common_suffix<T: PartialEq, I: Iterator<Item = J>, J: Iterator<Item = T> + DoubleEndedIterator>(mut sequences: I) -> Vec<T> {
     let mut suffix: Vec<T> = match sequences.next() {
         None => return <_>::default(),
@@ -116,50 +133,275 @@ pub enum CompileError {
     RenameFailure { before: CompactString },
     RenameConflict { before: (CompactString, CompactString), after: CompactString },
 
-    TransitionEmptyTarget { state_machine: CompactString, state: CompactString },
-    UnsupportedBlock { state_machine: CompactString, state: CompactString, info: CompactString },
-    NonTerminalTransition { state_machine: CompactString, state: CompactString },
-    MultipleHandlers { state_machine: CompactString, state: CompactString },
-    ComplexTransitionName { state_machine: CompactString, state: CompactString },
-    VariadicBlocks { state_machine: CompactString, state: CompactString },
-    ActionsOutsideTransition { state_machine: CompactString, state: CompactString },
+    TransitionEmptyTarget { state_machine: CompactString, state: CompactString, location: Box<Location> },
+    UnsupportedBlock { state_machine: CompactString, state: CompactString, info: CompactString, location: Box<Location> },
+    NonTerminalTransition { state_machine: CompactString, state: CompactString, location: Box<Location> },
+    MultipleHandlers { state_machine: CompactString, state: CompactString, location: Box<Location> },
+    ComplexTransitionName { state_machine: CompactString, state: CompactString, location: Box<Location> },
+    VariadicBlocks { state_machine: CompactString, state: CompactString, location: Box<Location> },
+    ActionsOutsideTransition { state_machine: CompactString, state: CompactString, location: Box<Location> },
     VariableOverlap { state_machines: (CompactString, CompactString), variable: CompactString },
-    TransitionForeignMachine { state_machine: CompactString, state: CompactString, foreign_machine: CompactString },
+    TransitionForeignMachine { state_machine: CompactString, state: CompactString, foreign_machine: CompactString, location: Box<Location> },
+
+    UnsupportedExport { state_machine: CompactString, state: CompactString, info: CompactString },
+}
+impl CompileError {
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            CompileError::TransitionEmptyTarget { location, .. } | CompileError::UnsupportedBlock { location, .. } | CompileError::NonTerminalTransition { location, .. }
+            | CompileError::MultipleHandlers { location, .. } | CompileError::ComplexTransitionName { location, .. } | CompileError::VariadicBlocks { location, .. }
+            | CompileError::ActionsOutsideTransition { location, .. } | CompileError::TransitionForeignMachine { location, .. } => Some(location),
+            CompileError::ParseError(_) | CompileError::RoleCount { .. } | CompileError::UnknownRole { .. } | CompileError::RenameFailure { .. }
+            | CompileError::RenameConflict { .. } | CompileError::VariableOverlap { .. } | CompileError::UnsupportedExport { .. } => None,
+        }
+    }
+    fn within(mut self, outer: &Location) -> Self {
+        match &mut self {
+            CompileError::TransitionEmptyTarget { location, .. } | CompileError::UnsupportedBlock { location, .. } | CompileError::NonTerminalTransition { location, .. }
+            | CompileError::MultipleHandlers { location, .. } | CompileError::ComplexTransitionName { location, .. } | CompileError::VariadicBlocks { location, .. }
+            | CompileError::ActionsOutsideTransition { location, .. } | CompileError::TransitionForeignMachine { location, .. } => location.within(outer),
+            CompileError::ParseError(_) | CompileError::RoleCount { .. } | CompileError::UnknownRole { .. } | CompileError::RenameFailure { .. }
+            | CompileError::RenameConflict { .. } | CompileError::VariableOverlap { .. } | CompileError::UnsupportedExport { .. } => (),
+        }
+        self
+    }
+}
+fn write_parse_error(f: &mut fmt::Formatter<'_>, kind: &ast::ErrorKind) -> fmt::Result {
+    use ast::{ErrorKind, XmlError, ProjectError, CompileError as AstError};
+    match kind {
+        ErrorKind::XmlError(XmlError::Read { error }) => write!(f, "invalid xml: {error}"),
+        ErrorKind::XmlError(XmlError::IllegalSequence { sequence }) => write!(f, "invalid xml: illegal character sequence '{sequence}'"),
+        ErrorKind::XmlError(XmlError::UnexpectedEof) => write!(f, "invalid xml: unexpected end of file"),
+        ErrorKind::Base64Error(e) => write!(f, "invalid base64 content: {e}"),
+        ErrorKind::ProjectError(e) => match e {
+            ProjectError::NoRoot => write!(f, "no project was found"),
+            ProjectError::NoStage => write!(f, "the project has no stage"),
+            ProjectError::RoleNoName => write!(f, "a role has no name"),
+            ProjectError::RoleNoContent => write!(f, "a role has no content"),
+            ProjectError::RefMissingId => write!(f, "a reference has no id"),
+            ProjectError::ValueNotEvaluated => write!(f, "a value could not be evaluated"),
+            ProjectError::UpvarNotConst => write!(f, "an upvar name is not a fixed value"),
+            ProjectError::UnnamedGlobal => write!(f, "a global variable has no name"),
+            ProjectError::GlobalsWithSameName { name } => write!(f, "there are multiple global variables named '{name}'"),
+            ProjectError::UnnamedEntity => write!(f, "a sprite has no name"),
+            ProjectError::EntitiesWithSameName { name } => write!(f, "there are multiple sprites named '{name}'"),
+            ProjectError::UnnamedField => write!(f, "a sprite variable has no name"),
+            ProjectError::FieldNoValue { name } => write!(f, "sprite variable '{name}' has no value"),
+            ProjectError::FieldsWithSameName { name } => write!(f, "there are multiple sprite variables named '{name}'"),
+            ProjectError::BlockWithoutType => write!(f, "a block has no type"),
+            ProjectError::BlockUnknownType => write!(f, "a block has an unknown type"),
+            ProjectError::BlockChildCount { needed, got } => write!(f, "a block has {got} inputs, but {needed} were expected"),
+            ProjectError::BlockMissingOption => write!(f, "a block is missing a required option"),
+            ProjectError::BlockOptionUnknown { got } => write!(f, "a block has an unknown option '{got}'"),
+            ProjectError::ImageWithoutId => write!(f, "an image has no id"),
+            ProjectError::ImagesWithSameId { id } => write!(f, "there are multiple images with id '{id}'"),
+            ProjectError::ImageWithoutContent { id } => write!(f, "image '{id}' has no content"),
+            ProjectError::ImageUnknownFormat { id, .. } => write!(f, "image '{id}' has an unknown format"),
+            ProjectError::SoundWithoutId => write!(f, "a sound has no id"),
+            ProjectError::SoundsWithSameId { id } => write!(f, "there are multiple sounds with id '{id}'"),
+            ProjectError::SoundWithoutContent { id } => write!(f, "sound '{id}' has no content"),
+            ProjectError::SoundUnknownFormat { id, .. } => write!(f, "sound '{id}' has an unknown format"),
+            ProjectError::CostumeIdFormat { id } => write!(f, "costume id '{id}' is malformed"),
+            ProjectError::CostumeUndefinedRef { id } => write!(f, "costume '{id}' is used but not defined"),
+            ProjectError::CostumesWithSameName { name } => write!(f, "there are multiple costumes named '{name}'"),
+            ProjectError::SoundIdFormat { id } => write!(f, "sound id '{id}' is malformed"),
+            ProjectError::SoundUndefinedRef { id } => write!(f, "sound '{id}' is used but not defined"),
+            ProjectError::SoundsWithSameName { name } => write!(f, "there are multiple sounds named '{name}'"),
+            ProjectError::BoolNoValue => write!(f, "a boolean has no value"),
+            ProjectError::BoolUnknownValue { got } => write!(f, "'{got}' is not a valid boolean"),
+            ProjectError::ColorUnknownValue { color } => write!(f, "'{color}' is not a valid color"),
+            ProjectError::CustomBlockWithoutName => write!(f, "a custom block has no name"),
+            ProjectError::CustomBlockWithoutInputsMeta => write!(f, "a custom block has no input information"),
+            ProjectError::CustomBlockInputsMetaCorrupted => write!(f, "a custom block has corrupted input information"),
+            ProjectError::CustomBlockWithoutType => write!(f, "a custom block has no type"),
+            ProjectError::CustomBlockUnknownType { ty } => write!(f, "a custom block has an unknown type '{ty}'"),
+            ProjectError::MessageTypeMissingName => write!(f, "a message type has no name"),
+            ProjectError::MessageTypeMissingFields { msg_type } => write!(f, "message type '{msg_type}' has no fields"),
+            ProjectError::MessageTypeFieldEmpty { msg_type } => write!(f, "message type '{msg_type}' has a field with an empty name"),
+            ProjectError::MessageTypeMultiplyDefined { msg_type } => write!(f, "message type '{msg_type}' is defined more than once"),
+        }
+        ErrorKind::CompileError(e) => match e {
+            AstError::AutofillGenerateError { input } => write!(f, "a name could not be generated for empty input {input}"),
+            AstError::NameTransformError { name } => write!(f, "'{name}' cannot be converted into a valid name"),
+            AstError::UnknownBlockType => write!(f, "a block has an unknown type"),
+            AstError::DerefAssignment => write!(f, "a block assigns to a value that is not a variable"),
+            AstError::UndefinedVariable { name } => write!(f, "variable '{name}' is not defined"),
+            AstError::UndefinedFn { name } => write!(f, "custom block '{name}' is not defined"),
+            AstError::BlockOptionNotConst => write!(f, "a block option is not a fixed value"),
+            AstError::BlockOptionNotSelected => write!(f, "a block option was not selected"),
+            AstError::UnknownEntity { unknown } => write!(f, "sprite '{unknown}' does not exist"),
+            AstError::UnknownEffect { effect } => write!(f, "'{effect}' is not a known graphic effect"),
+            AstError::UnknownPenAttr { attr } => write!(f, "'{attr}' is not a known pen attribute"),
+            AstError::UnknownMessageType { msg_type } => write!(f, "message type '{msg_type}' is not defined"),
+            AstError::MessageTypeWrongNumberArgs { msg_type, got, expected } => write!(f, "message type '{msg_type}' has {expected} fields, but {got} were given"),
+            AstError::UnknownService { service } => write!(f, "'{service}' is not a known service"),
+            AstError::UnknownRPC { service, rpc } => write!(f, "service '{service}' has no rpc named '{rpc}'"),
+            AstError::GlobalsWithSameTransName { trans_name, names } => write!(f, "global variables '{}' and '{}' would both be renamed to '{trans_name}'", names.0, names.1),
+            AstError::EntitiesWithSameTransName { trans_name, names } => write!(f, "sprites '{}' and '{}' would both be renamed to '{trans_name}'", names.0, names.1),
+            AstError::FieldsWithSameTransName { trans_name, names } => write!(f, "sprite variables '{}' and '{}' would both be renamed to '{trans_name}'", names.0, names.1),
+            AstError::LocalsWithSameTransName { trans_name, names } => write!(f, "local variables '{}' and '{}' would both be renamed to '{trans_name}'", names.0, names.1),
+            AstError::CostumesWithSameTransName { trans_name, names } => write!(f, "costumes '{}' and '{}' would both be renamed to '{trans_name}'", names.0, names.1),
+            AstError::SoundsWithSameTransName { trans_name, names } => write!(f, "sounds '{}' and '{}' would both be renamed to '{trans_name}'", names.0, names.1),
+            AstError::BlocksWithSameTransName { trans_name, names } => write!(f, "custom blocks '{}' and '{}' would both be renamed to '{trans_name}'", names.0, names.1),
+            AstError::InputsWithSameName { name } => write!(f, "a custom block has multiple inputs named '{name}'"),
+            AstError::BlocksWithSameName { name, sigs } => write!(f, "custom blocks '{}' and '{}' are both named '{name}'", sigs.0, sigs.1),
+            AstError::CurrentlyUnsupported { msg } => write!(f, "{msg}"),
+        }
+    }
+}
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::ParseError(e) => {
+                write!(f, "the project could not be parsed: ")?;
+                write_parse_error(f, &e.kind)?;
+                if let Some(entity) = e.location.entity.as_deref() {
+                    write!(f, " (sprite '{entity}')")?;
+                }
+            }
+            CompileError::RoleCount { count } => write!(f, "the project has {count} roles, so the role to use must be given by name")?,
+            CompileError::UnknownRole { name } => write!(f, "the project has no role named '{name}'")?,
+            CompileError::RenameFailure { before } => write!(f, "'{before}' cannot be converted into a valid name")?,
+            CompileError::RenameConflict { before, after } => write!(f, "'{}' and '{}' would both be renamed to '{after}'", before.0, before.1)?,
+            CompileError::TransitionEmptyTarget { state_machine, state, .. } => write!(f, "state '{state}' of machine '{state_machine}' changes to a state with an empty name")?,
+            CompileError::UnsupportedBlock { state_machine, state, info, .. } => write!(f, "state '{state}' of machine '{state_machine}' uses an unsupported {} block", block_kind_name(info))?,
+            CompileError::NonTerminalTransition { state_machine, state, .. } => write!(f, "state '{state}' of machine '{state_machine}' changes state before the end of its script")?,
+            CompileError::MultipleHandlers { state_machine, state, .. } => write!(f, "state '{state}' of machine '{state_machine}' is handled by more than one script")?,
+            CompileError::ComplexTransitionName { state_machine, state, .. } => write!(f, "state '{state}' of machine '{state_machine}' changes to a state whose name is not a fixed value")?,
+            CompileError::VariadicBlocks { state_machine, state, .. } => write!(f, "state '{state}' of machine '{state_machine}' uses a block whose inputs are not listed directly")?,
+            CompileError::ActionsOutsideTransition { state_machine, state, .. } => write!(f, "state '{state}' of machine '{state_machine}' runs blocks on a path that does not change state")?,
+            CompileError::VariableOverlap { state_machines, variable } => write!(f, "variable '{variable}' is used by both machine '{}' and machine '{}'", state_machines.0, state_machines.1)?,
+            CompileError::TransitionForeignMachine { state_machine, state, foreign_machine, .. } => write!(f, "state '{state}' of machine '{state_machine}' changes the state of another machine '{foreign_machine}'")?,
+            CompileError::UnsupportedExport { state_machine, state, info } => write!(f, "state '{state}' of machine '{state_machine}' cannot be exported because it uses {info}")?,
+        }
+        match self.location() {
+            Some(location) if !location.entity.is_empty() => write!(f, " ({location})"),
+            _ => Ok(()),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for CompileError {}
+
+// block_path holds the index of each enclosing block within its script, interleaved with the index of the
+// nested script within that block (e.g. [2, 1, 0] is the first block in the else branch of the third block)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub entity: CompactString,
+    pub script: usize,
+    pub block_path: Vec<usize>,
+    pub collab_id: Option<CompactString>,
+}
+impl Location {
+    fn within(&mut self, outer: &Location) {
+        self.entity = outer.entity.clone();
+        self.script = outer.script;
+        self.block_path.splice(0..0, outer.block_path.iter().copied());
+        if self.collab_id.is_none() {
+            self.collab_id = outer.collab_id.clone();
+        }
+    }
+    fn at(info: &ast::BlockInfo) -> Self {
+        Location { collab_id: info.location.clone(), ..<_>::default() }
+    }
+    fn block(index: usize, info: &ast::BlockInfo) -> Self {
+        Location { block_path: vec![index], collab_id: info.location.clone(), ..<_>::default() }
+    }
+    fn nested(index: usize) -> Self {
+        Location { block_path: vec![index], ..<_>::default() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileWarning {
+    OmittedBlock { state_machine: CompactString, state: CompactString, kind: CompactString, location: Box<Location> },
+}
+impl CompileWarning {
+    pub fn location(&self) -> &Location {
+        match self {
+            CompileWarning::OmittedBlock { location, .. } => location,
+        }
+    }
+    fn within(&mut self, outer: &Location) {
+        match self {
+            CompileWarning::OmittedBlock { location, .. } => location.within(outer),
+        }
+    }
+}
+impl fmt::Display for CompileWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileWarning::OmittedBlock { state_machine, state, kind, location } => {
+                write!(f, "state '{state}' of machine '{state_machine}' uses an unsupported {kind} block, which was replaced by '?' ({location})")
+            }
+        }
+    }
+}
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sprite '{}', script {}", self.entity, self.script + 1)?;
+        if !self.block_path.is_empty() {
+            write!(f, ", block {}", self.block_path.iter().map(|x| x + 1).map(|x| x.to_string()).collect::<Vec<_>>().join("."))?;
+        }
+        Ok(())
+    }
+}
+
+fn block_kind<T: fmt::Debug>(block: &T) -> CompactString {
+    block_kind_name(&format_compact!("{block:?}")).into()
+}
+fn block_kind_name(info: &str) -> &str {
+    info.split(|x: char| !x.is_alphanumeric()).next().unwrap_or_default()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CompileDiagnostics {
+    pub project: Option<Project>,
+    pub errors: Vec<CompileError>,
+    pub warnings: Vec<CompileWarning>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VariableKind {
     Local, Input, Output,
 }
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Project {
     pub name: CompactString,
     pub role: CompactString,
     pub state_machines: BTreeMap<CompactString, StateMachine>,
 }
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StateMachine {
     pub variables: BTreeMap<CompactString, Variable>,
     pub states: BTreeMap<CompactString, State>,
     pub initial_state: Option<CompactString>,
     pub current_state: Option<CompactString>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub placeholders: BTreeSet<CompactString>,
 }
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Variable {
-    pub init: CompactString,
+    pub init: Expr,
     pub kind: VariableKind,
 }
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State {
     pub parent: Option<CompactString>,
     pub transitions: VecDeque<Transition>,
 }
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transition {
     pub ordered_condition: Condition,
     pub unordered_condition: Condition,
-    pub actions: VecDeque<CompactString>,
+    pub actions: VecDeque<Stmt>,
     pub new_state: Option<CompactString>,
 }
 
@@ -170,78 +412,113 @@ pub struct Settings {
 struct Context {
     variables: Vec<ast::VariableRef>,
     junctions: Vec<(CompactString, State)>,
+    warnings: Vec<CompileWarning>,
     settings: Settings,
 }
+impl Context {
+    fn locate_warnings(&mut self, start: usize, outer: &Location) {
+        for warning in self.warnings[start..].iter_mut() {
+            warning.within(outer);
+        }
+    }
+}
 
 fn prune_unreachable(transitions: &mut VecDeque<Transition>) {
     transitions.retain(|t| t.ordered_condition != Condition::constant(false) && t.unordered_condition != Condition::constant(false));
 }
 
-fn translate_value(state_machine: &str, state: &str, value: &ast::Value) -> Result<CompactString, CompileError> {
+fn translate_value(state_machine: &str, state: &str, value: &ast::Value) -> Result<Expr, CompileError> {
     Ok(match value {
-        ast::Value::String(x) => x.clone(),
-        ast::Value::Number(x) => x.to_compact_string(),
-        ast::Value::Bool(x) => if *x { "true" } else { "false" }.into(),
+        ast::Value::String(x) => match x.parse::<Expr>() {
+            Ok(Expr::Number(n)) if n == *x => Expr::Number(n),
+            _ => Expr::String(x.clone()),
+        }
+        ast::Value::Number(x) => Expr::Number(x.to_compact_string()),
+        ast::Value::Bool(x) => Expr::Bool(*x),
         ast::Value::Constant(x) => match x {
-            ast::Constant::E => core::f64::consts::E.to_compact_string(),
-            ast::Constant::Pi => core::f64::consts::PI.to_compact_string(),
+            ast::Constant::E => Expr::Number(core::f64::consts::E.to_compact_string()),
+            ast::Constant::Pi => Expr::Number(core::f64::consts::PI.to_compact_string()),
         }
-        x => return Err(CompileError::UnsupportedBlock { state_machine: state_machine.into(), state: state.into(), info: format_compact!("{x:?}") }),
+        x => return Err(CompileError::UnsupportedBlock { state_machine: state_machine.into(), state: state.into(), info: format_compact!("{x:?}"), location: <_>::default() }),
     })
 }
-fn translate_expr(state_machine: &str, state: &str, expr: &ast::Expr, context: &mut Context) -> Result<CompactString, CompileError> {
-    fn extract_fixed_variadic(state_machine: &str, state: &str, values: &ast::Expr, context: &mut Context) -> Result<Vec<CompactString>, CompileError> {
+fn translate_expr(state_machine: &str, state: &str, expr: &ast::Expr, context: &mut Context) -> Result<Expr, CompileError> {
+    fn extract_fixed_variadic(state_machine: &str, state: &str, values: &ast::Expr, context: &mut Context) -> Result<Vec<Expr>, CompileError> {
         match &values.kind {
             ast::ExprKind::MakeList { values } => Ok(values.iter().map(|x| translate_expr(state_machine, state, x, context)).collect::<Result<_,_>>()?),
             ast::ExprKind::Value(ast::Value::List(values, _)) => Ok(values.iter().map(|x| translate_value(state_machine, state, x)).collect::<Result<_,_>>()?),
-            _ => Err(CompileError::VariadicBlocks { state_machine: state_machine.into(), state: state.into() }),
+            _ => Err(CompileError::VariadicBlocks { state_machine: state_machine.into(), state: state.into(), location: Box::new(Location::at(&values.info)) }),
         }
     }
 
+    macro_rules! call {
+        ($func:ident : $($args:expr),+) => {
+            Expr::Call { func: Function::$func, args: vec![$(translate_expr(state_machine, state, $args, context)?),+] }
+        };
+    }
+    macro_rules! binary {
+        ($op:ident : $left:expr, $right:expr) => {
+            Expr::Binary { op: BinaryOp::$op, left: Box::new(translate_expr(state_machine, state, $left, context)?), right: Box::new(translate_expr(state_machine, state, $right, context)?) }
+        };
+    }
+
     Ok(match &expr.kind {
-        ast::ExprKind::Value(x) => translate_value(state_machine, state, x)?,
+        ast::ExprKind::Value(x) => translate_value(state_machine, state, x).map_err(|e| e.within(&Location::at(&expr.info)))?,
         ast::ExprKind::Variable { var } => {
             context.variables.push(var.clone());
-            var.trans_name.clone()
-        }
-        ast::ExprKind::Sin { value } => format_compact!("sind({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Cos { value } => format_compact!("cosd({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Tan { value } => format_compact!("tand({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Asin { value } => format_compact!("asind({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Acos { value } => format_compact!("acosd({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Atan { value } => format_compact!("atand({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Sqrt { value } => format_compact!("sqrt({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Floor { value } => format_compact!("floor({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Ceil { value } => format_compact!("ceil({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Round { value } => format_compact!("round({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Sign { value } => format_compact!("sign({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Neg { value } => format_compact!("-{}", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Abs { value } => format_compact!("abs({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Sub { left, right } => format_compact!("({} - {})", translate_expr(state_machine, state, left, context)?, translate_expr(state_machine, state, right, context)?),
-        ast::ExprKind::Div { left, right } => format_compact!("({} / {})", translate_expr(state_machine, state, left, context)?, translate_expr(state_machine, state, right, context)?),
-        ast::ExprKind::Mod { left, right } => format_compact!("mod({}, {})", translate_expr(state_machine, state, left, context)?, translate_expr(state_machine, state, right, context)?),
-        ast::ExprKind::Log { value, base } => format_compact!("(log({}) / log({}))", translate_expr(state_machine, state, value, context)?, translate_expr(state_machine, state, base, context)?),
-        ast::ExprKind::Atan2 { y, x } => format_compact!("atan2d({}, {})", translate_expr(state_machine, state, y, context)?, translate_expr(state_machine, state, x, context)?),
-        ast::ExprKind::Add { values } => punctuate(extract_fixed_variadic(state_machine,state, values, context)?.iter().map(|x| x.as_str()), " + ").map(|x| format_compact!("({})", x.0)).unwrap_or_else(|| "0".into()),
-        ast::ExprKind::Mul { values } => punctuate(extract_fixed_variadic(state_machine,state, values, context)?.iter().map(|x| x.as_str()), " * ").map(|x| format_compact!("({})", x.0)).unwrap_or_else(|| "1".into()),
-        ast::ExprKind::Pow { base, power } => format_compact!("({} ^ {})", translate_expr(state_machine, state, base, context)?, translate_expr(state_machine, state, power, context)?),
-        ast::ExprKind::Eq { left, right } => format_compact!("{} == {}", translate_expr(state_machine, state, left, context)?, translate_expr(state_machine, state, right, context)?),
-        ast::ExprKind::Neq { left, right } => format_compact!("{} ~= {}", translate_expr(state_machine, state, left, context)?, translate_expr(state_machine, state, right, context)?),
-        ast::ExprKind::Greater { left, right } => format_compact!("{} > {}", translate_expr(state_machine, state, left, context)?, translate_expr(state_machine, state, right, context)?),
-        ast::ExprKind::GreaterEq { left, right } => format_compact!("{} >= {}", translate_expr(state_machine, state, left, context)?, translate_expr(state_machine, state, right, context)?),
-        ast::ExprKind::Less { left, right } => format_compact!("{} < {}", translate_expr(state_machine, state, left, context)?, translate_expr(state_machine, state, right, context)?),
-        ast::ExprKind::LessEq { left, right } => format_compact!("{} <= {}", translate_expr(state_machine, state, left, context)?, translate_expr(state_machine, state, right, context)?),
-        ast::ExprKind::And { left, right } => format_compact!("{} & {}", translate_expr(state_machine, state, left, context)?, translate_expr(state_machine, state, right, context)?),
-        ast::ExprKind::Or { left, right } => format_compact!("({} | {})", translate_expr(state_machine, state, left, context)?, translate_expr(state_machine, state, right, context)?),
-        ast::ExprKind::Not { value } => format_compact!("~({})", translate_expr(state_machine, state, value, context)?),
-        ast::ExprKind::Timer => "t".into(),
-        ast::ExprKind::Random { a, b } => match (translate_expr(state_machine, state, a, context)?.as_str(), translate_expr(state_machine, state, b, context)?.as_str()) {
-            ("1", b) => format_compact!("randi({b})"),
-            (a, b) => format_compact!("randi([{a}, {b}])"),
+            Expr::Variable(var.trans_name.clone())
+        }
+        ast::ExprKind::Sin { value } => call!(Sin: value),
+        ast::ExprKind::Cos { value } => call!(Cos: value),
+        ast::ExprKind::Tan { value } => call!(Tan: value),
+        ast::ExprKind::Asin { value } => call!(Asin: value),
+        ast::ExprKind::Acos { value } => call!(Acos: value),
+        ast::ExprKind::Atan { value } => call!(Atan: value),
+        ast::ExprKind::Sqrt { value } => call!(Sqrt: value),
+        ast::ExprKind::Floor { value } => call!(Floor: value),
+        ast::ExprKind::Ceil { value } => call!(Ceil: value),
+        ast::ExprKind::Round { value } => call!(Round: value),
+        ast::ExprKind::Sign { value } => call!(Sign: value),
+        ast::ExprKind::Neg { value } => match translate_expr(state_machine, state, value, context)? {
+            Expr::Number(x) if !x.starts_with('-') => Expr::Number(format_compact!("-{x}")),
+            x => Expr::Neg { value: Box::new(x) },
+        }
+        ast::ExprKind::Abs { value } => call!(Abs: value),
+        ast::ExprKind::Sub { left, right } => binary!(Sub: left, right),
+        ast::ExprKind::Div { left, right } => binary!(Div: left, right),
+        ast::ExprKind::Mod { left, right } => call!(Mod: left, right),
+        ast::ExprKind::Log { value, base } => Expr::Binary { op: BinaryOp::Div, left: Box::new(call!(Log: value)), right: Box::new(call!(Log: base)) },
+        ast::ExprKind::Atan2 { y, x } => call!(Atan2: y, x),
+        ast::ExprKind::Add { values } => match extract_fixed_variadic(state_machine, state, values, context)? {
+            values if values.is_empty() => Expr::Number("0".into()),
+            values => Expr::Add { values },
+        }
+        ast::ExprKind::Mul { values } => match extract_fixed_variadic(state_machine, state, values, context)? {
+            values if values.is_empty() => Expr::Number("1".into()),
+            values => Expr::Mul { values },
+        }
+        ast::ExprKind::Pow { base, power } => binary!(Pow: base, power),
+        ast::ExprKind::Eq { left, right } => binary!(Eq: left, right),
+        ast::ExprKind::Neq { left, right } => binary!(Neq: left, right),
+        ast::ExprKind::Greater { left, right } => binary!(Greater: left, right),
+        ast::ExprKind::GreaterEq { left, right } => binary!(GreaterEq: left, right),
+        ast::ExprKind::Less { left, right } => binary!(Less: left, right),
+        ast::ExprKind::LessEq { left, right } => binary!(LessEq: left, right),
+        ast::ExprKind::And { left, right } => binary!(And: left, right),
+        ast::ExprKind::Or { left, right } => binary!(Or: left, right),
+        ast::ExprKind::Not { value } => Expr::Not { value: Box::new(translate_expr(state_machine, state, value, context)?) },
+        ast::ExprKind::Timer => Expr::Timer,
+        ast::ExprKind::Random { a, b } => match (translate_expr(state_machine, state, a, context)?, translate_expr(state_machine, state, b, context)?) {
+            (Expr::Number(a), b) if a == "1" => Expr::Call { func: Function::Randi, args: vec![b] },
+            (a, b) => Expr::Call { func: Function::Randi, args: vec![a, b] },
         }
         x => match context.settings.omit_unknown_blocks {
-            true => "?".into(),
-            false => return Err(CompileError::UnsupportedBlock { state_machine: state_machine.into(), state: state.into(), info: format_compact!("{x:?}") }),
+            true => {
+                let kind = match x { ast::ExprKind::UnknownBlock { name, .. } => name.clone(), x => block_kind(x) };
+                context.warnings.push(CompileWarning::OmittedBlock { state_machine: state_machine.into(), state: state.into(), kind, location: Box::new(Location::at(&expr.info)) });
+                Expr::Unknown
+            }
+            false => return Err(CompileError::UnsupportedBlock { state_machine: state_machine.into(), state: state.into(), info: format_compact!("{x:?}"), location: Box::new(Location::at(&expr.info)) }),
         }
     })
 }
@@ -255,20 +532,24 @@ fn translate_condition(state_machine: &str, state: &str, expr: &ast::Expr, conte
         _ => Condition::atom(translate_expr(state_machine, state, expr, context)?),
     })
 }
-fn parse_actions(state_machine: &str, state: &str, stmt: &ast::Stmt, context: &mut Context) -> Result<Vec<CompactString>, CompileError> {
+fn parse_actions(state_machine: &str, state: &str, stmt: &ast::Stmt, context: &mut Context) -> Result<Vec<Stmt>, CompileError> {
     Ok(match &stmt.kind {
         ast::StmtKind::Assign { var, value } => {
             context.variables.push(var.clone());
-            vec![format_compact!("{} = {}", var.trans_name, translate_expr(state_machine, state, value, context)?)]
+            vec![Stmt::Assign { var: var.trans_name.clone(), value: translate_expr(state_machine, state, value, context)? }]
         }
         ast::StmtKind::AddAssign { var, value } => {
             context.variables.push(var.clone());
-            vec![format_compact!("{} = {} + {}", var.trans_name, var.trans_name, translate_expr(state_machine, state, value, context)?)]
+            vec![Stmt::AddAssign { var: var.trans_name.clone(), value: translate_expr(state_machine, state, value, context)? }]
         }
-        ast::StmtKind::ResetTimer => vec!["t = 0".into()],
+        ast::StmtKind::ResetTimer => vec![Stmt::ResetTimer],
         x => match context.settings.omit_unknown_blocks {
-            true => vec!["?".into()],
-            false => return Err(CompileError::UnsupportedBlock { state_machine: state_machine.into(), state: state.into(), info: format_compact!("{x:?}") }),
+            true => {
+                let kind = match x { ast::StmtKind::UnknownBlock { name, .. } => name.clone(), x => block_kind(x) };
+                context.warnings.push(CompileWarning::OmittedBlock { state_machine: state_machine.into(), state: state.into(), kind, location: <_>::default() });
+                vec![Stmt::Unknown]
+            }
+            false => return Err(CompileError::UnsupportedBlock { state_machine: state_machine.into(), state: state.into(), info: format_compact!("{x:?}"), location: <_>::default() }),
         }
     })
 }
@@ -276,7 +557,7 @@ fn parse_transitions(state_machine: &str, state: &str, stmt: &ast::Stmt, termina
     fn parse_transition_target(state_machine: &str, state: &str, expr: &ast::Expr, context: &mut Context) -> Result<VecDeque<Transition>, CompileError> {
         Ok(match &expr.kind {
             ast::ExprKind::Value(ast::Value::String(x)) => match x.as_str() {
-                "" => return Err(CompileError::TransitionEmptyTarget { state_machine: state_machine.into(), state: state.into() }),
+                "" => return Err(CompileError::TransitionEmptyTarget { state_machine: state_machine.into(), state: state.into(), location: <_>::default() }),
                 _ => deque![Transition { ordered_condition: Condition::constant(true), unordered_condition: Condition::constant(true), actions: <_>::default(), new_state: Some(x.clone()) }],
             }
             ast::ExprKind::Conditional { condition, then, otherwise } => {
@@ -296,7 +577,7 @@ fn parse_transitions(state_machine: &str, state: &str, stmt: &ast::Stmt, termina
                 then_transitions.extend(otherwise_transitions);
                 then_transitions
             }
-            _ => return Err(CompileError::ComplexTransitionName { state_machine: state_machine.into(), state: state.into() }),
+            _ => return Err(CompileError::ComplexTransitionName { state_machine: state_machine.into(), state: state.into(), location: Box::new(Location::at(&expr.info)) }),
         })
     }
 
@@ -305,7 +586,7 @@ fn parse_transitions(state_machine: &str, state: &str, stmt: &ast::Stmt, termina
             ("smTransition", [var, value]) => match &var.kind {
                 ast::ExprKind::Value(ast::Value::String(var)) => match *var == state_machine {
                     true => Some((parse_transition_target(state_machine, state, value, context)?, Condition::constant(false), true)),
-                    false => return Err(CompileError::TransitionForeignMachine { state_machine: state_machine.into(), state: state.into(), foreign_machine: var.clone() }),
+                    false => return Err(CompileError::TransitionForeignMachine { state_machine: state_machine.into(), state: state.into(), foreign_machine: var.clone(), location: <_>::default() }),
                 }
                 _ => None,
             }
@@ -313,11 +594,13 @@ fn parse_transitions(state_machine: &str, state: &str, stmt: &ast::Stmt, termina
         }
         ast::StmtKind::Assign { var, value } if var.name == state_machine => match terminal {
             true => Some((parse_transition_target(state_machine, state, value, context)?, Condition::constant(false), true)),
-            false => return Err(CompileError::NonTerminalTransition { state_machine: state_machine.into(), state: state.into() }),
+            false => return Err(CompileError::NonTerminalTransition { state_machine: state_machine.into(), state: state.into(), location: <_>::default() }),
         }
         ast::StmtKind::If { condition, then } => {
             let condition = translate_condition(state_machine, state, condition, context)?;
-            let (mut transitions, body_terminal, volatile) = parse_stmts(state_machine, state, then, terminal, context, false)?;
+            let warnings = context.warnings.len();
+            let (mut transitions, body_terminal, volatile) = parse_stmts(state_machine, state, then, terminal, context, false).map_err(|e| e.within(&Location::nested(0)))?;
+            context.locate_warnings(warnings, &Location::nested(0));
 
             if volatile {
                 make_junction(state, &mut <_>::default(), &mut transitions, context);
@@ -340,8 +623,12 @@ fn parse_transitions(state_machine: &str, state: &str, stmt: &ast::Stmt, termina
         ast::StmtKind::IfElse { condition, then, otherwise } => {
             let condition = translate_condition(state_machine, state, condition, context)?;
 
-            let (mut transitions_1, body_terminal_1, volatile_1) = parse_stmts(state_machine, state, then, terminal, context, false)?;
-            let (mut transitions_2, body_terminal_2, volatile_2) = parse_stmts(state_machine, state, otherwise, terminal, context, false)?;
+            let warnings = context.warnings.len();
+            let (mut transitions_1, body_terminal_1, volatile_1) = parse_stmts(state_machine, state, then, terminal, context, false).map_err(|e| e.within(&Location::nested(0)))?;
+            context.locate_warnings(warnings, &Location::nested(0));
+            let warnings = context.warnings.len();
+            let (mut transitions_2, body_terminal_2, volatile_2) = parse_stmts(state_machine, state, otherwise, terminal, context, false).map_err(|e| e.within(&Location::nested(1)))?;
+            context.locate_warnings(warnings, &Location::nested(1));
 
             if volatile_1 {
                 make_junction(state, &mut <_>::default(), &mut transitions_1, context);
@@ -383,7 +670,7 @@ fn parse_transitions(state_machine: &str, state: &str, stmt: &ast::Stmt, termina
         _ => None,
     })
 }
-fn make_junction<'a>(state: &str, actions: &mut VecDeque<CompactString>, transitions: &mut VecDeque<Transition>, context: &'a mut Context) {
+fn make_junction(state: &str, actions: &mut VecDeque<Stmt>, transitions: &mut VecDeque<Transition>, context: &mut Context) {
     prune_unreachable(transitions);
 
     let junction = format_compact!("::junction-{}::", context.junctions.len());
@@ -402,7 +689,7 @@ fn make_junction<'a>(state: &str, actions: &mut VecDeque<CompactString>, transit
     transitions.push_front(Transition { ordered_condition: Condition::constant(true), unordered_condition: Condition::constant(true), actions: core::mem::take(actions), new_state: Some(junction.clone()) });
     context.junctions.push((junction, junction_state));
 }
-fn handle_actions(state_machine: &str, state: &str, actions: &mut VecDeque<CompactString>, transitions: &mut VecDeque<Transition>, terminal: bool, volatile: &mut bool, context: &mut Context) -> Result<(), CompileError> {
+fn handle_actions(state_machine: &str, state: &str, actions: &mut VecDeque<Stmt>, transitions: &mut VecDeque<Transition>, terminal: bool, volatile: &mut bool, context: &mut Context) -> Result<(), CompileError> {
     prune_unreachable(transitions);
 
     if !actions.is_empty() {
@@ -415,7 +702,7 @@ fn handle_actions(state_machine: &str, state: &str, actions: &mut VecDeque<Compa
             debug_assert_eq!(transitions.len(), 1);
             *volatile = false;
         } else {
-            return Err(CompileError::ActionsOutsideTransition { state_machine: state_machine.into(), state: state.into() });
+            return Err(CompileError::ActionsOutsideTransition { state_machine: state_machine.into(), state: state.into(), location: <_>::default() });
         }
     }
 
@@ -423,7 +710,7 @@ fn handle_actions(state_machine: &str, state: &str, actions: &mut VecDeque<Compa
     Ok(())
 }
 fn parse_stmts(state_machine: &str, state: &str, stmts: &[ast::Stmt], script_terminal: bool, context: &mut Context, top_level: bool) -> Result<(VecDeque<Transition>, bool, bool), CompileError> {
-    let mut actions: VecDeque<CompactString> = <_>::default();
+    let mut actions: VecDeque<Stmt> = <_>::default();
     let mut transitions: VecDeque<Transition> = <_>::default();
     let mut body_terminal = false;
     let mut volatile = false;
@@ -432,8 +719,8 @@ fn parse_stmts(state_machine: &str, state: &str, stmts: &[ast::Stmt], script_ter
         transitions.push_back(Transition { unordered_condition: Condition::constant(true), ordered_condition: Condition::constant(true), actions: <_>::default(), new_state: Some(state.into()) });
     }
 
-    let mut stmts = stmts.iter().rev().peekable();
-    while let Some(stmt) = stmts.peek() {
+    let mut stmts = stmts.iter().enumerate().rev().peekable();
+    while let Some((_, stmt)) = stmts.peek() {
         match &stmt.kind {
             ast::StmtKind::Return { value: _ } => (),
             _ => break,
@@ -443,10 +730,12 @@ fn parse_stmts(state_machine: &str, state: &str, stmts: &[ast::Stmt], script_ter
     }
 
     let mut last = true;
-    for stmt in stmts {
-        match parse_transitions(state_machine, state, stmt, (script_terminal || body_terminal) && last, context)? {
+    for (i, stmt) in stmts {
+        let located = |e: CompileError| e.within(&Location::block(i, &stmt.info));
+        let warnings = context.warnings.len();
+        match parse_transitions(state_machine, state, stmt, (script_terminal || body_terminal) && last, context).map_err(located)? {
             Some((sub_transitions, tail_condition, sub_body_terminal)) => {
-                handle_actions(state_machine, state, &mut actions, &mut transitions, script_terminal || body_terminal, &mut volatile, context)?;
+                handle_actions(state_machine, state, &mut actions, &mut transitions, script_terminal || body_terminal, &mut volatile, context).map_err(located)?;
                 debug_assert_eq!(actions.len(), 0);
 
                 if volatile {
@@ -463,8 +752,8 @@ fn parse_stmts(state_machine: &str, state: &str, stmts: &[ast::Stmt], script_ter
                 transitions.extend_front(sub_transitions.into_iter());
             }
             None => match &stmt.kind {
-                ast::StmtKind::Sleep { seconds } => {
-                    handle_actions(state_machine, state, &mut actions, &mut transitions, script_terminal || body_terminal, &mut volatile, context)?;
+                ast::StmtKind::Sleep { .. } | ast::StmtKind::WaitUntil { .. } => {
+                    handle_actions(state_machine, state, &mut actions, &mut transitions, script_terminal || body_terminal, &mut volatile, context).map_err(located)?;
                     debug_assert_eq!(actions.len(), 0);
 
                     match transitions.as_slices() {
@@ -476,7 +765,11 @@ fn parse_stmts(state_machine: &str, state: &str, stmts: &[ast::Stmt], script_ter
                         }
                     };
 
-                    let condition = Condition::atom(format_compact!("after({}, sec)", translate_expr(state_machine, state, seconds, context)?));
+                    let condition = match &stmt.kind {
+                        ast::StmtKind::Sleep { seconds } => Condition::atom(Expr::Call { func: Function::After, args: vec![translate_expr(state_machine, state, seconds, context).map_err(located)?] }),
+                        ast::StmtKind::WaitUntil { condition } => translate_condition(state_machine, state, condition, context).map_err(located)?,
+                        _ => unreachable!(),
+                    };
                     for transition in transitions.iter_mut() {
                         for target in [&mut transition.unordered_condition, &mut transition.ordered_condition] {
                             *target = target.clone() & condition.clone();
@@ -492,9 +785,10 @@ fn parse_stmts(state_machine: &str, state: &str, stmts: &[ast::Stmt], script_ter
 
                     volatile = true;
                 }
-                _ => actions.extend_front(parse_actions(state_machine, state, stmt, context)?.into_iter()),
+                _ => actions.extend_front(parse_actions(state_machine, state, stmt, context).map_err(located)?.into_iter()),
             }
         }
+        context.locate_warnings(warnings, &Location::block(i, &stmt.info));
         last = false;
     }
 
@@ -504,12 +798,162 @@ fn parse_stmts(state_machine: &str, state: &str, stmts: &[ast::Stmt], script_ter
     Ok((transitions, body_terminal, volatile))
 }
 
+fn pascal_ident(raw: &str) -> Result<CompactString, ()> {
+    let mut res = CompactString::default();
+    for part in ast::util::c_ident(raw)?.split('_') {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            res.extend(first.to_uppercase());
+            res.push_str(chars.as_str());
+        }
+    }
+    if res.is_empty() { Err(()) } else { Ok(res) }
+}
+
+fn visit_exprs<'a, F: FnMut(&'a Expr)>(state_machine: &'a StateMachine, f: &mut F) {
+    for variable in state_machine.variables.values() {
+        variable.init.visit(f);
+    }
+    for state in state_machine.states.values() {
+        for transition in state.transitions.iter() {
+            for atom in transition.ordered_condition.atoms().into_iter().chain(transition.unordered_condition.atoms()) {
+                atom.visit(f);
+            }
+            for value in transition.actions.iter().flat_map(Stmt::value) {
+                value.visit(f);
+            }
+        }
+    }
+}
+
+fn check_numeric_exprs(state_machine_name: &str, state_machine: &StateMachine) -> Result<(), CompileError> {
+    let check = |state: &str, expr: &Expr| {
+        let mut res = Ok(());
+        expr.visit(&mut |x| if res.is_ok() {
+            match x {
+                Expr::Unknown => res = Err(CompileError::UnsupportedExport { state_machine: state_machine_name.into(), state: state.into(), info: "an unknown block".into() }),
+                Expr::String(value) if !value.parse::<f64>().is_ok_and(f64::is_finite) => res = Err(CompileError::UnsupportedExport { state_machine: state_machine_name.into(), state: state.into(), info: format_compact!("the non-numeric value {value:?}") }),
+                _ => (),
+            }
+        });
+        res
+    };
+    for variable in state_machine.variables.values() {
+        check("<init>", &variable.init)?;
+    }
+    for (state_name, state) in state_machine.states.iter() {
+        for transition in state.transitions.iter() {
+            for atom in transition.ordered_condition.atoms().into_iter().chain(transition.unordered_condition.atoms()) {
+                check(state_name, atom)?;
+            }
+            for action in transition.actions.iter() {
+                match action {
+                    Stmt::Unknown => return Err(CompileError::UnsupportedExport { state_machine: state_machine_name.into(), state: state_name.clone(), info: "an unknown block".into() }),
+                    _ => if let Some(value) = action.value() { check(state_name, value)?; }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn transition_labels<'a>(state_name: &CompactString, state: &'a State) -> Vec<(&'a Transition, CompactString)> {
+    let included_transitions = state.transitions.iter().filter(|t| t.new_state.as_ref().unwrap_or(state_name) != state_name || !t.actions.is_empty() || t.ordered_condition != Condition::constant(true)).collect::<Vec<_>>();
+    let numbered = included_transitions.len() > 1;
+    included_transitions.into_iter().enumerate().map(|(i, transition)| {
+        let mut label = CompactString::default();
+        if transition.ordered_condition != Condition::constant(true) {
+            write!(label, "[{}]", transition.ordered_condition.display(Dialect::MATLAB)).unwrap();
+        }
+        for (j, action) in transition.actions.iter().enumerate() {
+            label.push_str(if j != 0 { "; " } else if label.is_empty() { "/ " } else { " / " });
+            write!(label, "{}", action.display(Dialect::MATLAB)).unwrap();
+        }
+        if numbered {
+            label = if label.is_empty() { format_compact!("{}", i + 1) } else { format_compact!("{}: {label}", i + 1) };
+        }
+        (transition, label)
+    }).collect()
+}
+
+fn xml_escape(raw: &str) -> CompactString {
+    let mut res = CompactString::default();
+    for ch in raw.chars() {
+        match ch {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&apos;"),
+            _ => res.push(ch),
+        }
+    }
+    res
+}
+
+#[test]
+fn test_xml_escape() {
+    assert_eq!(xml_escape("a < b && c > \"d\" 'e'"), "a &lt; b &amp;&amp; c &gt; &quot;d&quot; &apos;e&apos;");
+}
+
+struct Writer {
+    res: CompactString,
+    depth: usize,
+}
+impl Writer {
+    fn line(&mut self, line: &str) {
+        for _ in 0..self.depth {
+            self.res.push_str("  ");
+        }
+        self.res.push_str(line);
+        self.res.push('\n');
+    }
+    fn open(&mut self, line: &str) {
+        self.line(line);
+        self.depth += 1;
+    }
+    fn close(&mut self, line: &str) {
+        self.depth -= 1;
+        self.line(line);
+    }
+}
+
 fn dot_id(name: &str) -> dot::Id {
     dot::Id::Escaped(format!("{name:?}"))
 }
 
 impl Project {
     pub fn compile(xml: &str, role: Option<&str>, settings: Settings) -> Result<Project, CompileError> {
+        Ok(Self::compile_impl(xml, role, settings, None)?.0)
+    }
+    pub fn compile_with_diagnostics(xml: &str, role: Option<&str>, settings: Settings) -> CompileDiagnostics {
+        let mut errors = vec![];
+        let (project, warnings) = match Self::compile_impl(xml, role, settings, Some(&mut errors)) {
+            Ok((project, warnings)) => (Some(project), warnings),
+            Err(e) => {
+                errors.push(e);
+                (None, vec![])
+            }
+        };
+        CompileDiagnostics { project, errors, warnings }
+    }
+    fn compile_impl(xml: &str, role: Option<&str>, settings: Settings, mut errors: Option<&mut Vec<CompileError>>) -> Result<(Project, Vec<CompileWarning>), CompileError> {
+        // in diagnostics mode, errors local to a single script (or variable) are collected instead of aborting compilation
+        macro_rules! recover {
+            ($e:expr, $fallback:expr) => {
+                match $e {
+                    Ok(x) => x,
+                    Err(e) => match errors.as_mut() {
+                        Some(errors) => {
+                            errors.push(e);
+                            $fallback
+                        }
+                        None => return Err(e),
+                    }
+                }
+            };
+        }
+
         let parser = ast::Parser {
             name_transformer: Box::new(ast::util::c_ident),
             ..Default::default()
@@ -526,9 +970,10 @@ impl Project {
             }
         };
 
+        let mut warnings = vec![];
         let mut state_machines: BTreeMap<CompactString, (StateMachine, Context)> = <_>::default();
         for entity in role.entities.iter() {
-            for script in entity.scripts.iter() {
+            for (script_index, script) in entity.scripts.iter().enumerate() {
                 let (state_machine_name, state_name) = match script.hat.as_ref().map(|x| &x.kind) {
                     Some(ast::HatKind::When { condition }) => match &condition.kind {
                         ast::ExprKind::Eq { left, right } => match (&left.kind, &right.kind) {
@@ -549,13 +994,27 @@ impl Project {
                 };
 
                 let (state_machine, context) = state_machines.entry(state_machine_name.clone()).or_insert_with(|| {
-                    (StateMachine { variables: <_>::default(), states: <_>::default(), initial_state: None, current_state: None }, Context { variables: vec![], junctions: vec![], settings })
+                    (StateMachine { variables: <_>::default(), states: <_>::default(), initial_state: None, current_state: None, placeholders: <_>::default() }, Context { variables: vec![], junctions: vec![], warnings: vec![], settings })
                 });
+                let location = Location { entity: entity.name.clone(), script: script_index, block_path: vec![], collab_id: script.hat.as_ref().and_then(|x| x.info.location.clone()) };
                 if state_machine.states.contains_key(state_name.as_str()) {
-                    return Err(CompileError::MultipleHandlers { state_machine: state_machine_name.clone(), state: state_name.clone() });
+                    recover!(Err(CompileError::MultipleHandlers { state_machine: state_machine_name.clone(), state: state_name.clone(), location: Box::new(location.clone()) }), continue);
                 }
 
-                let (transitions, _, _) = parse_stmts(state_machine_name, state_name, &script.stmts, true, context, true)?;
+                let (variables, junctions) = (context.variables.len(), context.junctions.len());
+                let (transitions, _, _) = recover!(parse_stmts(state_machine_name, state_name, &script.stmts, true, context, true).map_err(|e| e.within(&location)), {
+                    context.variables.truncate(variables);
+                    context.junctions.truncate(junctions);
+                    context.warnings.clear();
+                    continue
+                });
+
+                let mut script_warnings = core::mem::take(&mut context.warnings);
+                script_warnings.sort_by(|a, b| a.location().block_path.cmp(&b.location().block_path));
+                for mut warning in script_warnings {
+                    warning.within(&location);
+                    warnings.push(warning);
+                }
                 assert!(state_machine.states.insert(state_name.clone(), State { parent: None, transitions }).is_none());
             }
         }
@@ -574,7 +1033,7 @@ impl Project {
                 assert!(state_machine.states.insert(name, junction).is_none());
             }
             for variable in context.variables {
-                state_machine.variables.insert(variable.trans_name, Variable { init: "0".into(), kind: VariableKind::Local });
+                state_machine.variables.insert(variable.trans_name, Variable { init: Expr::Number("0".into()), kind: VariableKind::Local });
             }
             (state_machine_name, state_machine)
         }).collect::<BTreeMap<_,_>>();
@@ -582,29 +1041,40 @@ impl Project {
         for state_machine in state_machines.values_mut() {
             let target_states: Vec<_> = state_machine.states.values().flat_map(|s| s.transitions.iter().flat_map(|t| t.new_state.clone())).collect();
             for target_state in target_states {
-                state_machine.states.entry(target_state.clone()).or_insert_with(|| State {
+                if state_machine.states.contains_key(&target_state) {
+                    continue;
+                }
+                state_machine.placeholders.insert(target_state.clone());
+                state_machine.states.insert(target_state.clone(), State {
                     parent: None,
                     transitions: deque![Transition { unordered_condition: Condition::constant(true), ordered_condition: Condition::constant(true), actions: <_>::default(), new_state: Some(target_state) }]
                 });
             }
         }
 
-        let mut var_inits: BTreeMap<&CompactString, &ast::Expr> = BTreeMap::new();
+        let mut var_inits: BTreeMap<&CompactString, (&ast::Expr, Location)> = BTreeMap::new();
         let mut var_kinds: BTreeMap<&CompactString, VariableKind> = BTreeMap::new();
+        let mut marked_vars: Vec<(&CompactString, &CompactString)> = vec![];
         for entity in role.entities.iter() {
-            for script in entity.scripts.iter() {
+            for (script_index, script) in entity.scripts.iter().enumerate() {
                 if let Some(ast::HatKind::OnFlag) = script.hat.as_ref().map(|x| &x.kind) {
-                    for stmt in script.stmts.iter() {
+                    // marked variables belong to the machine most recently set in the same script
+                    let mut last_machine = None;
+                    for (stmt_index, stmt) in script.stmts.iter().enumerate() {
                         match &stmt.kind {
                             ast::StmtKind::Assign { var, value } => match state_machines.get_mut(&var.name) {
-                                Some(state_machine) => if let ast::ExprKind::Value(ast::Value::String(value)) = &value.kind {
-                                    if state_machine.states.contains_key(value) { state_machine.initial_state = Some(value.clone()); }
+                                Some(state_machine) => {
+                                    last_machine = Some(&var.name);
+                                    if let ast::ExprKind::Value(ast::Value::String(value)) = &value.kind {
+                                        if state_machine.states.contains_key(value) { state_machine.initial_state = Some(value.clone()); }
+                                    }
                                 }
-                                None => { var_inits.insert(&var.trans_name, value); }
+                                None => { var_inits.insert(&var.trans_name, (value, Location { entity: entity.name.clone(), script: script_index, ..Location::block(stmt_index, &stmt.info) })); }
                             }
                             ast::StmtKind::UnknownBlock { name, args } => match (name.as_str(), args.as_slice()) {
                                 ("smTransition", [var, value]) => if let (ast::ExprKind::Value(ast::Value::String(var)), ast::ExprKind::Value(ast::Value::String(value))) = (&var.kind, &value.kind) {
                                     if let  Some(state_machine) = state_machines.get_mut(var) {
+                                        last_machine = Some(var);
                                         if state_machine.states.contains_key(value) { state_machine.initial_state = Some(value.clone()); }
                                     }
                                 }
@@ -616,6 +1086,9 @@ impl Project {
                                         _ => continue,
                                     };
                                     var_kinds.insert(var, kind);
+                                    if let Some(state_machine) = last_machine {
+                                        marked_vars.push((state_machine, var));
+                                    }
                                 }
                                 _ => (),
                             }
@@ -626,7 +1099,13 @@ impl Project {
             }
         }
 
-        let mut var_inits_context = Context { variables: vec![], junctions: vec![], settings };
+        for (state_machine, var) in marked_vars {
+            if !state_machines.values().any(|x| x.variables.contains_key(var)) {
+                state_machines.get_mut(state_machine).unwrap().variables.insert(var.clone(), Variable { init: Expr::Number("0".into()), kind: VariableKind::Local });
+            }
+        }
+
+        let mut var_inits_context = Context { variables: vec![], junctions: vec![], warnings: vec![], settings };
         for (state_machine_name, state_machine) in state_machines.iter_mut() {
             if let Some(ast::Value::String(init)) = role.globals.iter().find(|g| g.def.name == state_machine_name).map(|g| &g.init) {
                 if state_machine.states.contains_key(init) {
@@ -635,8 +1114,16 @@ impl Project {
             }
 
             for (var, info) in state_machine.variables.iter_mut() {
-                if let Some(&init) = var_inits.get(var) {
-                    info.init = translate_expr(state_machine_name, "<init>", init, &mut var_inits_context)?;
+                if let Some((init, location)) = var_inits.get(var) {
+                    info.init = recover!(translate_expr(state_machine_name, "<init>", init, &mut var_inits_context).map_err(|e| e.within(location)), {
+                        var_inits_context.variables.clear();
+                        var_inits_context.warnings.clear();
+                        info.init.clone()
+                    });
+                    for mut warning in var_inits_context.warnings.drain(..) {
+                        warning.within(location);
+                        warnings.push(warning);
+                    }
                 }
                 if let Some(&kind) = var_kinds.get(var) {
                     info.kind = kind;
@@ -650,10 +1137,10 @@ impl Project {
         let mut machines = state_machines.iter();
         while let Some(machine_1) = machines.next() {
             if let Some((machine_2, var)) = machines.clone().find_map(|machine_2| machine_1.1.variables.keys().find(|&k| machine_2.1.variables.contains_key(k)).map(|x| (machine_2, x))) {
-                return Err(CompileError::VariableOverlap { state_machines: (machine_1.0.clone(), machine_2.0.clone()), variable: var.clone() });
+                recover!(Err(CompileError::VariableOverlap { state_machines: (machine_1.0.clone(), machine_2.0.clone()), variable: var.clone() }), ());
             }
             if let Some(var) = machine_1.1.variables.keys().find(|&x| state_machines.contains_key(x)) {
-                return Err(CompileError::VariableOverlap { state_machines: (machine_1.0.clone(), var.clone()), variable: var.clone() });
+                recover!(Err(CompileError::VariableOverlap { state_machines: (machine_1.0.clone(), var.clone()), variable: var.clone() }), ());
             }
         }
 
@@ -667,18 +1154,38 @@ impl Project {
             }
         }
 
-        Ok(Project { name: proj.name, role: role.name.clone(), state_machines })
+        Ok((Project { name: proj.name, role: role.name.clone(), state_machines }, warnings))
     }
     pub fn to_graphviz(&self) -> dot::Graph {
-        let stmts = self.state_machines.iter().map(|(name, state_machine)| {
+        self.graphviz(None)
+    }
+    pub fn to_graphviz_with_layout(&self, settings: LayoutSettings) -> dot::Graph {
+        self.graphviz(Some(settings))
+    }
+    fn graphviz(&self, settings: Option<LayoutSettings>) -> dot::Graph {
+        // positions are pinned for neato (dot ignores pos), so each machine is shifted to the right of the previous ones to keep them apart
+        let mut next_offset = 0.0;
+        let mut stmts = self.state_machines.iter().map(|(name, state_machine)| {
             let node_id = |state: &str| dot::NodeId(if !state.is_empty() { dot_id(&format!("{name} {state}")) } else { dot_id(name) }, None);
+            let layout = settings.map(|x| state_machine.layout(x));
+            let offset = next_offset;
+            if let (Some(settings), Some(layout)) = (settings, layout.as_ref()) {
+                next_offset += layout.states.values().map(|&(x, _, w, _)| (x + w) as f64).chain(layout.transitions.values().map(|x| x.0)).fold(0.0, f64::max) + settings.padding.0 as f64;
+            }
+            let center = |state: &str| layout.as_ref().map(|layout| {
+                let (x, y, w, h) = layout.states[state];
+                (offset + x as f64 + w as f64 / 2.0, y as f64 + h as f64 / 2.0)
+            });
 
             let mut stmts = vec![];
             if let Some(init) = state_machine.initial_state.as_ref() {
-                let attributes = vec![
+                let mut attributes = vec![
                     dot::Attribute(dot::Id::Plain("shape".into()), dot::Id::Plain("point".into())),
                     dot::Attribute(dot::Id::Plain("width".into()), dot::Id::Plain("0.1".into())),
                 ];
+                if let Some(&(x, y, w, _)) = layout.as_ref().and_then(|x| x.states.get(init.as_str())) {
+                    attributes.push(dot::Attribute(dot::Id::Plain("pos".into()), dot_id(&format!("{},{}!", offset + x as f64 + w as f64 / 2.0, 30.0 - y as f64))));
+                }
                 stmts.push(dot::Stmt::Node(dot::Node { id: node_id(""), attributes }));
                 stmts.push(dot::Stmt::Edge(dot::Edge { ty: dot::EdgeTy::Pair(dot::Vertex::N(node_id("")), dot::Vertex::N(node_id(init))), attributes: vec![] }));
             }
@@ -697,6 +1204,10 @@ impl Project {
                     attributes.push(dot::Attribute(dot::Id::Plain("style".into()), dot::Id::Plain("filled".into())));
                 }
 
+                if let Some((x, y)) = center(state_name) {
+                    attributes.push(dot::Attribute(dot::Id::Plain("pos".into()), dot_id(&format!("{x},{}!", -y))));
+                }
+
                 stmts.push(dot::Stmt::Node(dot::Node { id: node_id(state_name), attributes }));
             }
             for (state_name, state) in state_machine.states.iter() {
@@ -707,140 +1218,23 @@ impl Project {
                     _ => |i, t| t.map(|t| dot_id(&format!(" {}: {t} ", i + 1))).unwrap_or_else(|| dot_id(&format!(" {} ", i + 1))),
                 };
                 for (i, transition) in included_transitions.iter().enumerate() {
-                    stmts.push(dot::Stmt::Edge(dot::Edge { ty: dot::EdgeTy::Pair(dot::Vertex::N(node_id(state_name)), dot::Vertex::N(node_id(transition.new_state.as_ref().unwrap_or(state_name)))), attributes: vec![
-                        dot::Attribute(dot::Id::Plain("label".into()), labeler(i, if transition.ordered_condition != Condition::constant(true) { Some(transition.ordered_condition.to_string()) } else { None })),
-                    ] }));
-                }
-            }
-            dot::Stmt::Subgraph(dot::Subgraph { id: dot_id(name), stmts })
-        }).collect();
-        dot::Graph::DiGraph { id: dot_id(&self.name), strict: false, stmts }
-    }
-    pub fn to_stateflow(&self) -> Result<CompactString, CompileError> {
-        let mut rename_pool = RenamePool::new(ast::util::c_ident);
-        let mut rename = move |x| rename_pool.rename(x);
-        let model_name = rename(&self.name)?;
-
-        let state_size = (100, 100);
-        let junction_size = (100, 20);
-        let padding = (100, 100);
-
-        fn stateflow_escape(full: &str) -> String {
-            let mut res = String::new();
-            for line in full.lines() {
-                if !res.is_empty() {
-                    res.push_str(" + newline + ");
-                }
-                write!(res, "{line:?}").unwrap();
-            }
-            res
-        }
-
-        let mut res = CompactString::default();
-        writeln!(res, "sfnew {model_name}").unwrap();
-        for (state_machine_idx, (state_machine_name, state_machine)) in self.state_machines.iter().enumerate() {
-            let state_numbers: BTreeMap<&str, usize> = state_machine.states.iter().enumerate().map(|x| (x.1.0.as_str(), x.0)).collect();
-            let parent_state_numbers: BTreeMap<&str, usize> = state_machine.states.iter().filter(|x| x.1.parent.is_none()).enumerate().map(|x| (x.1.0.as_str(), x.0)).collect();
-
-            if state_machine_idx == 0 {
-                writeln!(res, "chart = find(sfroot, \"-isa\", \"Stateflow.Chart\")").unwrap();
-                writeln!(res, "chart.Name = {state_machine_name:?}").unwrap();
-            } else {
-                writeln!(res, "chart = add_block(\"sflib/Chart\", {:?})", format!("{model_name}/{state_machine_name}")).unwrap();
-            }
-
-            let included_transitions = state_machine.states.iter().map(|(state_name, state)| {
-                (state as *const State, state.transitions.iter().filter(|t| t.new_state.as_ref().unwrap_or(state_name) != state_name || !t.actions.is_empty() || t.ordered_condition != Condition::constant(true)).collect::<Vec<_>>())
-            }).collect::<BTreeMap<_,_>>();
-
-            let entry_actions = state_machine.states.iter().filter(|s| s.1.parent.is_none()).map(|(state_name, _)| {
-                let actions = match state_machine.initial_state.as_ref().map(|i| i != state_name).unwrap_or(true) {
-                    true => common_suffix(state_machine.states.iter().flat_map(|(n, s)| included_transitions[&(s as _)].iter().filter(|t| t.new_state.as_ref().unwrap_or(n) == state_name)).map(|t| t.actions.iter())),
-                    false => <_>::default(),
-                };
-                (state_name, actions)
-            }).collect::<BTreeMap<_,_>>();
-            let exit_actions = state_machine.states.iter().filter(|s| s.1.parent.is_none()).map(|(state_name, state)| {
-                let actions = common_suffix(included_transitions[&(state as _)].iter().map(|t| t.actions.iter().take(t.actions.len() - entry_actions.get(t.new_state.as_ref().unwrap_or(state_name)).map(|x| x.len()).unwrap_or(0))));
-                (state_name, actions)
-            }).collect::<BTreeMap<_,_>>();
-
-            let mut child_counts: BTreeMap<&str, usize> = Default::default();
-            for (state_idx, (state_name, state)) in state_machine.states.iter().enumerate() {
-                match state.parent.as_deref() {
-                    Some(parent) => {
-                        *child_counts.entry(parent).or_default() += 1;
-                        writeln!(res, "s{state_idx} = Stateflow.State(chart)").unwrap();
-                        writeln!(res, "s{state_idx}.LabelString = \"{}_{}\"", rename(parent)?, child_counts[parent]).unwrap();
-                        writeln!(res, "s{state_idx}.Position = [{}, {}, {}, {}]", parent_state_numbers[parent] * (state_size.0 + padding.0) + (state_size.0 - junction_size.0) / 2, state_size.1 + padding.1 * child_counts[parent], junction_size.0, junction_size.1).unwrap();
-                    }
-                    None => {
-                        let mut label = rename(state_name)?;
-                        match entry_actions.get(state_name) {
-                            Some(actions) if !actions.is_empty() => {
-                                label.push_str("\nentry:");
-                                for action in actions {
-                                    write!(label, " {action};").unwrap();
-                                }
-                            }
-                            _ => (),
+                    let mut attributes = vec![
+                        dot::Attribute(dot::Id::Plain("label".into()), labeler(i, if transition.ordered_condition != Condition::constant(true) { Some(transition.ordered_condition.display(Dialect::MATLAB).to_string()) } else { None })),
+                    ];
+                    if let Some(layout) = layout.as_ref() {
+                        let index = state.transitions.iter().position(|x| core::ptr::eq(x, *transition)).unwrap();
+                        if let Some((x, y)) = layout.transitions.get(&(state_name.clone(), index)) {
+                            attributes.push(dot::Attribute(dot::Id::Plain("lp".into()), dot_id(&format!("{},{}", offset + x, -y))));
                         }
-                        match exit_actions.get(state_name) {
-                            Some(actions) if !actions.is_empty() => {
-                                label.push_str("\nexit:");
-                                for action in actions {
-                                    write!(label, " {action};").unwrap();
-                                }
-                            }
-                            _ => (),
-                        }
-
-                        writeln!(res, "s{state_idx} = Stateflow.State(chart)").unwrap();
-                        writeln!(res, "s{state_idx}.LabelString = {}", stateflow_escape(&label)).unwrap();
-                        writeln!(res, "s{state_idx}.Position = [{}, {}, {}, {}]", parent_state_numbers[state_name.as_str()] * (state_size.0 + padding.0), 0, state_size.0, state_size.1).unwrap();
                     }
+                    stmts.push(dot::Stmt::Edge(dot::Edge { ty: dot::EdgeTy::Pair(dot::Vertex::N(node_id(state_name)), dot::Vertex::N(node_id(transition.new_state.as_ref().unwrap_or(state_name)))), attributes }));
                 }
             }
-            for (state_idx, (state_name, state)) in state_machine.states.iter().enumerate() {
-                for transition in &included_transitions[&(state as _)] {
-                    writeln!(res, "t = Stateflow.Transition(chart)").unwrap();
-                    writeln!(res, "t.Source = s{state_idx}").unwrap();
-                    writeln!(res, "t.Destination = s{}", state_numbers[transition.new_state.as_deref().unwrap_or(state_name)]).unwrap();
-
-                    let mut label = CompactString::default();
-                    if transition.unordered_condition != Condition::constant(true) {
-                        write!(label, "[{}]", transition.unordered_condition).unwrap();
-                    }
-
-                    let entry_action_count = entry_actions.get(transition.new_state.as_ref().unwrap_or(state_name)).map(|x| x.len()).unwrap_or(0);
-                    let exit_action_count = exit_actions.get(state_name).map(|x| x.len()).unwrap_or(0);
-                    if transition.actions.len() > entry_action_count + exit_action_count {
-                        label.push('{');
-                        for action in transition.actions.iter().take(transition.actions.len() - (entry_action_count + exit_action_count)) {
-                            write!(label, "{action};").unwrap();
-                        }
-                        label.push('}');
-                    }
-
-                    writeln!(res, "t.LabelString = {label:?}").unwrap();
-                }
-            }
-            if let Some(initial_state) = state_machine.initial_state.as_deref() {
-                writeln!(res, "t = Stateflow.Transition(chart)").unwrap();
-                writeln!(res, "t.Destination = s{}", state_numbers[initial_state]).unwrap();
-                writeln!(res, "t.DestinationOClock = 0").unwrap();
-                writeln!(res, "t.SourceEndpoint = t.DestinationEndpoint - [0 30]").unwrap();
-                writeln!(res, "t.Midpoint = t.DestinationEndpoint - [0 15]").unwrap();
-            }
-            for (var, info) in state_machine.variables.iter() {
-                writeln!(res, "d = Stateflow.Data(chart)").unwrap();
-                writeln!(res, "d.Name = {var:?}").unwrap();
-                writeln!(res, "d.Props.InitialValue = {:?}", info.init).unwrap();
-                writeln!(res, "d.Scope = \"{:?}\"", info.kind).unwrap();
-            }
+            dot::Stmt::Subgraph(dot::Subgraph { id: dot_id(name), stmts })
+        }).collect::<Vec<_>>();
+        if settings.is_some() {
+            stmts.insert(0, dot::Stmt::Attribute(dot::Attribute(dot::Id::Plain("layout".into()), dot::Id::Plain("neato".into()))));
         }
-        debug_assert_eq!(res.chars().next_back(), Some('\n'));
-        res.pop();
-        Ok(res)
+        dot::Graph::DiGraph { id: dot_id(&self.name), strict: false, stmts }
     }
 }
@@ -1,10 +1,15 @@
+use std::process::ExitCode;
+
 use clap::{Parser, ValueEnum};
 
 use netsblox_stateflow::*;
 
 #[derive(ValueEnum, Clone)]
 enum Mode {
-    Raw, Graphviz, Stateflow,
+    Raw,
+    #[cfg(feature = "serde")]
+    Json,
+    Graphviz, Stateflow, Scxml, Mermaid, Plantuml, C, Rust, Python, Netsblox,
 }
 
 #[derive(Parser)]
@@ -13,17 +18,73 @@ struct Args {
 
     #[clap(short, long)]
     mode: Mode,
-}
 
-fn main() {
-    let Args { input, mode } = Args::parse();
+    #[clap(long)]
+    connective_junctions: bool,
 
-    let content = std::fs::read_to_string(&input).unwrap();
-    let project = Project::compile(&content, None, Settings { omit_unknown_blocks: true }).unwrap();
+    #[clap(long)]
+    layout: bool,
+}
 
+fn print_output(project: &Project, mode: Mode, stateflow_settings: StateflowSettings, layout: Option<LayoutSettings>) -> Result<(), CompileError> {
     match mode {
         Mode::Raw => println!("{project:?}"),
-        Mode::Graphviz => println!("{}", graphviz::print(project.to_graphviz(), &mut Default::default())),
-        Mode::Stateflow => println!("{}", project.to_stateflow().unwrap()),
+        #[cfg(feature = "serde")]
+        Mode::Json => println!("{}", serde_json::to_string_pretty(&project).unwrap()),
+        Mode::Graphviz => match layout {
+            Some(layout) => println!("{}", graphviz::print(project.to_graphviz_with_layout(layout), &mut Default::default())),
+            None => println!("{}", graphviz::print(project.to_graphviz(), &mut Default::default())),
+        }
+        Mode::Stateflow => println!("{}", project.to_stateflow_with(stateflow_settings)?),
+        Mode::Scxml => println!("{}", project.to_scxml()?),
+        Mode::Mermaid => for (i, diagram) in project.to_mermaid()?.values().enumerate() {
+            if i != 0 { println!(); }
+            println!("{diagram}");
+        }
+        Mode::Rust => for (name, code) in project.to_rust()? {
+            println!("// ---- {name} ----\n{code}");
+        }
+        Mode::Plantuml => println!("{}", project.to_plantuml()?),
+        Mode::Python => println!("{}", project.to_python()?),
+        Mode::Netsblox => println!("{}", project.to_netsblox()?),
+        Mode::C => {
+            let code = project.to_c()?;
+            println!("/* {} */\n{}\n/* source */\n{}", code.header_name, code.header, code.source);
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let Args { input, mode, connective_junctions, layout } = Args::parse();
+    let layout = layout.then(LayoutSettings::default);
+    let stateflow_settings = StateflowSettings { connective_junctions, layout };
+
+    let content = match std::fs::read_to_string(&input) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("error: failed to read {input}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let CompileDiagnostics { project, errors, warnings } = Project::compile_with_diagnostics(&content, None, Settings { omit_unknown_blocks: true });
+    for warning in warnings.iter() {
+        eprintln!("warning: {warning}");
+    }
+    for error in errors.iter() {
+        eprintln!("error: {error}");
+    }
+    let project = match (project, errors.is_empty()) {
+        (Some(project), true) => project,
+        _ => return ExitCode::FAILURE,
+    };
+
+    match print_output(&project, mode, stateflow_settings, layout) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
     }
 }
@@ -0,0 +1,59 @@
+use alloc::collections::BTreeMap;
+use alloc::fmt::Write as _;
+
+use netsblox_ast as ast;
+use netsblox_ast::compact_str::CompactString;
+
+use crate::*;
+
+fn mermaid_escape(raw: &str) -> CompactString {
+    let mut res = CompactString::default();
+    for ch in raw.chars() {
+        match ch {
+            ';' => res.push_str("#59;"),
+            '"' => res.push_str("#quot;"),
+            '\n' => res.push(' '),
+            _ => res.push(ch),
+        }
+    }
+    res
+}
+
+impl Project {
+    pub fn to_mermaid(&self) -> Result<BTreeMap<CompactString, CompactString>, CompileError> {
+        let mut res = BTreeMap::new();
+        for (state_machine_name, state_machine) in self.state_machines.iter() {
+            let mut rename_pool = RenamePool::new(ast::util::c_ident);
+            let state_ids = state_machine.states.keys().map(|x| Ok((x.as_str(), rename_pool.rename(x)?))).collect::<Result<BTreeMap<_,_>, CompileError>>()?;
+
+            let mut diagram = CompactString::new("stateDiagram-v2\n");
+            for (state_name, state) in state_machine.states.iter() {
+                match state.parent {
+                    Some(_) => writeln!(diagram, "    state {} <<choice>>", state_ids[state_name.as_str()]).unwrap(),
+                    None => writeln!(diagram, "    state \"{}\" as {}", mermaid_escape(state_name), state_ids[state_name.as_str()]).unwrap(),
+                }
+            }
+            if let Some(init) = state_machine.initial_state.as_deref() {
+                writeln!(diagram, "    [*] --> {}", state_ids[init]).unwrap();
+            }
+            for (state_name, state) in state_machine.states.iter() {
+                for (transition, label) in transition_labels(state_name, state) {
+                    write!(diagram, "    {} --> {}", state_ids[state_name.as_str()], state_ids[transition.new_state.as_deref().unwrap_or(state_name)]).unwrap();
+                    if !label.is_empty() {
+                        write!(diagram, " : {}", mermaid_escape(&label)).unwrap();
+                    }
+                    diagram.push('\n');
+                }
+            }
+            if let Some(current) = state_machine.current_state.as_deref() {
+                writeln!(diagram, "    classDef current font-weight:bold,stroke-width:3px").unwrap();
+                writeln!(diagram, "    class {} current", state_ids[current]).unwrap();
+            }
+
+            debug_assert_eq!(diagram.chars().next_back(), Some('\n'));
+            diagram.pop();
+            res.insert(state_machine_name.clone(), diagram);
+        }
+        Ok(res)
+    }
+}
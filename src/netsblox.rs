@@ -0,0 +1,312 @@
+use netsblox_ast::compact_str::{CompactString, format_compact};
+
+use crate::*;
+
+fn literal(w: &mut Writer, value: &str) {
+    w.line(&format_compact!("<l>{}</l>", xml_escape(value)));
+}
+
+fn monadic(w: &mut Writer, state_machine: &str, state: &str, func: &str, value: &Expr) -> Result<(), CompileError> {
+    w.open("<block s=\"reportMonadic\">");
+    w.line(&format_compact!("<l><option>{func}</option></l>"));
+    write_expr(w, state_machine, state, value)?;
+    w.close("</block>");
+    Ok(())
+}
+
+fn write_block(w: &mut Writer, state_machine: &str, state: &str, name: &str, args: &[&Expr]) -> Result<(), CompileError> {
+    w.open(&format_compact!("<block s=\"{name}\">"));
+    for arg in args {
+        write_expr(w, state_machine, state, arg)?;
+    }
+    w.close("</block>");
+    Ok(())
+}
+
+fn write_variadic(w: &mut Writer, state_machine: &str, state: &str, name: &str, values: &[Expr]) -> Result<(), CompileError> {
+    w.open(&format_compact!("<block s=\"{name}\">"));
+    w.open("<list>");
+    for value in values {
+        write_expr(w, state_machine, state, value)?;
+    }
+    w.close("</list>");
+    w.close("</block>");
+    Ok(())
+}
+
+fn write_expr(w: &mut Writer, state_machine: &str, state: &str, expr: &Expr) -> Result<(), CompileError> {
+    match expr {
+        Expr::Number(x) => match x.strip_prefix('-') {
+            Some(x) => monadic(w, state_machine, state, "neg", &Expr::Number(x.into()))?,
+            None => literal(w, x),
+        }
+        Expr::Bool(x) => w.line(&format_compact!("<l><bool>{x}</bool></l>")),
+        Expr::String(x) => literal(w, x),
+        Expr::Variable(x) => w.line(&format_compact!("<block var=\"{}\"/>", xml_escape(x))),
+        Expr::Timer => w.line("<block s=\"getTimer\"/>"),
+        Expr::Neg { value } => monadic(w, state_machine, state, "neg", value)?,
+        Expr::Not { value } => write_block(w, state_machine, state, "reportNot", &[value])?,
+        Expr::Add { values } => write_variadic(w, state_machine, state, "reportVariadicSum", values)?,
+        Expr::Mul { values } => write_variadic(w, state_machine, state, "reportVariadicProduct", values)?,
+        Expr::Binary { op: BinaryOp::Div, left, right } => match (&**left, &**right) {
+            (Expr::Call { func: Function::Log, args: value }, Expr::Call { func: Function::Log, args: base }) if value.len() == 1 && matches!(base.as_slice(), [Expr::Number(_)]) => {
+                let func = match &base[0] {
+                    Expr::Number(x) => match x.parse::<f64>() {
+                        Ok(x) if x == core::f64::consts::E => Some("ln"),
+                        Ok(2.0) => Some("lg"),
+                        Ok(10.0) => Some("log"),
+                        _ => None,
+                    }
+                    _ => None,
+                };
+                match func {
+                    Some(func) => monadic(w, state_machine, state, func, &value[0])?,
+                    None => write_block(w, state_machine, state, "reportQuotient", &[left, right])?,
+                }
+            }
+            _ => write_block(w, state_machine, state, "reportQuotient", &[left, right])?,
+        }
+        Expr::Binary { op, left, right } => {
+            let name = match op {
+                BinaryOp::Sub => "reportDifference",
+                BinaryOp::Div => unreachable!(),
+                BinaryOp::Pow => "reportPower",
+                BinaryOp::Eq => "reportEquals",
+                BinaryOp::Neq => "reportNotEquals",
+                BinaryOp::Less => "reportLessThan",
+                BinaryOp::LessEq => "reportLessThanOrEquals",
+                BinaryOp::Greater => "reportGreaterThan",
+                BinaryOp::GreaterEq => "reportGreaterThanOrEquals",
+                BinaryOp::And => "reportAnd",
+                BinaryOp::Or => "reportOr",
+            };
+            write_block(w, state_machine, state, name, &[left, right])?;
+        }
+        Expr::Call { func, args } => match (func, args.as_slice()) {
+            (Function::Sin, [x]) => monadic(w, state_machine, state, "sin", x)?,
+            (Function::Cos, [x]) => monadic(w, state_machine, state, "cos", x)?,
+            (Function::Tan, [x]) => monadic(w, state_machine, state, "tan", x)?,
+            (Function::Asin, [x]) => monadic(w, state_machine, state, "asin", x)?,
+            (Function::Acos, [x]) => monadic(w, state_machine, state, "acos", x)?,
+            (Function::Atan, [x]) => monadic(w, state_machine, state, "atan", x)?,
+            (Function::Sqrt, [x]) => monadic(w, state_machine, state, "sqrt", x)?,
+            (Function::Floor, [x]) => monadic(w, state_machine, state, "floor", x)?,
+            (Function::Ceil, [x]) => monadic(w, state_machine, state, "ceiling", x)?,
+            (Function::Sign, [x]) => monadic(w, state_machine, state, "sign", x)?,
+            (Function::Abs, [x]) => monadic(w, state_machine, state, "abs", x)?,
+            (Function::Log, [x]) => monadic(w, state_machine, state, "ln", x)?,
+            (Function::Round, [x]) => write_block(w, state_machine, state, "reportRound", &[x])?,
+            (Function::Atan2, [y, x]) => write_block(w, state_machine, state, "reportAtan2", &[y, x])?,
+            (Function::Mod, [a, b]) => write_block(w, state_machine, state, "reportModulus", &[a, b])?,
+            (Function::Randi, [b]) => write_block(w, state_machine, state, "reportRandom", &[&Expr::Number("1".into()), b])?,
+            (Function::Randi, [a, b]) => write_block(w, state_machine, state, "reportRandom", &[a, b])?,
+            _ => return Err(CompileError::UnsupportedExport { state_machine: state_machine.into(), state: state.into(), info: format_compact!("the call {expr}") }),
+        }
+        Expr::Unknown => return Err(CompileError::UnsupportedExport { state_machine: state_machine.into(), state: state.into(), info: "an unknown block".into() }),
+    }
+    Ok(())
+}
+
+fn write_condition(w: &mut Writer, state_machine: &str, state: &str, condition: &RawCondition) -> Result<(), CompileError> {
+    match condition {
+        RawCondition::Const(x) => w.line(&format_compact!("<l><bool>{x}</bool></l>")),
+        RawCondition::Atom(x) => write_expr(w, state_machine, state, x)?,
+        RawCondition::Not(x) => {
+            w.open("<block s=\"reportNot\">");
+            write_condition(w, state_machine, state, x)?;
+            w.close("</block>");
+        }
+        RawCondition::And(a, b) | RawCondition::Or(a, b) => {
+            w.open(if matches!(condition, RawCondition::And(..)) { "<block s=\"reportAnd\">" } else { "<block s=\"reportOr\">" });
+            write_condition(w, state_machine, state, a)?;
+            write_condition(w, state_machine, state, b)?;
+            w.close("</block>");
+        }
+    }
+    Ok(())
+}
+
+fn write_set_var(w: &mut Writer, state_machine: &str, state: &str, var: &str, value: &Expr) -> Result<(), CompileError> {
+    w.open("<block s=\"doSetVar\">");
+    literal(w, var);
+    write_expr(w, state_machine, state, value)?;
+    w.close("</block>");
+    Ok(())
+}
+
+fn write_transition(w: &mut Writer, state_machine_name: &str, state_machine: &StateMachine, state: &str, transition: &Transition, depth: usize) -> Result<(), CompileError> {
+    for action in transition.actions.iter() {
+        match action {
+            Stmt::Assign { var, value } => write_set_var(w, state_machine_name, state, var, value)?,
+            Stmt::AddAssign { var, value } => {
+                w.open("<block s=\"doChangeVar\">");
+                literal(w, var);
+                write_expr(w, state_machine_name, state, value)?;
+                w.close("</block>");
+            }
+            Stmt::ResetTimer => w.line("<block s=\"doResetTimer\"/>"),
+            Stmt::Unknown => return Err(CompileError::UnsupportedExport { state_machine: state_machine_name.into(), state: state.into(), info: "an unknown block".into() }),
+        }
+    }
+    match transition.new_state.as_deref() {
+        Some(new_state) if state_machine.states[new_state].parent.is_some() && depth < state_machine.states.len() => write_state(w, state_machine_name, state_machine, state, &state_machine.states[new_state], depth + 1)?,
+        Some(new_state) => write_set_var(w, state_machine_name, state, state_machine_name, &Expr::String(new_state.into()))?,
+        None if depth == 0 => write_set_var(w, state_machine_name, state, state_machine_name, &Expr::String(state.into()))?,
+        None => (),
+    }
+    Ok(())
+}
+
+fn conjuncts<'a>(condition: &'a RawCondition, res: &mut Vec<&'a RawCondition>) {
+    match condition {
+        RawCondition::And(a, b) => {
+            conjuncts(a, res);
+            conjuncts(b, res);
+        }
+        RawCondition::Const(true) => (),
+        x => res.push(x),
+    }
+}
+
+fn write_state(w: &mut Writer, state_machine_name: &str, state_machine: &StateMachine, state_name: &str, state: &State, depth: usize) -> Result<(), CompileError> {
+    let is_noop = |t: &Transition| t.new_state.is_none() && t.actions.is_empty();
+    let mut transitions = state.transitions.iter().collect::<Vec<_>>();
+
+    // a final transition whose fallback was pruned must keep its guard, or recompiling would derive a weaker unordered condition
+    let (guarded, noop);
+    if let Some((last, init)) = transitions.split_last() {
+        let derived = init.iter().fold(Condition::constant(true), |a, t| a & !t.ordered_condition.clone());
+        let (mut have, mut want) = (vec![], vec![]);
+        conjuncts(derived.raw(), &mut have);
+        conjuncts(last.unordered_condition.raw(), &mut want);
+        let guard = want.into_iter().filter(|x| !have.contains(x)).map(|x| Condition::from(x.clone())).reduce(|a, b| a & b);
+        if let (0, false, Some(guard)) = (depth, is_noop(last), guard) {
+            guarded = Transition { ordered_condition: guard.clone(), unordered_condition: guard, actions: last.actions.clone(), new_state: last.new_state.clone() };
+            noop = Transition { ordered_condition: Condition::constant(true), unordered_condition: Condition::constant(true), actions: <_>::default(), new_state: None };
+            transitions.pop();
+            transitions.extend([&guarded, &noop]);
+        }
+    }
+
+    if let [first, last] = transitions.as_slice() {
+        if let RawCondition::Atom(Expr::Call { func: Function::After, args }) = first.ordered_condition.raw() {
+            if let ([seconds], true) = (args.as_slice(), is_noop(last)) {
+                w.open("<block s=\"doWait\">");
+                write_expr(w, state_machine_name, state_name, seconds)?;
+                w.close("</block>");
+                return write_transition(w, state_machine_name, state_machine, state_name, first, depth);
+            }
+        }
+        if depth > 0 && is_noop(last) {
+            w.open("<block s=\"doWaitUntil\">");
+            write_condition(w, state_machine_name, state_name, first.ordered_condition.raw())?;
+            w.close("</block>");
+            return write_transition(w, state_machine_name, state_machine, state_name, first, depth);
+        }
+    }
+
+    fn write_chain(w: &mut Writer, state_machine_name: &str, state_machine: &StateMachine, state_name: &str, transitions: &[&Transition], depth: usize) -> Result<(), CompileError> {
+        match transitions {
+            [] => Ok(()),
+            [last] if last.new_state.is_none() && last.actions.is_empty() => Ok(()),
+            [last] => write_transition(w, state_machine_name, state_machine, state_name, last, depth),
+            [first, rest @ ..] => {
+                let exits = first.new_state.is_some() || depth == 0;
+                w.open(if exits { "<block s=\"doIf\">" } else { "<block s=\"doIfElse\">" });
+                write_condition(w, state_machine_name, state_name, first.ordered_condition.raw())?;
+                w.open("<script>");
+                write_transition(w, state_machine_name, state_machine, state_name, first, depth)?;
+                if exits && !matches!(rest, [last] if last.new_state.is_none() && last.actions.is_empty()) {
+                    w.line("<block s=\"doReport\"><l></l></block>");
+                }
+                w.close("</script>");
+                if !exits {
+                    w.open("<script>");
+                    write_chain(w, state_machine_name, state_machine, state_name, rest, depth)?;
+                    w.close("</script>");
+                }
+                w.close("</block>");
+                match exits {
+                    true => write_chain(w, state_machine_name, state_machine, state_name, rest, depth),
+                    false => Ok(()),
+                }
+            }
+        }
+    }
+    write_chain(w, state_machine_name, state_machine, state_name, &transitions, depth)
+}
+
+impl Project {
+    pub fn to_netsblox(&self) -> Result<CompactString, CompileError> {
+        let mut w = Writer { res: CompactString::default(), depth: 0 };
+        w.open(&format_compact!("<room name=\"{}\" app=\"netsblox-stateflow\">", xml_escape(&self.name)));
+        w.open(&format_compact!("<role name=\"{}\">", xml_escape(&self.role)));
+        w.open(&format_compact!("<project name=\"{}\" app=\"netsblox-stateflow\" version=\"2.4.8\">", xml_escape(&self.role)));
+        w.open("<stage name=\"Stage\" width=\"480\" height=\"360\" costume=\"0\" color=\"255,255,255,1\" tempo=\"60\" threadsafe=\"false\" penlog=\"false\" volume=\"100\" pan=\"0\" lines=\"round\" ternary=\"false\" hyperops=\"true\" codify=\"false\" inheritance=\"false\" sublistIDs=\"false\" scheduled=\"false\">");
+        w.open("<sprites>");
+        w.open("<sprite name=\"Sprite\" idx=\"1\" x=\"0\" y=\"0\" heading=\"90\" scale=\"1\" volume=\"100\" pan=\"0\" rotation=\"1\" draggable=\"true\" costume=\"0\" color=\"80,80,80,1\" pen=\"tip\">");
+        w.open("<scripts>");
+
+        w.open("<script>");
+        w.line("<block s=\"receiveGo\"/>");
+        for (state_machine_name, state_machine) in self.state_machines.iter() {
+            match state_machine.initial_state.as_deref() {
+                Some(initial_state) => write_set_var(&mut w, state_machine_name, "<init>", state_machine_name, &Expr::String(initial_state.into()))?,
+                // a no-op assignment so the variables marked below are attached to this machine
+                None if !state_machine.variables.is_empty() => write_set_var(&mut w, state_machine_name, "<init>", state_machine_name, &Expr::Variable(state_machine_name.clone()))?,
+                None => (),
+            }
+            for (var, info) in state_machine.variables.iter() {
+                write_set_var(&mut w, state_machine_name, "<init>", var, &info.init)?;
+                let kind = match info.kind {
+                    VariableKind::Local => "local",
+                    VariableKind::Input => "input",
+                    VariableKind::Output => "output",
+                };
+                w.open("<block s=\"smMarkVar\">");
+                literal(&mut w, var);
+                literal(&mut w, kind);
+                w.close("</block>");
+            }
+        }
+        w.close("</script>");
+
+        for (state_machine_name, state_machine) in self.state_machines.iter() {
+            for (state_name, state) in state_machine.states.iter().filter(|x| x.1.parent.is_none() && !state_machine.placeholders.contains(x.0)) {
+                w.open("<script>");
+                w.open("<block s=\"receiveCondition\">");
+                w.open("<block s=\"reportEquals\">");
+                w.line(&format_compact!("<block var=\"{}\"/>", xml_escape(state_machine_name)));
+                literal(&mut w, state_name);
+                w.close("</block>");
+                w.close("</block>");
+                write_state(&mut w, state_machine_name, state_machine, state_name, state, 0)?;
+                w.close("</script>");
+            }
+        }
+
+        w.close("</scripts>");
+        w.close("</sprite>");
+        w.close("</sprites>");
+        w.close("</stage>");
+        w.open("<variables>");
+        for (state_machine_name, state_machine) in self.state_machines.iter() {
+            w.open(&format_compact!("<variable name=\"{}\">", xml_escape(state_machine_name)));
+            literal(&mut w, state_machine.current_state.as_deref().unwrap_or("0"));
+            w.close("</variable>");
+            for var in state_machine.variables.keys() {
+                w.open(&format_compact!("<variable name=\"{}\">", xml_escape(var)));
+                literal(&mut w, "0");
+                w.close("</variable>");
+            }
+        }
+        w.close("</variables>");
+        w.close("</project>");
+        w.close("</role>");
+        w.close("</room>");
+
+        let mut res = w.res;
+        res.pop();
+        Ok(res)
+    }
+}
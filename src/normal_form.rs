@@ -0,0 +1,104 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::*;
+use crate::bdd::{Builder, Op, FALSE};
+
+type Cube = BTreeMap<usize, bool>;
+
+fn cube_node(builder: &mut Builder, cube: &Cube) -> usize {
+    let mut res = builder.build(&RawCondition::Const(true));
+    for (&var, &value) in cube.iter() {
+        let literal = builder.literal(var, value);
+        res = builder.apply(Op::And, res, literal);
+    }
+    res
+}
+
+fn minimize(builder: &mut Builder, f: usize, positive: bool) -> Vec<Cube> {
+    let not_f = builder.not(f);
+    let mut cubes = builder.cubes(f);
+
+    for cube in cubes.iter_mut() {
+        for var in cube.keys().copied().collect::<Vec<_>>() {
+            let value = cube.remove(&var).unwrap();
+            let expanded = cube_node(builder, cube);
+            if builder.apply(Op::And, expanded, not_f) != FALSE {
+                cube.insert(var, value);
+            }
+        }
+    }
+    cubes.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    cubes.dedup();
+
+    let mut i = 0;
+    while i < cubes.len() {
+        let mut others = FALSE;
+        for (j, cube) in cubes.iter().enumerate() {
+            if j != i {
+                let cube = cube_node(builder, cube);
+                others = builder.apply(Op::Or, others, cube);
+            }
+        }
+        let uncovered = builder.not(others);
+        match builder.apply(Op::And, f, uncovered) {
+            FALSE => { cubes.remove(i); }
+            _ => i += 1,
+        }
+    }
+
+    cubes.sort_by_key(|cube| (cube.len(), cube.iter().map(|(&var, &value)| (var, value != positive)).collect::<Vec<_>>()));
+    cubes
+}
+
+impl Condition {
+    pub fn to_dnf(&self) -> RawCondition {
+        let atoms = self.atoms();
+        let mut builder = Builder::new(&atoms);
+        let f = builder.build(self.raw());
+        let atoms = atoms.into_iter().collect::<Vec<_>>();
+
+        minimize(&mut builder, f, true).into_iter().map(|cube| {
+            cube.into_iter().map(|(var, value)| match value {
+                true => RawCondition::Atom(atoms[var].clone()),
+                false => !RawCondition::Atom(atoms[var].clone()),
+            }).reduce(|a, b| a & b).unwrap_or(RawCondition::Const(true))
+        }).reduce(|a, b| a | b).unwrap_or(RawCondition::Const(false))
+    }
+    pub fn to_cnf(&self) -> RawCondition {
+        let atoms = self.atoms();
+        let mut builder = Builder::new(&atoms);
+        let f = builder.build(self.raw());
+        let not_f = builder.not(f);
+        let atoms = atoms.into_iter().collect::<Vec<_>>();
+
+        minimize(&mut builder, not_f, false).into_iter().map(|cube| {
+            cube.into_iter().map(|(var, value)| match value {
+                true => !RawCondition::Atom(atoms[var].clone()),
+                false => RawCondition::Atom(atoms[var].clone()),
+            }).reduce(|a, b| a | b).unwrap_or(RawCondition::Const(false))
+        }).reduce(|a, b| a & b).unwrap_or(RawCondition::Const(true))
+    }
+}
+
+#[test]
+fn test_normal_forms() {
+    let cases = [
+        ("a", "a", "a"),
+        ("true", "true", "true"),
+        ("a & ~a", "false", "false"),
+        ("(a & b) | (a & ~b & c)", "(a & b) | (a & c)", "a & (b | c)"),
+        ("(a | b) & (a | c)", "a | (b & c)", "(a | b) & (a | c)"),
+        ("(a & ~b) | (~a & b)", "(a & ~b) | (~a & b)", "(a | b) & (~a | ~b)"),
+        ("~(a & b & c)", "~a | ~b | ~c", "~a | ~b | ~c"),
+        ("(a & b) | (~a & c) | (b & c)", "(a & b) | (~a & c)", "(a | c) & (~a | b)"),
+        ("(x < 10 & y > 2) | ~(x < 10)", "~(x < 10) | y > 2", "~(x < 10) | y > 2"),
+    ];
+    for (src, dnf, cnf) in cases {
+        let cond = src.parse::<Condition>().unwrap();
+        assert_eq!(cond.to_dnf().to_string(), dnf, "{src}");
+        assert_eq!(cond.to_cnf().to_string(), cnf, "{src}");
+        assert!(Condition::from(cond.to_dnf()).equivalent(&cond));
+        assert!(Condition::from(cond.to_cnf()).equivalent(&cond));
+    }
+}
@@ -0,0 +1,59 @@
+use alloc::collections::BTreeMap;
+use alloc::fmt::Write as _;
+
+use netsblox_ast as ast;
+use netsblox_ast::compact_str::{CompactString, format_compact};
+
+use crate::*;
+
+fn plantuml_escape(raw: &str) -> CompactString {
+    raw.replace('"', "'").replace('\n', "\\n").into()
+}
+
+impl Project {
+    pub fn to_plantuml(&self) -> Result<CompactString, CompileError> {
+        let mut rename_pool = RenamePool::new(ast::util::c_ident);
+        let mut rename = move |x: &str| rename_pool.rename(x);
+
+        let mut res = CompactString::new("@startuml\n");
+        writeln!(res, "title {}", plantuml_escape(&self.name)).unwrap();
+        for (state_machine_name, state_machine) in self.state_machines.iter() {
+            let state_machine_id = rename(state_machine_name)?;
+            let state_ids = state_machine.states.keys().map(|state_name| Ok((state_name.as_str(), rename(&format_compact!("{state_machine_name} {state_name}"))?))).collect::<Result<BTreeMap<_,_>, CompileError>>()?;
+
+            writeln!(res, "state \"{}\" as {state_machine_id} {{", plantuml_escape(state_machine_name)).unwrap();
+            for (state_name, state) in state_machine.states.iter() {
+                match state.parent {
+                    Some(_) => writeln!(res, "  state {} <<choice>>", state_ids[state_name.as_str()]).unwrap(),
+                    None => match state_machine.current_state.as_ref() == Some(state_name) {
+                        true => writeln!(res, "  state \"{}\" as {} #lightgray", plantuml_escape(state_name), state_ids[state_name.as_str()]).unwrap(),
+                        false => writeln!(res, "  state \"{}\" as {}", plantuml_escape(state_name), state_ids[state_name.as_str()]).unwrap(),
+                    }
+                }
+            }
+            if let Some(init) = state_machine.initial_state.as_deref() {
+                writeln!(res, "  [*] --> {}", state_ids[init]).unwrap();
+            }
+            for (state_name, state) in state_machine.states.iter() {
+                for (transition, label) in transition_labels(state_name, state) {
+                    write!(res, "  {} --> {}", state_ids[state_name.as_str()], state_ids[transition.new_state.as_deref().unwrap_or(state_name)]).unwrap();
+                    if !label.is_empty() {
+                        write!(res, " : {}", plantuml_escape(&label)).unwrap();
+                    }
+                    res.push('\n');
+                }
+            }
+            res.push_str("}\n");
+
+            if !state_machine.variables.is_empty() {
+                writeln!(res, "note right of {state_machine_id}").unwrap();
+                for (var, info) in state_machine.variables.iter() {
+                    writeln!(res, "  {var} : {:?} = {}", info.kind, info.init.display(Dialect::MATLAB)).unwrap();
+                }
+                res.push_str("end note\n");
+            }
+        }
+        res.push_str("@enduml");
+        Ok(res)
+    }
+}
@@ -0,0 +1,152 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::fmt::Write as _;
+use alloc::vec::Vec;
+
+use netsblox_ast as ast;
+use netsblox_ast::compact_str::{CompactString, format_compact};
+
+use crate::*;
+
+const PYTHON_DIALECT: Dialect<'static> = Dialect { variable_prefix: "self.", function_prefix: "_sf_", timer: "self._timer", state_time: "self._state_time", ..Dialect::new(Language::Python) };
+
+fn python_helper(func: Function) -> Option<&'static str> {
+    Some(match func {
+        Function::Sin => "def _sf_sind(x):\n    return math.sin(math.radians(x))",
+        Function::Cos => "def _sf_cosd(x):\n    return math.cos(math.radians(x))",
+        Function::Tan => "def _sf_tand(x):\n    return math.tan(math.radians(x))",
+        Function::Asin => "def _sf_asind(x):\n    return math.degrees(math.asin(x))",
+        Function::Acos => "def _sf_acosd(x):\n    return math.degrees(math.acos(x))",
+        Function::Atan => "def _sf_atand(x):\n    return math.degrees(math.atan(x))",
+        Function::Atan2 => "def _sf_atan2d(y, x):\n    return math.degrees(math.atan2(y, x))",
+        Function::Sqrt => "def _sf_sqrt(x):\n    return math.sqrt(x)",
+        Function::Floor => "def _sf_floor(x):\n    return math.floor(x)",
+        Function::Ceil => "def _sf_ceil(x):\n    return math.ceil(x)",
+        Function::Round => "def _sf_round(x):\n    return math.copysign(math.floor(abs(x) + 0.5), x)",
+        Function::Sign => "def _sf_sign(x):\n    return (x > 0) - (x < 0)",
+        Function::Abs => "def _sf_abs(x):\n    return abs(x)",
+        Function::Log => "def _sf_log(x):\n    return math.log(x)",
+        Function::Mod => "def _sf_mod(a, b):\n    return a if b == 0 else a - math.floor(a / b) * b",
+        Function::Randi => "def _sf_randi(a, b):\n    return random.randint(math.ceil(min(a, b)), math.floor(max(a, b)))",
+        Function::After => return None,
+    })
+}
+
+fn python_list<'a, I: Iterator<Item = &'a CompactString>>(names: I) -> CompactString {
+    let mut res = CompactString::new("[");
+    for (i, name) in names.enumerate() {
+        if i != 0 { res.push_str(", "); }
+        write!(res, "'{}'", Expr::Variable(name.clone()).display(Dialect::new(Language::Python))).unwrap();
+    }
+    res.push(']');
+    res
+}
+
+impl Project {
+    pub fn to_python(&self) -> Result<CompactString, CompileError> {
+        let mut class_rename_pool = RenamePool::new(|x| pascal_ident(x).map(|x| if matches!(x.as_str(), "None" | "True" | "False") { format_compact!("{x}_") } else { x }));
+
+        let mut functions = BTreeSet::new();
+        for (state_machine_name, state_machine) in self.state_machines.iter() {
+            check_numeric_exprs(state_machine_name, state_machine)?;
+            visit_exprs(state_machine, &mut |expr| if let Expr::Call { func, .. } = expr { functions.insert(*func); });
+        }
+        let helpers = functions.iter().flat_map(|&f| python_helper(f)).collect::<Vec<_>>();
+
+        let mut res = CompactString::default();
+        writeln!(res, "# generated from project {:?}", self.name).unwrap();
+        writeln!(res, "import enum").unwrap();
+        if !helpers.is_empty() {
+            writeln!(res, "import math").unwrap();
+        }
+        if functions.contains(&Function::Randi) {
+            writeln!(res, "import random").unwrap();
+        }
+        for helper in helpers {
+            write!(res, "\n\n{helper}\n").unwrap();
+        }
+
+        for (state_machine_name, state_machine) in self.state_machines.iter() {
+            let class_name = class_rename_pool.rename(state_machine_name)?;
+            let mut rename_pool = RenamePool::new(|x| ast::util::c_ident(x).map(|x| x.to_uppercase()));
+            let members = state_machine.states.keys().map(|x| Ok((x.as_str(), rename_pool.rename(x)?))).collect::<Result<BTreeMap<_,_>, CompileError>>()?;
+            if let Some(var) = state_machine.variables.keys().find(|x| matches!(x.as_str(), "state" | "step" | "outputs")) {
+                return Err(CompileError::RenameConflict { before: (var.clone(), format_compact!("{class_name}.{var}")), after: format_compact!("self.{var}") });
+            }
+
+            writeln!(res, "\n").unwrap();
+            writeln!(res, "class {class_name}:").unwrap();
+            writeln!(res, "    class State(enum.Enum):").unwrap();
+            for (state_name, member) in members.iter() {
+                writeln!(res, "        {member} = {state_name:?}").unwrap();
+            }
+            res.push('\n');
+            writeln!(res, "    INPUTS = {}", python_list(state_machine.variables.iter().filter(|x| x.1.kind == VariableKind::Input).map(|x| x.0))).unwrap();
+            writeln!(res, "    OUTPUTS = {}", python_list(state_machine.variables.iter().filter(|x| x.1.kind == VariableKind::Output).map(|x| x.0))).unwrap();
+            res.push('\n');
+
+            let initial_state = state_machine.initial_state.as_deref().or(state_machine.current_state.as_deref()).or_else(|| state_machine.states.iter().find(|s| s.1.parent.is_none()).map(|s| s.0.as_str()));
+            writeln!(res, "    def __init__(self):").unwrap();
+            if let Some(initial_state) = initial_state {
+                writeln!(res, "        self.state = {class_name}.State.{}", members[initial_state]).unwrap();
+            }
+            writeln!(res, "        self._state_time = 0").unwrap();
+            writeln!(res, "        self._timer = 0").unwrap();
+            for (var, info) in state_machine.variables.iter() {
+                writeln!(res, "        {}", Stmt::Assign { var: var.clone(), value: info.init.clone() }.display(PYTHON_DIALECT)).unwrap();
+            }
+            res.push('\n');
+
+            writeln!(res, "    def step(self, inputs, dt):").unwrap();
+            writeln!(res, "        for name, value in inputs.items():").unwrap();
+            writeln!(res, "            if name not in {class_name}.INPUTS:").unwrap();
+            writeln!(res, "                raise KeyError(f'{{name!r}} is not an input variable')").unwrap();
+            writeln!(res, "            setattr(self, name, value)").unwrap();
+            writeln!(res, "        self._timer += dt").unwrap();
+            writeln!(res, "        self._state_time += dt").unwrap();
+            writeln!(res, "        for _ in range({}):", state_machine.states.len() + 1).unwrap();
+            for (i, (state_name, state)) in state_machine.states.iter().enumerate() {
+                writeln!(res, "            {} self.state == {class_name}.State.{}:", if i == 0 { "if" } else { "elif" }, members[state_name.as_str()]).unwrap();
+                let mut total = false;
+                for transition in state.transitions.iter() {
+                    total = transition.ordered_condition == Condition::constant(true);
+                    let indent = match total {
+                        true => "                ",
+                        false => {
+                            writeln!(res, "                if {}:", transition.ordered_condition.display(PYTHON_DIALECT)).unwrap();
+                            "                    "
+                        }
+                    };
+                    for action in transition.actions.iter() {
+                        writeln!(res, "{indent}{}", action.display(PYTHON_DIALECT)).unwrap();
+                    }
+                    match transition.new_state.as_deref() {
+                        Some(new_state) => {
+                            writeln!(res, "{indent}self.state = {class_name}.State.{}", members[new_state]).unwrap();
+                            writeln!(res, "{indent}self._state_time = 0").unwrap();
+                            match state_machine.states[new_state].parent {
+                                Some(_) => writeln!(res, "{indent}continue").unwrap(),
+                                None => writeln!(res, "{indent}break").unwrap(),
+                            }
+                        }
+                        None => writeln!(res, "{indent}break").unwrap(),
+                    }
+                    if total {
+                        break;
+                    }
+                }
+                if !total {
+                    writeln!(res, "                break").unwrap();
+                }
+            }
+            writeln!(res, "        return self.outputs()").unwrap();
+            res.push('\n');
+
+            writeln!(res, "    def outputs(self):").unwrap();
+            writeln!(res, "        return {{name: getattr(self, name) for name in {class_name}.OUTPUTS}}").unwrap();
+        }
+
+        debug_assert_eq!(res.chars().next_back(), Some('\n'));
+        res.pop();
+        Ok(res)
+    }
+}
@@ -0,0 +1,178 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::fmt::Write as _;
+
+use netsblox_ast::compact_str::{CompactString, ToCompactString, format_compact};
+
+use crate::*;
+
+const RUST_DIALECT: Dialect<'static> = Dialect { variable_prefix: "self.variables.", function_prefix: "self.", timer: "self.timer", state_time: "self.state_time", ..Dialect::new(Language::Rust) };
+
+fn rust_variant(raw: &str) -> Result<CompactString, ()> {
+    let res = pascal_ident(raw)?;
+    Ok(if res == "Self" { "Self_".into() } else { res })
+}
+
+fn rust_helper(func: Function) -> Option<&'static str> {
+    Some(match func {
+        Function::Sin => "fn sind(&self, x: f64) -> f64 { libm::sin(x * (core::f64::consts::PI / 180.0)) }",
+        Function::Cos => "fn cosd(&self, x: f64) -> f64 { libm::cos(x * (core::f64::consts::PI / 180.0)) }",
+        Function::Tan => "fn tand(&self, x: f64) -> f64 { libm::tan(x * (core::f64::consts::PI / 180.0)) }",
+        Function::Asin => "fn asind(&self, x: f64) -> f64 { libm::asin(x) * (180.0 / core::f64::consts::PI) }",
+        Function::Acos => "fn acosd(&self, x: f64) -> f64 { libm::acos(x) * (180.0 / core::f64::consts::PI) }",
+        Function::Atan => "fn atand(&self, x: f64) -> f64 { libm::atan(x) * (180.0 / core::f64::consts::PI) }",
+        Function::Atan2 => "fn atan2d(&self, y: f64, x: f64) -> f64 { libm::atan2(y, x) * (180.0 / core::f64::consts::PI) }",
+        Function::Sqrt => "fn sqrt(&self, x: f64) -> f64 { libm::sqrt(x) }",
+        Function::Floor => "fn floor(&self, x: f64) -> f64 { libm::floor(x) }",
+        Function::Ceil => "fn ceil(&self, x: f64) -> f64 { libm::ceil(x) }",
+        Function::Round => "fn round(&self, x: f64) -> f64 { libm::round(x) }",
+        Function::Sign => "fn sign(&self, x: f64) -> f64 { if x > 0.0 { 1.0 } else if x < 0.0 { -1.0 } else { 0.0 } }",
+        Function::Abs => "fn abs(&self, x: f64) -> f64 { libm::fabs(x) }",
+        Function::Log => "fn log(&self, x: f64) -> f64 { libm::log(x) }",
+        Function::Mod => "fn r#mod(&self, a: f64, b: f64) -> f64 { if b == 0.0 { a } else { a - libm::floor(a / b) * b } }",
+        Function::Randi => "fn randi(&mut self, a: f64, b: f64) -> f64 { self.rng ^= self.rng >> 12; self.rng ^= self.rng << 25; self.rng ^= self.rng >> 27; let x = self.rng.wrapping_mul(0x2545f4914f6cdd1d) >> 11; let (a, b) = (libm::ceil(a.min(b)), libm::floor(a.max(b))); a + libm::floor((x as f64 / (1u64 << 53) as f64) * (b - a + 1.0)) }",
+        Function::After => return None,
+    })
+}
+
+impl Project {
+    pub fn to_rust(&self) -> Result<BTreeMap<CompactString, CompactString>, CompileError> {
+        let mut res = BTreeMap::new();
+        for (state_machine_name, state_machine) in self.state_machines.iter() {
+            let mut rename_pool = RenamePool::new(rust_variant);
+            let variants = state_machine.states.keys().map(|x| Ok((x.as_str(), rename_pool.rename(x)?))).collect::<Result<BTreeMap<_,_>, CompileError>>()?;
+
+            check_numeric_exprs(state_machine_name, state_machine)?;
+
+            // there is no state to start in (the machine has no states at all), so there is nothing to generate
+            let initial_state = match state_machine.initial_state.as_deref().or(state_machine.current_state.as_deref()).or_else(|| state_machine.states.iter().find(|s| s.1.parent.is_none()).map(|s| s.0.as_str())) {
+                Some(x) => x,
+                None => continue,
+            };
+
+            let mut functions = BTreeSet::new();
+            let mut uses_pow = false;
+            visit_exprs(state_machine, &mut |expr| match expr {
+                Expr::Call { func, .. } => { functions.insert(*func); }
+                Expr::Binary { op: BinaryOp::Pow, .. } => uses_pow = true,
+                _ => (),
+            });
+
+            let mut code = CompactString::default();
+            writeln!(code, "// generated from state machine {state_machine_name:?}").unwrap();
+            writeln!(code, "#![allow(non_snake_case)]").unwrap();
+            code.push('\n');
+
+            writeln!(code, "#[derive(Debug, Clone, Copy, PartialEq, Eq)]").unwrap();
+            writeln!(code, "pub enum State {{").unwrap();
+            for variant in variants.values() {
+                writeln!(code, "    {variant},").unwrap();
+            }
+            writeln!(code, "}}").unwrap();
+            code.push('\n');
+
+            writeln!(code, "#[derive(Debug, Clone, Copy, PartialEq, Default)]").unwrap();
+            writeln!(code, "pub struct Variables {{").unwrap();
+            for (var, info) in state_machine.variables.iter() {
+                let field = Expr::Variable(var.clone()).display(Dialect::new(Language::Rust)).to_compact_string();
+                writeln!(code, "    pub {field}: f64, // {:?}", info.kind).unwrap();
+            }
+            writeln!(code, "}}").unwrap();
+            code.push('\n');
+
+            writeln!(code, "#[derive(Debug, Clone, PartialEq)]").unwrap();
+            writeln!(code, "pub struct StateMachine {{").unwrap();
+            writeln!(code, "    pub state: State,").unwrap();
+            writeln!(code, "    pub state_time: f64,").unwrap();
+            writeln!(code, "    pub timer: f64,").unwrap();
+            writeln!(code, "    pub rng: u64,").unwrap();
+            writeln!(code, "    pub variables: Variables,").unwrap();
+            writeln!(code, "}}").unwrap();
+            code.push('\n');
+
+            writeln!(code, "impl StateMachine {{").unwrap();
+            writeln!(code, "    pub fn new() -> Self {{").unwrap();
+            let init = format_compact!("Self {{ state: State::{}, state_time: 0.0, timer: 0.0, rng: 0x853c49e6748fea9b, variables: Variables::default() }}", variants[initial_state]);
+            if state_machine.variables.is_empty() {
+                writeln!(code, "        {init}").unwrap();
+            } else {
+                writeln!(code, "        let mut res = {init};").unwrap();
+                for (var, info) in state_machine.variables.iter() {
+                    let init = Stmt::Assign { var: var.clone(), value: info.init.clone() };
+                    writeln!(code, "        {};", init.display(Dialect { variable_prefix: "res.variables.", function_prefix: "res.", timer: "res.timer", state_time: "res.state_time", ..RUST_DIALECT })).unwrap();
+                }
+                writeln!(code, "        res").unwrap();
+            }
+            writeln!(code, "    }}").unwrap();
+
+            writeln!(code, "    pub fn step(&mut self, dt: f64) {{").unwrap();
+            writeln!(code, "        self.timer += dt;").unwrap();
+            writeln!(code, "        self.state_time += dt;").unwrap();
+            writeln!(code, "        for _ in 0..={} {{", state_machine.states.len()).unwrap();
+            writeln!(code, "            match self.state {{").unwrap();
+            for (state_name, state) in state_machine.states.iter() {
+                writeln!(code, "                State::{} => {{", variants[state_name.as_str()]).unwrap();
+                let mut total = false;
+                for transition in state.transitions.iter() {
+                    total = transition.ordered_condition == Condition::constant(true);
+                    let indent = match total {
+                        true => "                    ",
+                        false => {
+                            writeln!(code, "                    if {} {{", transition.ordered_condition.display(RUST_DIALECT)).unwrap();
+                            "                        "
+                        }
+                    };
+                    for action in transition.actions.iter() {
+                        writeln!(code, "{indent}{};", action.display(RUST_DIALECT)).unwrap();
+                    }
+                    match transition.new_state.as_deref() {
+                        Some(new_state) => {
+                            writeln!(code, "{indent}self.state = State::{};", variants[new_state]).unwrap();
+                            writeln!(code, "{indent}self.state_time = 0.0;").unwrap();
+                            match state_machine.states[new_state].parent {
+                                Some(_) => writeln!(code, "{indent}continue;").unwrap(),
+                                None => writeln!(code, "{indent}return;").unwrap(),
+                            }
+                        }
+                        None => writeln!(code, "{indent}return;").unwrap(),
+                    }
+                    if total {
+                        break;
+                    }
+                    writeln!(code, "                    }}").unwrap();
+                }
+                if !total {
+                    writeln!(code, "                    return;").unwrap();
+                }
+                writeln!(code, "                }}").unwrap();
+            }
+            writeln!(code, "            }}").unwrap();
+            writeln!(code, "        }}").unwrap();
+            writeln!(code, "    }}").unwrap();
+
+            for helper in functions.iter().flat_map(|&f| rust_helper(f)).chain(uses_pow.then_some("fn pow(&self, a: f64, b: f64) -> f64 { libm::pow(a, b) }")) {
+                writeln!(code, "    {helper}").unwrap();
+            }
+            writeln!(code, "}}").unwrap();
+            code.push('\n');
+
+            writeln!(code, "impl Default for StateMachine {{").unwrap();
+            writeln!(code, "    fn default() -> Self {{").unwrap();
+            writeln!(code, "        Self::new()").unwrap();
+            writeln!(code, "    }}").unwrap();
+            writeln!(code, "}}").unwrap();
+
+            res.insert(state_machine_name.clone(), code);
+        }
+        Ok(res)
+    }
+}
+
+#[test]
+fn test_rust_variant() {
+    assert_eq!(rust_variant("idle").unwrap(), "Idle");
+    assert_eq!(rust_variant("door open").unwrap(), "DoorOpen");
+    assert_eq!(rust_variant("::junction-3::").unwrap(), "Junction3");
+    assert_eq!(rust_variant("3 lights").unwrap(), "Var3Lights");
+    assert_eq!(rust_variant("self").unwrap(), "Self_");
+    assert_eq!(rust_variant("!!").unwrap_err(), ());
+}
@@ -0,0 +1,178 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::fmt::Write as _;
+use alloc::vec::Vec;
+
+use netsblox_ast as ast;
+use netsblox_ast::compact_str::{CompactString, format_compact};
+
+use crate::*;
+
+fn js_helper(func: Function) -> Option<&'static str> {
+    Some(match func {
+        Function::Sin => "function _sind(x) { return Math.sin(x * Math.PI / 180); }",
+        Function::Cos => "function _cosd(x) { return Math.cos(x * Math.PI / 180); }",
+        Function::Tan => "function _tand(x) { return Math.tan(x * Math.PI / 180); }",
+        Function::Asin => "function _asind(x) { return Math.asin(x) * 180 / Math.PI; }",
+        Function::Acos => "function _acosd(x) { return Math.acos(x) * 180 / Math.PI; }",
+        Function::Atan => "function _atand(x) { return Math.atan(x) * 180 / Math.PI; }",
+        Function::Atan2 => "function _atan2d(y, x) { return Math.atan2(y, x) * 180 / Math.PI; }",
+        Function::Sqrt => "function _sqrt(x) { return Math.sqrt(x); }",
+        Function::Floor => "function _floor(x) { return Math.floor(x); }",
+        Function::Ceil => "function _ceil(x) { return Math.ceil(x); }",
+        Function::Round => "function _round(x) { return Math.sign(x) * Math.round(Math.abs(x)); }",
+        Function::Sign => "function _sign(x) { return Math.sign(x); }",
+        Function::Abs => "function _abs(x) { return Math.abs(x); }",
+        Function::Log => "function _log(x) { return Math.log(x); }",
+        Function::Mod => "function _mod(a, b) { return b === 0 ? a : a - Math.floor(a / b) * b; }",
+        Function::Randi => "function _randi(a, b) { a = Math.ceil(a); b = Math.floor(b); return a + Math.floor(Math.random() * (b - a + 1)); }",
+        Function::After => return None,
+    })
+}
+
+fn write_actions(w: &mut Writer, actions: &VecDeque<Stmt>, dialect: Dialect<'_>) {
+    for action in actions.iter() {
+        match action {
+            Stmt::Assign { var, value } => w.line(&format_compact!("<assign location=\"{}\" expr=\"{}\"/>", xml_escape(var), xml_escape(&value.display(dialect).to_compact_string()))),
+            Stmt::AddAssign { var, value } => {
+                let value = Expr::Add { values: vec![Expr::Variable(var.clone()), value.clone()] };
+                w.line(&format_compact!("<assign location=\"{}\" expr=\"{}\"/>", xml_escape(var), xml_escape(&value.display(dialect).to_compact_string())));
+            }
+            Stmt::ResetTimer => w.line("<assign location=\"_t0\" expr=\"_now()\"/>"),
+            Stmt::Unknown => w.line("<log label=\"unknown block\"/>"),
+        }
+    }
+}
+
+impl Project {
+    pub fn to_scxml(&self) -> Result<CompactString, CompileError> {
+        let mut rename_pool = RenamePool::new(ast::util::c_ident);
+        let mut rename = move |x: &str| rename_pool.rename(x);
+
+        let mut functions = BTreeSet::new();
+        let mut uses_timer = false;
+        let mut uses_after = BTreeSet::new();
+        for (state_machine_idx, state_machine) in self.state_machines.values().enumerate() {
+            visit_exprs(state_machine, &mut |expr| match expr {
+                Expr::Timer => uses_timer = true,
+                Expr::Call { func: Function::After, .. } => { uses_after.insert(state_machine_idx); }
+                Expr::Call { func, .. } => { functions.insert(*func); }
+                _ => (),
+            });
+            uses_timer |= state_machine.states.values().flat_map(|s| s.transitions.iter().flat_map(|t| t.actions.iter())).any(|a| *a == Stmt::ResetTimer);
+        }
+
+        let mut w = Writer { res: CompactString::default(), depth: 0 };
+        w.line("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+        w.open(&format_compact!("<scxml xmlns=\"http://www.w3.org/2005/07/scxml\" version=\"1.0\" datamodel=\"ecmascript\" name=\"{}\">", xml_escape(&self.name)));
+
+        w.open("<datamodel>");
+        if uses_timer {
+            w.line("<data id=\"_t0\" expr=\"0\"/>");
+        }
+        for &state_machine_idx in uses_after.iter() {
+            w.line(&format_compact!("<data id=\"_entered_{state_machine_idx}\" expr=\"0\"/>"));
+        }
+        for state_machine in self.state_machines.values() {
+            for var in state_machine.variables.keys() {
+                w.line(&format_compact!("<data id=\"{}\"/>", xml_escape(var)));
+            }
+        }
+        w.close("</datamodel>");
+
+        let helpers = (uses_timer || !uses_after.is_empty()).then_some("function _now() { return Date.now() / 1000; }").into_iter().chain(functions.iter().flat_map(|&f| js_helper(f))).collect::<Vec<_>>();
+        if !helpers.is_empty() {
+            w.open("<script>");
+            for helper in helpers {
+                w.line(&xml_escape(helper));
+            }
+            w.close("</script>");
+        }
+
+        w.open(&format_compact!("<parallel id=\"{}\">", rename(&self.name)?));
+        // variables are initialized here rather than in the datamodel since their inits may call the helpers in the script above
+        if uses_timer || self.state_machines.values().any(|x| !x.variables.is_empty()) {
+            w.open("<onentry>");
+            if uses_timer {
+                w.line("<assign location=\"_t0\" expr=\"_now()\"/>");
+            }
+            for state_machine in self.state_machines.values() {
+                for (var, info) in state_machine.variables.iter() {
+                    w.line(&format_compact!("<assign location=\"{}\" expr=\"{}\"/>", xml_escape(var), xml_escape(&info.init.display(Dialect { function_prefix: "_", timer: "(_now() - _t0)", ..Dialect::new(Language::JavaScript) }).to_compact_string())));
+                }
+            }
+            w.close("</onentry>");
+        }
+        for state_machine in self.state_machines.values() {
+            for (var, _) in state_machine.variables.iter().filter(|x| x.1.kind == VariableKind::Input) {
+                w.open(&format_compact!("<transition event=\"input.{}\">", xml_escape(var)));
+                w.line(&format_compact!("<assign location=\"{}\" expr=\"_event.data\"/>", xml_escape(var)));
+                w.close("</transition>");
+            }
+        }
+
+        for (state_machine_idx, (state_machine_name, state_machine)) in self.state_machines.iter().enumerate() {
+            let state_ids = state_machine.states.keys().map(|state_name| Ok((state_name.as_str(), rename(&format_compact!("{state_machine_name} {state_name}"))?))).collect::<Result<BTreeMap<_,_>, CompileError>>()?;
+            let state_time = format_compact!("(_now() - _entered_{state_machine_idx})");
+            let dialect = Dialect { function_prefix: "_", timer: "(_now() - _t0)", state_time: &state_time, ..Dialect::new(Language::JavaScript) };
+
+            let mut header = format_compact!("<state id=\"{}\"", rename(state_machine_name)?);
+            if let Some(initial_state) = state_machine.initial_state.as_deref().or(state_machine.current_state.as_deref()) {
+                write!(header, " initial=\"{}\"", state_ids[initial_state]).unwrap();
+            }
+            header.push('>');
+            w.open(&header);
+
+            for (state_name, state) in state_machine.states.iter() {
+                w.open(&format_compact!("<state id=\"{}\">", state_ids[state_name.as_str()]));
+                if uses_after.contains(&state_machine_idx) {
+                    w.open("<onentry>");
+                    w.line(&format_compact!("<assign location=\"_entered_{state_machine_idx}\" expr=\"_now()\"/>"));
+                    w.close("</onentry>");
+                }
+                for transition in state.transitions.iter() {
+                    // junctions are passed through immediately, but waiting in place has to be driven by events or it would never settle.
+                    // eventless transitions are not blocked by earlier evented ones, so junctions must use the unordered conditions
+                    let eventless = state.parent.is_some() && transition.new_state.is_some();
+                    if state.parent.is_some() && transition.new_state.is_none() && transition.actions.is_empty() {
+                        continue;
+                    }
+                    let condition = match state.parent {
+                        Some(_) => &transition.unordered_condition,
+                        None => &transition.ordered_condition,
+                    };
+
+                    let mut header = CompactString::new("<transition");
+                    if !eventless {
+                        header.push_str(" event=\"step\"");
+                    }
+                    if *condition != Condition::constant(true) {
+                        write!(header, " cond=\"{}\"", xml_escape(&condition.display(dialect).to_compact_string())).unwrap();
+                    }
+                    if let Some(new_state) = transition.new_state.as_deref() {
+                        write!(header, " target=\"{}\"", state_ids[new_state]).unwrap();
+                    }
+
+                    if transition.actions.is_empty() {
+                        header.push_str("/>");
+                        w.line(&header);
+                    } else {
+                        header.push('>');
+                        w.open(&header);
+                        write_actions(&mut w, &transition.actions, dialect);
+                        w.close("</transition>");
+                    }
+                }
+                w.close("</state>");
+            }
+
+            w.close("</state>");
+        }
+        w.close("</parallel>");
+        w.close("</scxml>");
+
+        debug_assert_eq!(w.depth, 0);
+        let mut res = w.res;
+        res.pop();
+        Ok(res)
+    }
+}
@@ -0,0 +1,166 @@
+use alloc::collections::BTreeMap;
+
+use netsblox_ast::compact_str::{CompactString, ToCompactString};
+
+use crate::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimulationError {
+    NoInitialState,
+    UnknownState { state: CompactString },
+    NoTransition { state: CompactString },
+    JunctionLoop { state: CompactString },
+    NotAnInput { variable: CompactString },
+    EvalError { src: CompactString, error: EvalError },
+}
+
+struct Env<'a> {
+    variables: &'a mut BTreeMap<CompactString, f64>,
+    timer: &'a mut f64,
+    state_time: f64,
+    rng: &'a mut u64,
+}
+impl Environment for Env<'_> {
+    fn get(&self, var: &str) -> Option<f64> {
+        self.variables.get(var).copied()
+    }
+    fn set(&mut self, var: &str, value: f64) -> Result<(), EvalError> {
+        match self.variables.get_mut(var) {
+            Some(x) => { *x = value; Ok(()) }
+            None => Err(EvalError::UndefinedVariable { name: var.into() }),
+        }
+    }
+    fn timer(&self) -> Result<f64, EvalError> {
+        Ok(*self.timer)
+    }
+    fn reset_timer(&mut self) -> Result<(), EvalError> {
+        *self.timer = 0.0;
+        Ok(())
+    }
+    fn state_time(&self) -> Result<f64, EvalError> {
+        Ok(self.state_time)
+    }
+    fn random(&mut self, a: f64, b: f64) -> Result<f64, EvalError> {
+        // xorshift64* is plenty for picking integers in a small range
+        *self.rng ^= *self.rng >> 12;
+        *self.rng ^= *self.rng << 25;
+        *self.rng ^= *self.rng >> 27;
+        let x = self.rng.wrapping_mul(0x2545f4914f6cdd1d) >> 11;
+        let (a, b) = (libm::ceil(a.min(b)), libm::floor(a.max(b)));
+        Ok(a + libm::floor((x as f64 / (1u64 << 53) as f64) * (b - a + 1.0)))
+    }
+}
+impl Env<'_> {
+    fn eval(&mut self, expr: &Expr) -> Result<f64, SimulationError> {
+        expr.eval(self).map_err(|error| SimulationError::EvalError { src: expr.to_compact_string(), error })
+    }
+    fn exec(&mut self, stmt: &Stmt) -> Result<(), SimulationError> {
+        stmt.exec(self).map_err(|error| SimulationError::EvalError { src: stmt.to_compact_string(), error })
+    }
+    fn check(&mut self, condition: &RawCondition) -> Result<bool, SimulationError> {
+        Ok(match condition {
+            RawCondition::Const(x) => *x,
+            RawCondition::Atom(x) => self.eval(x)? != 0.0,
+            RawCondition::Not(x) => !self.check(x)?,
+            RawCondition::And(a, b) => self.check(a)? && self.check(b)?,
+            RawCondition::Or(a, b) => self.check(a)? || self.check(b)?,
+        })
+    }
+}
+
+pub struct Simulator<'a> {
+    state_machine: &'a StateMachine,
+    state: CompactString,
+    variables: BTreeMap<CompactString, f64>,
+    timer: f64,
+    state_time: f64,
+    rng: u64,
+}
+impl<'a> Simulator<'a> {
+    pub fn new(state_machine: &'a StateMachine) -> Result<Self, SimulationError> {
+        let state = state_machine.initial_state.as_ref().or(state_machine.current_state.as_ref()).ok_or(SimulationError::NoInitialState)?.clone();
+        if !state_machine.states.contains_key(&state) {
+            return Err(SimulationError::UnknownState { state });
+        }
+
+        let mut res = Self { state_machine, state, variables: state_machine.variables.keys().map(|x| (x.clone(), 0.0)).collect(), timer: 0.0, state_time: 0.0, rng: 0x853c49e6748fea9b };
+        for (name, variable) in state_machine.variables.iter() {
+            let value = res.env().eval(&variable.init)?;
+            *res.variables.get_mut(name).unwrap() = value;
+        }
+        Ok(res)
+    }
+    fn env(&mut self) -> Env<'_> {
+        Env { variables: &mut self.variables, timer: &mut self.timer, state_time: self.state_time, rng: &mut self.rng }
+    }
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = seed.max(1);
+    }
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+    pub fn variables(&self) -> &BTreeMap<CompactString, f64> {
+        &self.variables
+    }
+    pub fn timer(&self) -> f64 {
+        self.timer
+    }
+    pub fn outputs(&self) -> BTreeMap<CompactString, f64> {
+        self.variables.iter().filter(|x| self.state_machine.variables[x.0].kind == VariableKind::Output).map(|(k, v)| (k.clone(), *v)).collect()
+    }
+    pub fn step<K: AsRef<str>, I: IntoIterator<Item = (K, f64)>>(&mut self, inputs: I, dt: f64) -> Result<BTreeMap<CompactString, f64>, SimulationError> {
+        // validate every input before applying any of them so a bad name leaves the simulator untouched
+        let mut values = vec![];
+        for (var, value) in inputs {
+            let var = var.as_ref();
+            match self.state_machine.variables.get(var) {
+                Some(Variable { kind: VariableKind::Input, .. }) => values.push((CompactString::new(var), value)),
+                _ => return Err(SimulationError::NotAnInput { variable: var.into() }),
+            }
+        }
+        for (var, value) in values {
+            *self.variables.get_mut(&var).unwrap() = value;
+        }
+
+        self.timer += dt;
+        self.state_time += dt;
+
+        let mut junctions = 0;
+        loop {
+            let state_machine = self.state_machine;
+            let state = state_machine.states.get(&self.state).ok_or_else(|| SimulationError::UnknownState { state: self.state.clone() })?;
+
+            let mut env = Env { variables: &mut self.variables, timer: &mut self.timer, state_time: self.state_time, rng: &mut self.rng };
+            let mut transition = None;
+            for t in state.transitions.iter() {
+                if env.check(t.ordered_condition.raw())? {
+                    transition = Some(t);
+                    break;
+                }
+            }
+            let transition = transition.ok_or_else(|| SimulationError::NoTransition { state: self.state.clone() })?;
+
+            for action in transition.actions.iter() {
+                env.exec(action)?;
+            }
+
+            let new_state = match transition.new_state.as_ref() {
+                Some(x) => x,
+                None => break,
+            };
+            let parent = &state_machine.states.get(new_state).ok_or_else(|| SimulationError::UnknownState { state: new_state.clone() })?.parent;
+            self.state = new_state.clone();
+            self.state_time = 0.0;
+
+            if parent.is_none() {
+                break;
+            }
+            junctions += 1;
+            if junctions > self.state_machine.states.len() {
+                return Err(SimulationError::JunctionLoop { state: self.state.clone() });
+            }
+        }
+
+        Ok(self.outputs())
+    }
+}
@@ -0,0 +1,233 @@
+use alloc::collections::BTreeMap;
+use alloc::fmt::Write as _;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use netsblox_ast as ast;
+use netsblox_ast::compact_str::{CompactString, format_compact};
+
+use crate::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StateflowSettings {
+    pub connective_junctions: bool,
+    pub layout: Option<LayoutSettings>,
+}
+
+struct ChartState {
+    label: CompactString,
+    position: (usize, usize, usize, usize),
+    junction: bool,
+}
+
+impl ChartState {
+    fn junction_center(&self) -> (usize, usize) {
+        let (x, y, w, h) = self.position;
+        (x + w / 2, y + h / 2)
+    }
+}
+
+struct ChartTransition {
+    source: usize,
+    destination: usize,
+    label: CompactString,
+    execution_order: usize,
+    midpoint: Option<(f64, f64)>,
+}
+
+struct Chart<'a> {
+    name: &'a CompactString,
+    state_machine: &'a StateMachine,
+    states: Vec<ChartState>,
+    transitions: Vec<ChartTransition>,
+    initial_state: Option<usize>,
+}
+
+const JUNCTION_RADIUS: usize = 7;
+
+fn stateflow_escape(full: &str) -> String {
+    let mut res = String::new();
+    for line in full.lines() {
+        if !res.is_empty() {
+            res.push_str(" + newline + ");
+        }
+        write!(res, "{line:?}").unwrap();
+    }
+    res
+}
+
+impl Project {
+    fn stateflow_charts(&self, settings: StateflowSettings) -> Result<(CompactString, Vec<Chart<'_>>), CompileError> {
+        let mut rename_pool = RenamePool::new(ast::util::c_ident);
+        let mut rename = move |x| rename_pool.rename(x);
+        let model_name = rename(&self.name)?;
+
+        let LayoutSettings { state_size, junction_size, padding, .. } = settings.layout.unwrap_or_default();
+
+        let mut charts = vec![];
+        for (state_machine_name, state_machine) in self.state_machines.iter() {
+            let layout = settings.layout.map(|x| state_machine.layout(x));
+            let state_numbers: BTreeMap<&str, usize> = state_machine.states.iter().enumerate().map(|x| (x.1.0.as_str(), x.0)).collect();
+            let parent_state_numbers: BTreeMap<&str, usize> = state_machine.states.iter().filter(|x| x.1.parent.is_none()).enumerate().map(|x| (x.1.0.as_str(), x.0)).collect();
+
+            let included_transitions = state_machine.states.iter().map(|(state_name, state)| {
+                (state as *const State, state.transitions.iter().filter(|t| t.new_state.as_ref().unwrap_or(state_name) != state_name || !t.actions.is_empty() || t.ordered_condition != Condition::constant(true)).collect::<Vec<_>>())
+            }).collect::<BTreeMap<_,_>>();
+
+            let entry_actions = state_machine.states.iter().filter(|s| s.1.parent.is_none()).map(|(state_name, _)| {
+                let actions = match state_machine.initial_state.as_ref().map(|i| i != state_name).unwrap_or(true) {
+                    true => common_suffix(state_machine.states.iter().flat_map(|(n, s)| included_transitions[&(s as _)].iter().filter(|t| t.new_state.as_ref().unwrap_or(n) == state_name)).map(|t| t.actions.iter())),
+                    false => <_>::default(),
+                };
+                (state_name, actions)
+            }).collect::<BTreeMap<_,_>>();
+            let exit_actions = state_machine.states.iter().filter(|s| s.1.parent.is_none()).map(|(state_name, state)| {
+                let actions = common_suffix(included_transitions[&(state as _)].iter().map(|t| t.actions.iter().take(t.actions.len() - entry_actions.get(t.new_state.as_ref().unwrap_or(state_name)).map(|x| x.len()).unwrap_or(0))));
+                (state_name, actions)
+            }).collect::<BTreeMap<_,_>>();
+
+            let mut states = vec![];
+            let mut child_counts: BTreeMap<&str, usize> = Default::default();
+            for (state_name, state) in state_machine.states.iter() {
+                match state.parent.as_deref() {
+                    Some(parent) => {
+                        *child_counts.entry(parent).or_default() += 1;
+                        let label = format_compact!("{}_{}", rename(parent)?, child_counts[parent]);
+                        let position = match layout.as_ref() {
+                            Some(layout) => layout.states[state_name],
+                            None => (parent_state_numbers[parent] * (state_size.0 + padding.0) + (state_size.0 - junction_size.0) / 2, state_size.1 + padding.1 * child_counts[parent], junction_size.0, junction_size.1),
+                        };
+                        // junctions that can stay in place (e.g., waiting) must remain states
+                        let junction = settings.connective_junctions && state.transitions.iter().all(|t| t.new_state.is_some());
+                        states.push(ChartState { label, position, junction });
+                    }
+                    None => {
+                        let mut label = rename(state_name)?;
+                        match entry_actions.get(state_name) {
+                            Some(actions) if !actions.is_empty() => {
+                                label.push_str("\nentry:");
+                                for action in actions {
+                                    write!(label, " {};", action.display(Dialect::MATLAB)).unwrap();
+                                }
+                            }
+                            _ => (),
+                        }
+                        match exit_actions.get(state_name) {
+                            Some(actions) if !actions.is_empty() => {
+                                label.push_str("\nexit:");
+                                for action in actions {
+                                    write!(label, " {};", action.display(Dialect::MATLAB)).unwrap();
+                                }
+                            }
+                            _ => (),
+                        }
+                        let position = match layout.as_ref() {
+                            Some(layout) => layout.states[state_name],
+                            None => (parent_state_numbers[state_name.as_str()] * (state_size.0 + padding.0), 0, state_size.0, state_size.1),
+                        };
+                        states.push(ChartState { label, position, junction: false });
+                    }
+                }
+            }
+
+            let mut transitions = vec![];
+            for (state_idx, (state_name, state)) in state_machine.states.iter().enumerate() {
+                for (i, transition) in included_transitions[&(state as _)].iter().enumerate() {
+                    let condition = match settings.connective_junctions {
+                        true => &transition.ordered_condition,
+                        false => &transition.unordered_condition,
+                    };
+                    let mut label = CompactString::default();
+                    if *condition != Condition::constant(true) {
+                        write!(label, "[{}]", condition.display(Dialect::MATLAB)).unwrap();
+                    }
+
+                    let entry_action_count = entry_actions.get(transition.new_state.as_ref().unwrap_or(state_name)).map(|x| x.len()).unwrap_or(0);
+                    let exit_action_count = exit_actions.get(state_name).map(|x| x.len()).unwrap_or(0);
+                    if transition.actions.len() > entry_action_count + exit_action_count {
+                        label.push('{');
+                        for action in transition.actions.iter().take(transition.actions.len() - (entry_action_count + exit_action_count)) {
+                            write!(label, "{};", action.display(Dialect::MATLAB)).unwrap();
+                        }
+                        label.push('}');
+                    }
+
+                    let midpoint = layout.as_ref().and_then(|layout| {
+                        let index = state.transitions.iter().position(|x| core::ptr::eq(x, *transition)).unwrap();
+                        layout.transitions.get(&(state_name.clone(), index)).copied()
+                    });
+                    transitions.push(ChartTransition { source: state_idx, destination: state_numbers[transition.new_state.as_deref().unwrap_or(state_name)], label, execution_order: i + 1, midpoint });
+                }
+            }
+
+            let initial_state = state_machine.initial_state.as_deref().map(|x| state_numbers[x]);
+            charts.push(Chart { name: state_machine_name, state_machine, states, transitions, initial_state });
+        }
+        Ok((model_name, charts))
+    }
+    pub fn to_stateflow(&self) -> Result<CompactString, CompileError> {
+        self.to_stateflow_with(StateflowSettings::default())
+    }
+    pub fn to_stateflow_with(&self, settings: StateflowSettings) -> Result<CompactString, CompileError> {
+        let (model_name, charts) = self.stateflow_charts(settings)?;
+
+        let mut res = CompactString::default();
+        writeln!(res, "sfnew {model_name}").unwrap();
+        for (chart_idx, chart) in charts.iter().enumerate() {
+            if chart_idx == 0 {
+                writeln!(res, "chart = find(sfroot, \"-isa\", \"Stateflow.Chart\")").unwrap();
+                writeln!(res, "chart.Name = {:?}", chart.name).unwrap();
+            } else {
+                writeln!(res, "chart = add_block(\"sflib/Chart\", {:?})", format!("{model_name}/{}", chart.name)).unwrap();
+            }
+            if settings.connective_junctions {
+                writeln!(res, "chart.UserSpecifiedStateTransitionExecutionOrder = true").unwrap();
+            }
+
+            for (state_idx, state) in chart.states.iter().enumerate() {
+                match state.junction {
+                    true => {
+                        let (x, y) = state.junction_center();
+                        writeln!(res, "s{state_idx} = Stateflow.Junction(chart)").unwrap();
+                        writeln!(res, "s{state_idx}.Position.Center = [{x}, {y}]").unwrap();
+                        writeln!(res, "s{state_idx}.Position.Radius = {JUNCTION_RADIUS}").unwrap();
+                    }
+                    false => {
+                        let (x, y, w, h) = state.position;
+                        writeln!(res, "s{state_idx} = Stateflow.State(chart)").unwrap();
+                        writeln!(res, "s{state_idx}.LabelString = {}", stateflow_escape(&state.label)).unwrap();
+                        writeln!(res, "s{state_idx}.Position = [{x}, {y}, {w}, {h}]").unwrap();
+                    }
+                }
+            }
+            for transition in chart.transitions.iter() {
+                writeln!(res, "t = Stateflow.Transition(chart)").unwrap();
+                writeln!(res, "t.Source = s{}", transition.source).unwrap();
+                writeln!(res, "t.Destination = s{}", transition.destination).unwrap();
+                writeln!(res, "t.LabelString = {:?}", transition.label).unwrap();
+                if settings.connective_junctions {
+                    writeln!(res, "t.ExecutionOrder = {}", transition.execution_order).unwrap();
+                }
+                if let Some((x, y)) = transition.midpoint {
+                    writeln!(res, "t.Midpoint = [{x}, {y}]").unwrap();
+                }
+            }
+            if let Some(initial_state) = chart.initial_state {
+                writeln!(res, "t = Stateflow.Transition(chart)").unwrap();
+                writeln!(res, "t.Destination = s{initial_state}").unwrap();
+                writeln!(res, "t.DestinationOClock = 0").unwrap();
+                writeln!(res, "t.SourceEndpoint = t.DestinationEndpoint - [0 30]").unwrap();
+                writeln!(res, "t.Midpoint = t.DestinationEndpoint - [0 15]").unwrap();
+            }
+            for (var, info) in chart.state_machine.variables.iter() {
+                writeln!(res, "d = Stateflow.Data(chart)").unwrap();
+                writeln!(res, "d.Name = {var:?}").unwrap();
+                writeln!(res, "d.Props.InitialValue = {:?}", info.init.display(Dialect::MATLAB).to_string()).unwrap();
+                writeln!(res, "d.Scope = \"{:?}\"", info.kind).unwrap();
+            }
+        }
+        debug_assert_eq!(res.chars().next_back(), Some('\n'));
+        res.pop();
+        Ok(res)
+    }
+}
@@ -1,13 +1,13 @@
-use std::collections::{BTreeSet, BTreeMap};
+use std::collections::{BTreeSet, BTreeMap, VecDeque};
 
 use itertools::Itertools;
 
 use netsblox_stateflow::*;
 
-fn aggregate_atoms<'a>(src: &'a RawCondition, dest: &mut BTreeSet<&'a str>) {
+fn aggregate_atoms<'a>(src: &'a RawCondition, dest: &mut BTreeSet<&'a Expr>) {
     match src {
         RawCondition::Const(_) => (),
-        RawCondition::Atom(x) => { dest.insert(x.as_str()); }
+        RawCondition::Atom(x) => { dest.insert(x); }
         RawCondition::And(a, b) | RawCondition::Or(a, b) => {
             aggregate_atoms(a, dest);
             aggregate_atoms(b, dest);
@@ -16,18 +16,35 @@ fn aggregate_atoms<'a>(src: &'a RawCondition, dest: &mut BTreeSet<&'a str>) {
     }
 }
 
-fn eval(cond: &RawCondition, assignments: &BTreeMap<&str, bool>) -> bool {
+fn eval(cond: &RawCondition, assignments: &BTreeMap<&Expr, bool>) -> bool {
     match cond {
         RawCondition::Const(x) => *x,
-        RawCondition::Atom(x) => assignments[x.as_str()],
+        RawCondition::Atom(x) => assignments[x],
         RawCondition::And(a, b) => eval(a, assignments) && eval(b, assignments),
         RawCondition::Or(a, b) => eval(a, assignments) || eval(b, assignments),
         RawCondition::Not(a) => !eval(a, assignments),
     }
 }
 
+// comparisons against constants on the same variable are not independent (e.g. x < 4 and x > 5),
+// so skip the assignments that no value of the variable could produce
+fn consistent(assignments: &BTreeMap<&Expr, bool>) -> bool {
+    let mut comparisons: BTreeMap<&Expr, Vec<Comparison>> = BTreeMap::new();
+    for (atom, value) in assignments.iter() {
+        if let Some(x) = Comparison::from_expr(atom) {
+            comparisons.entry(x.var).or_default().push(if *value { x } else { x.negated() });
+        }
+    }
+    comparisons.values().all(|comparisons| {
+        let constants = comparisons.iter().map(|x| x.value).collect::<Vec<_>>();
+        constants.iter().flat_map(|&c| [c - 1.0, c - 0.5, c, c + 0.5, c + 1.0]).chain(constants.iter().tuple_combinations().map(|(a, b)| (a + b) / 2.0))
+            .any(|x| comparisons.iter().all(|c| c.holds(x)))
+    })
+}
+
 fn assert_complete(proj: &Project) {
     for (state_machine_name, state_machine) in proj.state_machines.iter() {
+        assert_eq!(state_machine.check_completeness(), vec![], "{state_machine_name:?}");
         for (state_name, state) in state_machine.states.iter() {
             match state.transitions.back() {
                 Some(t) => if t.ordered_condition != Condition::constant(true) { panic!("{state_machine_name:?} :: {state_name:?} > transitions not in normal form") },
@@ -44,13 +61,13 @@ fn assert_complete(proj: &Project) {
             for values in vec![[false, true]; variables.len()].into_iter().multi_cartesian_product() {
                 assert_eq!(variables.len(), values.len());
                 let assignments = variables.iter().copied().zip(values.iter().copied()).collect::<BTreeMap<_,_>>();
+                if !consistent(&assignments) { continue }
 
                 match state.transitions.iter().enumerate().find(|x| eval(x.1.ordered_condition.raw(), &assignments)).map(|x| x.0) {
                     Some(i) => { chosen_transitions.insert(assignments, i); }
                     None => panic!("{state_machine_name:?} :: {state_name:?} > no ordered transition for {assignments:?}"),
                 }
             }
-            assert_eq!(chosen_transitions.len(), 1 << variables.len());
 
             for (assignments, chosen_transition) in chosen_transitions.iter() {
                 let activations = state.transitions.iter().map(|t| eval(t.unordered_condition.raw(), assignments)).collect::<Vec<_>>();
@@ -60,7 +77,7 @@ fn assert_complete(proj: &Project) {
                         x if x == *chosen_transition => (),
                         x => panic!("{state_machine_name:?} :: {state_name:?} > wrong unordered transition (got {x} expected {chosen_transition}) for {assignments:?}"),
                     }
-                    _ => panic!("{state_machine_name:?} :: {state_name:?} > multiple unordered transitions (got {x:?} expected {chosen_transition}) for {assignments:?}", x = activations.iter().enumerate().filter_map(|t| t.1.then(|| t.0)).collect::<Vec<_>>()),
+                    _ => panic!("{state_machine_name:?} :: {state_name:?} > multiple unordered transitions (got {x:?} expected {chosen_transition}) for {assignments:?}", x = activations.iter().enumerate().filter(|t| *t.1).map(|t| t.0).collect::<Vec<_>>()),
                 }
             }
         }
@@ -118,6 +135,7 @@ fn test_simple() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -162,7 +180,7 @@ fn test_factoring_1() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("x".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("x".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("left".into(), State {
@@ -172,7 +190,7 @@ fn test_factoring_1() {
                                 ordered_condition: Condition::constant(true),
                                 unordered_condition: Condition::constant(true),
                                 actions: [
-                                    "x = (x + 1)".into(),
+                                    "x = (x + 1)".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: None,
                             },
@@ -181,6 +199,7 @@ fn test_factoring_1() {
                 ].into_iter().collect(),
                 initial_state: Some("left".into()),
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -217,24 +236,24 @@ fn test_factoring_2() {
         state_machines: [
             ("gcd state".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Input }),
-                    ("b".into(), Variable { init: "0".into(), kind: VariableKind::Output }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Input }),
+                    ("b".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Output }),
                 ].into_iter().collect(),
                 states: [
                     ("start".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                ordered_condition: Condition::atom("a > 20".into()),
-                                unordered_condition: Condition::atom("a > 20".into()),
+                                ordered_condition: Condition::atom("a > 20".parse().unwrap()),
+                                unordered_condition: Condition::atom("a > 20".parse().unwrap()),
                                 actions: [
-                                    "b = randi(6)".into(),
+                                    "b = randi(6)".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("Shake".into()),
                             },
                             Transition {
                                 ordered_condition: Condition::constant(true),
-                                unordered_condition: !Condition::atom("a > 20".into()),
+                                unordered_condition: !Condition::atom("a > 20".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
                             },
@@ -244,16 +263,16 @@ fn test_factoring_2() {
                         parent: None,
                         transitions: [
                             Transition {
-                                ordered_condition: Condition::atom("after(3, sec)".into()),
-                                unordered_condition: Condition::atom("after(3, sec)".into()),
+                                ordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
+                                unordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [
-                                    "b = 0".into(),
+                                    "b = 0".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("start".into()),
                             },
                             Transition {
                                 ordered_condition: Condition::constant(true),
-                                unordered_condition: !Condition::atom("after(3, sec)".into()),
+                                unordered_condition: !Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
                             },
@@ -262,6 +281,7 @@ fn test_factoring_2() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -346,6 +366,7 @@ fn test_simple_no_handler() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 3".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -377,13 +398,13 @@ fn test_single_transition() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -404,6 +425,7 @@ fn test_single_transition() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -466,6 +488,7 @@ fn test_multiple_machines_1() {
                 ].into_iter().collect(),
                 initial_state: Some("foo".into()),
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
             ("machine 2".into(), StateMachine {
                 variables: [].into_iter().collect(),
@@ -506,6 +529,7 @@ fn test_multiple_machines_1() {
                 ].into_iter().collect(),
                 initial_state: Some("bar".into()),
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -550,13 +574,13 @@ fn test_simple_if_timer() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -577,6 +601,7 @@ fn test_simple_if_timer() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -597,15 +622,15 @@ fn test_if_timer_reset_1() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()),
                                 actions: [
-                                    "t = 0".into(),
+                                    "t = 0".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -626,6 +651,7 @@ fn test_if_timer_reset_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -646,15 +672,15 @@ fn test_if_timer_reset_2() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()),
                                 actions: [
-                                    "t = 0".into(),
+                                    "t = 0".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -675,6 +701,7 @@ fn test_if_timer_reset_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -695,13 +722,13 @@ fn test_if_timer_reset_3() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -715,7 +742,7 @@ fn test_if_timer_reset_3() {
                                 ordered_condition: Condition::constant(true),
                                 unordered_condition: Condition::constant(true),
                                 actions: [
-                                    "t = 0".into(),
+                                    "t = 0".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("thing 1".into()),
                             },
@@ -724,6 +751,7 @@ fn test_if_timer_reset_3() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -744,13 +772,13 @@ fn test_no_transitions_1() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -764,7 +792,7 @@ fn test_no_transitions_1() {
                                 ordered_condition: Condition::constant(true),
                                 unordered_condition: Condition::constant(true),
                                 actions: [
-                                    "t = 0".into(),
+                                    "t = 0".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: None,
                             },
@@ -773,6 +801,7 @@ fn test_no_transitions_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -793,13 +822,13 @@ fn test_no_transitions_2() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -820,6 +849,7 @@ fn test_no_transitions_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -840,19 +870,13 @@ fn test_if_chain_1() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 9".into()),
-                                ordered_condition: Condition::atom("t > 9".into()),
+                                unordered_condition: Condition::atom("t > 9".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 9".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 3".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t > 9".into()) & Condition::atom("t > 10".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 2".into()),
-                            },
-                            Transition {
-                                unordered_condition: !Condition::atom("t > 9".into()) & !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 9".parse().unwrap()) & !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -884,6 +908,7 @@ fn test_if_chain_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 3".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -904,25 +929,13 @@ fn test_if_chain_2() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 8".into()),
-                                ordered_condition: Condition::atom("t > 8".into()),
+                                unordered_condition: Condition::atom("t > 8".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 8".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 4".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t > 8".into()) & Condition::atom("t > 9".into()),
-                                ordered_condition: Condition::atom("t > 9".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 3".into()),
-                            },
-                            Transition {
-                                unordered_condition: !Condition::atom("t > 8".into()) & !Condition::atom("t > 9".into()) & Condition::atom("t > 10".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 2".into()),
-                            },
-                            Transition {
-                                unordered_condition: !Condition::atom("t > 8".into()) & !Condition::atom("t > 9".into()) & !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 8".parse().unwrap()) & !Condition::atom("t > 9".parse().unwrap()) & !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -940,17 +953,6 @@ fn test_if_chain_2() {
                             },
                         ].into_iter().collect(),
                     }),
-                    ("thing 3".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                unordered_condition: Condition::constant(true),
-                                ordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                     ("thing 4".into(), State {
                         parent: None,
                         transitions: [
@@ -965,6 +967,7 @@ fn test_if_chain_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 4".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -974,11 +977,8 @@ digraph "untitled" {
   subgraph "something" {
     "something thing 1"[label="thing 1"]
     "something thing 2"[label="thing 2"]
-    "something thing 3"[label="thing 3"]
     "something thing 4"[label="thing 4"]
-    "something thing 1" -> "something thing 4" [label=" 1: t > 8 "]
-    "something thing 1" -> "something thing 3" [label=" 2: t > 9 "]
-    "something thing 1" -> "something thing 2" [label=" 3: t > 10 "]
+    "something thing 1" -> "something thing 4" [label=" t > 8 "]
     "something thing 2" -> "something thing 1" [label=""]
   }
 }
@@ -999,13 +999,13 @@ fn test_nested_if_1() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 0".into()),
@@ -1037,6 +1037,7 @@ fn test_nested_if_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 0".into(), "thing 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1057,13 +1058,13 @@ fn test_nested_if_2() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !(Condition::atom("t > 10".into()) & Condition::atom("t > 9".into())),
+                                unordered_condition: !(Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap())),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 0".into()),
@@ -1095,6 +1096,7 @@ fn test_nested_if_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 0".into(), "thing 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1115,19 +1117,13 @@ fn test_nested_if_3() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & !Condition::atom("t > 9".into()) & Condition::atom("t > 8".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 8".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 3".into()),
-                            },
-                            Transition {
-                                unordered_condition: !(Condition::atom("t > 10".into()) & (Condition::atom("t > 9".into()) | Condition::atom("t > 8".into()))),
+                                unordered_condition: !(Condition::atom("t > 10".parse().unwrap()) & (Condition::atom("t > 9".parse().unwrap()) | Condition::atom("t > 8".parse().unwrap()))),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 0".into()),
@@ -1156,20 +1152,10 @@ fn test_nested_if_3() {
                             },
                         ].into_iter().collect(),
                     }),
-                    ("thing 3".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 0".into(), "thing 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1190,25 +1176,13 @@ fn test_nested_if_4() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & Condition::atom("t > 7".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & Condition::atom("t > 7".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()) & Condition::atom("t > 7".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()) & Condition::atom("t > 7".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 4".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & !Condition::atom("t > 7".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 3".into()),
-                            },
-                            Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & !Condition::atom("t > 9".into()) & Condition::atom("t > 8".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 8".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 2".into()),
-                            },
-                            Transition {
-                                unordered_condition: !(Condition::atom("t > 10".into()) & ((Condition::atom("t > 9".into()) & Condition::atom("t > 7".into())) | Condition::atom("t > 9".into()) | Condition::atom("t > 8".into()))),
+                                unordered_condition: !(Condition::atom("t > 10".parse().unwrap()) & ((Condition::atom("t > 9".parse().unwrap()) & Condition::atom("t > 7".parse().unwrap())) | Condition::atom("t > 9".parse().unwrap()) | Condition::atom("t > 8".parse().unwrap()))),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 0".into()),
@@ -1224,28 +1198,6 @@ fn test_nested_if_4() {
                             new_state: None,
                         },].into_iter().collect(),
                     }),
-                    ("thing 2".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
-                    ("thing 3".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                     ("thing 4".into(), State {
                         parent: None,
                         transitions: [
@@ -1260,6 +1212,7 @@ fn test_nested_if_4() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 0".into(), "thing 4".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1280,31 +1233,13 @@ fn test_nested_if_5() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & Condition::atom("t > 7".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & Condition::atom("t > 7".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()) & Condition::atom("t > 7".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()) & Condition::atom("t > 7".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 4".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & !Condition::atom("t > 7".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 3".into()),
-                            },
-                            Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & !Condition::atom("t > 9".into()) & Condition::atom("t > 8".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 8".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 2".into()),
-                            },
-                            Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & !Condition::atom("t > 9".into()) & !Condition::atom("t > 8".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 5".into()),
-                            },
-                            Transition {
-                                unordered_condition: !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 0".into()),
@@ -1322,28 +1257,6 @@ fn test_nested_if_5() {
                             },
                         ].into_iter().collect(),
                     }),
-                    ("thing 2".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
-                    ("thing 3".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                     ("thing 4".into(), State {
                         parent: None,
                         transitions: [
@@ -1355,20 +1268,10 @@ fn test_nested_if_5() {
                             },
                         ].into_iter().collect(),
                     }),
-                    ("thing 5".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 0".into(), "thing 4".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1384,54 +1287,27 @@ fn test_nested_if_6() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & Condition::atom("t > 7".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & Condition::atom("t > 7".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()) & Condition::atom("t > 7".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()) & Condition::atom("t > 7".parse().unwrap()),
                                 actions: [
-                                    "foo = 234".into(),
-                                    "foo = 652".into(),
+                                    "foo = 234".parse().unwrap(),
+                                    "foo = 652".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("thing 4".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & !Condition::atom("t > 7".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()),
-                                actions: [
-                                    "foo = 123".into(),
-                                    "foo = 453".into(),
-                                ].into_iter().collect(),
-                                new_state: Some("thing 3".into()),
-                            },
-                            Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & !Condition::atom("t > 9".into()) & Condition::atom("t > 8".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 8".into()),
-                                actions: [
-                                    "foo = 546".into(),
-                                    "foo = 876".into(),
-                                ].into_iter().collect(),
-                                new_state: Some("thing 2".into()),
-                            },
-                            Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & !Condition::atom("t > 9".into()) & !Condition::atom("t > 8".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
-                                actions: [
-                                    "foo = 431".into(),
-                                    "foo = 197".into(),
-                                ].into_iter().collect(),
-                                new_state: Some("thing 5".into()),
-                            },
-                            Transition {
-                                unordered_condition: !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = 856".into(),
-                                    "foo = 465".into(),
+                                    "foo = 856".parse().unwrap(),
+                                    "foo = 465".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("thing 0".into()),
                             },
@@ -1448,28 +1324,6 @@ fn test_nested_if_6() {
                             },
                         ].into_iter().collect(),
                     }),
-                    ("thing 2".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
-                    ("thing 3".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                     ("thing 4".into(), State {
                         parent: None,
                         transitions: [
@@ -1481,20 +1335,10 @@ fn test_nested_if_6() {
                             },
                         ].into_iter().collect(),
                     }),
-                    ("thing 5".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 0".into(), "thing 4".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1510,7 +1354,7 @@ fn test_variables_1() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
@@ -1520,8 +1364,8 @@ fn test_variables_1() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = 14".into(),
-                                    "foo = 21".into(),
+                                    "foo = 14".parse().unwrap(),
+                                    "foo = 21".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
@@ -1534,8 +1378,8 @@ fn test_variables_1() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = 76".into(),
-                                    "foo = 43".into(),
+                                    "foo = 76".parse().unwrap(),
+                                    "foo = 43".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("thing 1".into()),
                             },
@@ -1544,6 +1388,7 @@ fn test_variables_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1559,7 +1404,7 @@ fn test_variables_2() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
@@ -1569,8 +1414,8 @@ fn test_variables_2() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = 14".into(),
-                                    "foo = 21".into(),
+                                    "foo = 14".parse().unwrap(),
+                                    "foo = 21".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
@@ -1583,8 +1428,8 @@ fn test_variables_2() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = 76".into(),
-                                    "foo = 43".into(),
+                                    "foo = 76".parse().unwrap(),
+                                    "foo = 43".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("thing 1".into()),
                             },
@@ -1593,10 +1438,11 @@ fn test_variables_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
             ("another".into(), StateMachine {
                 variables: [
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("test 1".into(), State {
@@ -1606,8 +1452,8 @@ fn test_variables_2() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "bar = 231".into(),
-                                    "bar = 453".into(),
+                                    "bar = 231".parse().unwrap(),
+                                    "bar = 453".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("test 2".into()),
                             },
@@ -1620,8 +1466,8 @@ fn test_variables_2() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "bar = 432".into(),
-                                    "bar = 646".into(),
+                                    "bar = 432".parse().unwrap(),
+                                    "bar = 646".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("test 1".into()),
                             },
@@ -1630,6 +1476,7 @@ fn test_variables_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1657,9 +1504,9 @@ fn test_var_inits() {
         state_machines: [
             ("thingy g".into(), StateMachine {
                 variables: [
-                    ("foo_3".into(), Variable { init: "(7 + 2)".into(), kind: VariableKind::Local }),
-                    ("bar_5".into(), Variable { init: "(4 * 4)".into(), kind: VariableKind::Local }),
-                    ("baz_b".into(), Variable { init: "(3 ^ 2)".into(), kind: VariableKind::Local }),
+                    ("foo_3".into(), Variable { init: "(7 + 2)".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar_5".into(), Variable { init: "(4 * 4)".parse().unwrap(), kind: VariableKind::Local }),
+                    ("baz_b".into(), Variable { init: "(3 ^ 2)".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("merp derp".into(), State {
@@ -1669,9 +1516,9 @@ fn test_var_inits() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo_3 = (foo_3 * 2)".into(),
-                                    "bar_5 = bar_5 + (1 + 1)".into(),
-                                    "baz_b = (bar_5 + foo_3)".into(),
+                                    "foo_3 = (foo_3 * 2)".parse().unwrap(),
+                                    "bar_5 = bar_5 + (1 + 1)".parse().unwrap(),
+                                    "baz_b = (bar_5 + foo_3)".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("derp merp".into()),
                             },
@@ -1684,9 +1531,9 @@ fn test_var_inits() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo_3 = (foo_3 * 0.1)".into(),
-                                    "bar_5 = bar_5 + (1 + -4)".into(),
-                                    "baz_b = (bar_5 - foo_3)".into(),
+                                    "foo_3 = (foo_3 * 0.1)".parse().unwrap(),
+                                    "bar_5 = bar_5 + (1 + -4)".parse().unwrap(),
+                                    "baz_b = (bar_5 - foo_3)".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("merp derp".into()),
                             },
@@ -1695,6 +1542,7 @@ fn test_var_inits() {
                 ].into_iter().collect(),
                 initial_state: Some("merp derp".into()),
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1746,9 +1594,9 @@ fn test_var_kinds_1() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Input }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("baz".into(), Variable { init: "0".into(), kind: VariableKind::Output }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Input }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("baz".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Output }),
                 ].into_iter().collect(),
                 states: [
                     ("start".into(), State {
@@ -1758,8 +1606,8 @@ fn test_var_kinds_1() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "baz = bar".into(),
-                                    "bar = foo".into(),
+                                    "baz = bar".parse().unwrap(),
+                                    "bar = foo".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: None,
                             },
@@ -1768,6 +1616,7 @@ fn test_var_kinds_1() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1812,21 +1661,21 @@ fn test_if_else_1() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("foo == bar".into()),
-                                ordered_condition: Condition::atom("foo == bar".into()),
+                                unordered_condition: Condition::atom("foo == bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo == bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo == bar".into()),
+                                unordered_condition: !Condition::atom("foo == bar".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 3".into()),
@@ -1858,6 +1707,7 @@ fn test_if_else_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 2".into(), "thing 3".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1867,19 +1717,19 @@ fn test_if_else_1() {
 #[test]
 fn test_if_else_2() {
     let err = Project::compile(include_str!("projects/if-else-2.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0, 0, 0], collab_id: Some("item_131".into()) }) });
 }
 
 #[test]
 fn test_if_else_3() {
     let err = Project::compile(include_str!("projects/if-else-3.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0, 1, 0], collab_id: Some("item_134".into()) }) });
 }
 
 #[test]
 fn test_if_else_4() {
     let err = Project::compile(include_str!("projects/if-else-4.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0, 0, 0], collab_id: Some("item_131".into()) }) });
 }
 
 #[test]
@@ -1891,21 +1741,21 @@ fn test_if_else_5() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("foo == bar".into()),
-                                ordered_condition: Condition::atom("foo == bar".into()),
+                                unordered_condition: Condition::atom("foo == bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo == bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo == bar".into()),
+                                unordered_condition: !Condition::atom("foo == bar".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 3".into()),
@@ -1937,6 +1787,7 @@ fn test_if_else_5() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 2".into(), "thing 3".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -1952,21 +1803,21 @@ fn test_if_else_6() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("foo == bar".into()),
-                                ordered_condition: Condition::atom("foo == bar".into()),
+                                unordered_condition: Condition::atom("foo == bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo == bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo == bar".into()),
+                                unordered_condition: !Condition::atom("foo == bar".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 4".into()),
@@ -1998,6 +1849,7 @@ fn test_if_else_6() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 2".into(), "thing 4".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2013,21 +1865,21 @@ fn test_if_else_7() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: !Condition::atom("foo == bar".into()),
-                                ordered_condition: !Condition::atom("foo == bar".into()),
+                                unordered_condition: !Condition::atom("foo == bar".parse().unwrap()),
+                                ordered_condition: !Condition::atom("foo == bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("foo == bar".into()),
+                                unordered_condition: Condition::atom("foo == bar".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 4".into()),
@@ -2059,6 +1911,7 @@ fn test_if_else_7() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 2".into(), "thing 4".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2074,8 +1927,8 @@ fn test_if_else_8() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
@@ -2103,6 +1956,7 @@ fn test_if_else_8() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 4".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2112,25 +1966,25 @@ fn test_if_else_8() {
 #[test]
 fn test_if_else_9() {
     let err = Project::compile(include_str!("projects/if-else-9.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::ActionsOutsideTransition { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::ActionsOutsideTransition { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0, 0], collab_id: Some("item_126".into()) }) });
 }
 
 #[test]
 fn test_if_else_10() {
     let err = Project::compile(include_str!("projects/if-else-10.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::ActionsOutsideTransition { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::ActionsOutsideTransition { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0, 1], collab_id: Some("item_126".into()) }) });
 }
 
 #[test]
 fn test_if_else_11() {
     let err = Project::compile(include_str!("projects/if-else-11.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::ActionsOutsideTransition { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::ActionsOutsideTransition { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0, 0], collab_id: Some("item_126".into()) }) });
 }
 
 #[test]
 fn test_if_else_12() {
     let err = Project::compile(include_str!("projects/if-else-12.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::ActionsOutsideTransition { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::ActionsOutsideTransition { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0, 0], collab_id: Some("item_126".into()) }) });
 }
 
 #[test]
@@ -2142,20 +1996,20 @@ fn test_if_else_13() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == 6".into()),
-                                ordered_condition: Condition::atom("a == 6".into()),
+                                unordered_condition: Condition::atom("a == 6".parse().unwrap()),
+                                ordered_condition: Condition::atom("a == 6".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("second".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("a == 6".into()),
+                                unordered_condition: !Condition::atom("a == 6".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -2176,6 +2030,7 @@ fn test_if_else_13() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["second".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2191,20 +2046,20 @@ fn test_if_else_14() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: !Condition::atom("a == 6".into()),
-                                ordered_condition: !Condition::atom("a == 6".into()),
+                                unordered_condition: !Condition::atom("a == 6".parse().unwrap()),
+                                ordered_condition: !Condition::atom("a == 6".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("second".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("a == 6".into()),
+                                unordered_condition: Condition::atom("a == 6".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -2225,6 +2080,7 @@ fn test_if_else_14() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["second".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2240,20 +2096,20 @@ fn test_if_else_15() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: !Condition::atom("a == 6".into()),
-                                ordered_condition: !Condition::atom("a == 6".into()),
+                                unordered_condition: !Condition::atom("a == 6".parse().unwrap()),
+                                ordered_condition: !Condition::atom("a == 6".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("fourth".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("a == 6".into()),
+                                unordered_condition: Condition::atom("a == 6".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -2274,6 +2130,7 @@ fn test_if_else_15() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["fourth".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2289,20 +2146,20 @@ fn test_if_else_16() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: !Condition::atom("a == 6".into()),
-                                ordered_condition: !Condition::atom("a == 6".into()),
+                                unordered_condition: !Condition::atom("a == 6".parse().unwrap()),
+                                ordered_condition: !Condition::atom("a == 6".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("fourth".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("a == 6".into()),
+                                unordered_condition: Condition::atom("a == 6".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -2323,6 +2180,7 @@ fn test_if_else_16() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["fourth".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2338,8 +2196,8 @@ fn test_if_else_17() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
@@ -2349,8 +2207,8 @@ fn test_if_else_17() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "t = 0".into(),
-                                    "t = 0".into(),
+                                    "t = 0".parse().unwrap(),
+                                    "t = 0".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
@@ -2360,13 +2218,13 @@ fn test_if_else_17() {
                         parent: Some("thing 1".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("foo == bar".into()),
-                                ordered_condition: Condition::atom("foo == bar".into()),
+                                unordered_condition: Condition::atom("foo == bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo == bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo == bar".into()),
+                                unordered_condition: !Condition::atom("foo == bar".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 3".into()),
@@ -2398,6 +2256,7 @@ fn test_if_else_17() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 2".into(), "thing 3".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2413,8 +2272,8 @@ fn test_if_else_18() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
@@ -2424,9 +2283,9 @@ fn test_if_else_18() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = 1".into(),
-                                    "foo = 2".into(),
-                                    "foo = 3".into(),
+                                    "foo = 1".parse().unwrap(),
+                                    "foo = 2".parse().unwrap(),
+                                    "foo = 3".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
@@ -2436,13 +2295,13 @@ fn test_if_else_18() {
                         parent: Some("thing 1".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("foo == bar".into()),
-                                ordered_condition: Condition::atom("foo == bar".into()),
+                                unordered_condition: Condition::atom("foo == bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo == bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo == bar".into()),
+                                unordered_condition: !Condition::atom("foo == bar".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 3".into()),
@@ -2474,6 +2333,7 @@ fn test_if_else_18() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 2".into(), "thing 3".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2489,8 +2349,8 @@ fn test_if_else_19() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
@@ -2500,9 +2360,9 @@ fn test_if_else_19() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = 1".into(),
-                                    "foo = 2".into(),
-                                    "foo = 3".into(),
+                                    "foo = 1".parse().unwrap(),
+                                    "foo = 2".parse().unwrap(),
+                                    "foo = 3".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
@@ -2512,13 +2372,13 @@ fn test_if_else_19() {
                         parent: Some("thing 1".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("foo == bar".into()),
-                                ordered_condition: Condition::atom("foo == bar".into()),
+                                unordered_condition: Condition::atom("foo == bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo == bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo == bar".into()),
+                                unordered_condition: !Condition::atom("foo == bar".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 3".into()),
@@ -2550,6 +2410,7 @@ fn test_if_else_19() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 2".into(), "thing 3".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2559,43 +2420,43 @@ fn test_if_else_19() {
 #[test]
 fn test_if_fall_through_1() {
     let err = Project::compile(include_str!("projects/if-fall-through-1.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0, 0, 0], collab_id: Some("item_5".into()) }) });
 }
 
 #[test]
 fn test_if_fall_through_2() {
     let err = Project::compile(include_str!("projects/if-fall-through-2.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![1, 0, 0], collab_id: Some("item_52_2".into()) }) });
 }
 
 #[test]
 fn test_simple_fall_through_1() {
     let err = Project::compile(include_str!("projects/simple-fall-through-1.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0], collab_id: Some("item_5".into()) }) });
 }
 
 #[test]
 fn test_simple_fall_through_2() {
     let err = Project::compile(include_str!("projects/simple-fall-through-2.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![1], collab_id: Some("item_5".into()) }) });
 }
 
 #[test]
 fn test_multiple_handlers() {
     let err = Project::compile(include_str!("projects/multiple-handlers.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::MultipleHandlers { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::MultipleHandlers { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 1, block_path: vec![], collab_id: Some("item_4".into()) }) });
 }
 
 #[test]
 fn test_complex_transition_1() {
     let err = Project::compile(include_str!("projects/complex-transition-1.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::ComplexTransitionName { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::ComplexTransitionName { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0], collab_id: Some("item_16".into()) }) });
 }
 
 #[test]
 fn test_complex_transition_2() {
     let err = Project::compile(include_str!("projects/complex-transition-2.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::ComplexTransitionName { state_machine: "something".into(), state: "thing 1".into() });
+    assert_eq!(err, CompileError::ComplexTransitionName { state_machine: "something".into(), state: "thing 1".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0], collab_id: Some("item_18".into()) }) });
 }
 
 #[test]
@@ -2607,25 +2468,25 @@ fn test_tail_actions_1() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("foo > bar".into()),
-                                ordered_condition: Condition::atom("foo > bar".into()),
+                                unordered_condition: Condition::atom("foo > bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo > bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo > bar".into()),
+                                unordered_condition: !Condition::atom("foo > bar".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = (2 * foo * 2)".into(),
-                                    "bar = (3 * bar)".into(),
+                                    "foo = (2 * foo * 2)".parse().unwrap(),
+                                    "bar = (3 * bar)".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: None,
                             },
@@ -2645,6 +2506,7 @@ fn test_tail_actions_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2660,100 +2522,94 @@ fn test_operators() {
         state_machines: [
             ("something".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("thing 1".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("foo < bar".into()),
-                                ordered_condition: Condition::atom("foo < bar".into()),
+                                unordered_condition: Condition::atom("foo < bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo < bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo < bar".into()) & Condition::atom("foo <= bar".into()),
-                                ordered_condition: Condition::atom("foo <= bar".into()),
+                                unordered_condition: !Condition::atom("foo < bar".parse().unwrap()) & Condition::atom("foo <= bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo <= bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 3".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo < bar".into()) & !Condition::atom("foo <= bar".into()) & Condition::atom("foo > bar".into()),
-                                ordered_condition: Condition::atom("foo > bar".into()),
+                                unordered_condition: !Condition::atom("foo < bar".parse().unwrap()) & !Condition::atom("foo <= bar".parse().unwrap()) & Condition::atom("foo > bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo > bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 4".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo < bar".into()) & !Condition::atom("foo <= bar".into()) & !Condition::atom("foo > bar".into()) & Condition::atom("foo >= bar".into()),
-                                ordered_condition: Condition::atom("foo >= bar".into()),
+                                unordered_condition: !Condition::atom("foo < bar".parse().unwrap()) & !Condition::atom("foo <= bar".parse().unwrap()) & !Condition::atom("foo > bar".parse().unwrap()) & Condition::atom("foo >= bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo >= bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 5".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo < bar".into()) & !Condition::atom("foo <= bar".into()) & !Condition::atom("foo > bar".into()) & !Condition::atom("foo >= bar".into()) & Condition::atom("foo == bar".into()),
-                                ordered_condition: Condition::atom("foo == bar".into()),
+                                unordered_condition: !Condition::atom("foo < bar".parse().unwrap()) & !Condition::atom("foo <= bar".parse().unwrap()) & !Condition::atom("foo > bar".parse().unwrap()) & !Condition::atom("foo >= bar".parse().unwrap()) & Condition::atom("foo == bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo == bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 6".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo < bar".into()) & !Condition::atom("foo <= bar".into()) & !Condition::atom("foo > bar".into()) & !Condition::atom("foo >= bar".into()) & !Condition::atom("foo == bar".into()) & Condition::atom("foo ~= bar".into()),
-                                ordered_condition: Condition::atom("foo ~= bar".into()),
+                                unordered_condition: !Condition::atom("foo < bar".parse().unwrap()) & !Condition::atom("foo <= bar".parse().unwrap()) & !Condition::atom("foo > bar".parse().unwrap()) & !Condition::atom("foo >= bar".parse().unwrap()) & !Condition::atom("foo == bar".parse().unwrap()) & Condition::atom("foo ~= bar".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo ~= bar".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 7".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo < bar".into()) & !Condition::atom("foo <= bar".into()) & !Condition::atom("foo > bar".into()) & !Condition::atom("foo >= bar".into()) & !Condition::atom("foo == bar".into()) & !Condition::atom("foo ~= bar".into()) & (Condition::atom("foo < 4".into()) & Condition::constant(true)),
-                                ordered_condition: Condition::atom("foo < 4".into()) & Condition::constant(true),
+                                unordered_condition: !Condition::atom("foo < bar".parse().unwrap()) & !Condition::atom("foo <= bar".parse().unwrap()) & !Condition::atom("foo > bar".parse().unwrap()) & !Condition::atom("foo >= bar".parse().unwrap()) & !Condition::atom("foo == bar".parse().unwrap()) & !Condition::atom("foo ~= bar".parse().unwrap()) & (Condition::atom("foo < 4".parse().unwrap()) & Condition::constant(true)),
+                                ordered_condition: Condition::atom("foo < 4".parse().unwrap()) & Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 8".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo < bar".into()) & !Condition::atom("foo <= bar".into()) & !Condition::atom("foo > bar".into()) & !Condition::atom("foo >= bar".into()) & !Condition::atom("foo == bar".into()) & !Condition::atom("foo ~= bar".into()) & !(Condition::atom("foo < 4".into()) & Condition::constant(true)) & (Condition::constant(false) | Condition::atom("foo > 4".into())),
-                                ordered_condition: Condition::constant(false) | Condition::atom("foo > 4".into()),
+                                unordered_condition: !Condition::atom("foo < bar".parse().unwrap()) & !Condition::atom("foo <= bar".parse().unwrap()) & !Condition::atom("foo > bar".parse().unwrap()) & !Condition::atom("foo >= bar".parse().unwrap()) & !Condition::atom("foo == bar".parse().unwrap()) & !Condition::atom("foo ~= bar".parse().unwrap()) & !(Condition::atom("foo < 4".parse().unwrap()) & Condition::constant(true)) & (Condition::constant(false) | Condition::atom("foo > 4".parse().unwrap())),
+                                ordered_condition: Condition::constant(false) | Condition::atom("foo > 4".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 9".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo < bar".into()) & !Condition::atom("foo <= bar".into()) & !Condition::atom("foo > bar".into()) & !Condition::atom("foo >= bar".into()) & !Condition::atom("foo == bar".into()) & !Condition::atom("foo ~= bar".into()) & !(Condition::atom("foo < 4".into()) & Condition::constant(true)) & !(Condition::constant(false) | Condition::atom("foo > 4".into())) & !Condition::atom("foo == 4".into()),
-                                ordered_condition: !Condition::atom("foo == 4".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 10".into()),
-                            },
-                            Transition {
-                                unordered_condition: !Condition::atom("foo < bar".into()) & !Condition::atom("foo <= bar".into()) & !Condition::atom("foo > bar".into()) & !Condition::atom("foo >= bar".into()) & !Condition::atom("foo == bar".into()) & !Condition::atom("foo ~= bar".into()) & !(Condition::atom("foo < 4".into()) & Condition::constant(true)) & !(Condition::constant(false) | Condition::atom("foo > 4".into())) & !!Condition::atom("foo == 4".into()),
+                                unordered_condition: !Condition::atom("foo < bar".parse().unwrap()) & !Condition::atom("foo <= bar".parse().unwrap()) & !Condition::atom("foo > bar".parse().unwrap()) & !Condition::atom("foo >= bar".parse().unwrap()) & !Condition::atom("foo == bar".parse().unwrap()) & !Condition::atom("foo ~= bar".parse().unwrap()) & !(Condition::atom("foo < 4".parse().unwrap()) & Condition::constant(true)) & !(Condition::constant(false) | Condition::atom("foo > 4".parse().unwrap())) & !!Condition::atom("foo == 4".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = (foo + bar)".into(),
-                                    "foo = (foo + 7 + bar)".into(),
-                                    "foo = (foo - bar)".into(),
-                                    "foo = (foo * bar)".into(),
-                                    "foo = (foo * 5 * bar)".into(),
-                                    "foo = (bar / foo)".into(),
-                                    "foo = (6 ^ foo)".into(),
-                                    "foo = mod(bar, foo)".into(),
-                                    "foo = round(3.14159)".into(),
-                                    "foo = atan2d(foo, bar)".into(),
-                                    "foo = abs(3.1415)".into(),
-                                    "foo = -3.1415".into(),
-                                    "foo = sign(3.1415)".into(),
-                                    "foo = ceil(3.1415)".into(),
-                                    "foo = floor(3.1415)".into(),
-                                    "foo = sqrt(3.1415)".into(),
-                                    "foo = sind(3.1415)".into(),
-                                    "foo = cosd(3.1415)".into(),
-                                    "foo = tand(3.1415)".into(),
-                                    "foo = asind(3.1415)".into(),
-                                    "foo = acosd(3.1415)".into(),
-                                    "foo = atand(3.1415)".into(),
-                                    "foo = (log(3.1415) / log(2.718281828459045))".into(),
-                                    "foo = (log(3.1415) / log(10.0))".into(),
-                                    "foo = (log(3.1415) / log(2.0))".into(),
-                                    "foo = (2.718281828459045 ^ 3.1415)".into(),
-                                    "foo = (10.0 ^ 3.1415)".into(),
-                                    "foo = (2.0 ^ 3.1415)".into(),
-                                    "foo = 3.1415".into(),
+                                    "foo = (foo + bar)".parse().unwrap(),
+                                    "foo = (foo + 7 + bar)".parse().unwrap(),
+                                    "foo = (foo - bar)".parse().unwrap(),
+                                    "foo = (foo * bar)".parse().unwrap(),
+                                    "foo = (foo * 5 * bar)".parse().unwrap(),
+                                    "foo = (bar / foo)".parse().unwrap(),
+                                    "foo = (6 ^ foo)".parse().unwrap(),
+                                    "foo = mod(bar, foo)".parse().unwrap(),
+                                    "foo = round(3.14159)".parse().unwrap(),
+                                    "foo = atan2d(foo, bar)".parse().unwrap(),
+                                    "foo = abs(3.1415)".parse().unwrap(),
+                                    "foo = -3.1415".parse().unwrap(),
+                                    "foo = sign(3.1415)".parse().unwrap(),
+                                    "foo = ceil(3.1415)".parse().unwrap(),
+                                    "foo = floor(3.1415)".parse().unwrap(),
+                                    "foo = sqrt(3.1415)".parse().unwrap(),
+                                    "foo = sind(3.1415)".parse().unwrap(),
+                                    "foo = cosd(3.1415)".parse().unwrap(),
+                                    "foo = tand(3.1415)".parse().unwrap(),
+                                    "foo = asind(3.1415)".parse().unwrap(),
+                                    "foo = acosd(3.1415)".parse().unwrap(),
+                                    "foo = atand(3.1415)".parse().unwrap(),
+                                    "foo = (log(3.1415) / log(2.718281828459045))".parse().unwrap(),
+                                    "foo = (log(3.1415) / log(10.0))".parse().unwrap(),
+                                    "foo = (log(3.1415) / log(2.0))".parse().unwrap(),
+                                    "foo = (2.718281828459045 ^ 3.1415)".parse().unwrap(),
+                                    "foo = (10.0 ^ 3.1415)".parse().unwrap(),
+                                    "foo = (2.0 ^ 3.1415)".parse().unwrap(),
+                                    "foo = 3.1415".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: None,
                             },
@@ -2847,20 +2703,10 @@ fn test_operators() {
                             },
                         ].into_iter().collect(),
                     }),
-                    ("thing 10".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 2".into(), "thing 3".into(), "thing 4".into(), "thing 5".into(), "thing 6".into(), "thing 7".into(), "thing 8".into(), "thing 9".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2876,7 +2722,7 @@ fn test_actions_1() {
         state_machines: [
             ("state".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("state 1".into(), State {
@@ -2886,7 +2732,7 @@ fn test_actions_1() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = 456".into()
+                                    "foo = 456".parse().unwrap()
                                 ].into_iter().collect(),
                                 new_state: None,
                             }
@@ -2895,6 +2741,7 @@ fn test_actions_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2910,8 +2757,8 @@ fn test_actions_2() {
         state_machines: [
             ("state".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("bar".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("bar".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("state 1".into(), State {
@@ -2921,7 +2768,7 @@ fn test_actions_2() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = (foo + bar)".into()
+                                    "foo = (foo + bar)".parse().unwrap()
                                 ].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             }
@@ -2942,13 +2789,13 @@ fn test_actions_2() {
                         parent: Some("state 1".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("foo < 7".into()),
-                                ordered_condition: Condition::atom("foo < 7".into()),
+                                unordered_condition: Condition::atom("foo < 7".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo < 7".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("state 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo < 7".into()),
+                                unordered_condition: !Condition::atom("foo < 7".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("state 1".into()),
@@ -2958,6 +2805,7 @@ fn test_actions_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["state 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -2990,31 +2838,13 @@ fn test_ext_blocks_1() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & Condition::atom("t > 7".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & Condition::atom("t > 7".into()),
+                                unordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()) & Condition::atom("t > 7".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 10".parse().unwrap()) & Condition::atom("t > 9".parse().unwrap()) & Condition::atom("t > 7".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 4".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()) & !Condition::atom("t > 7".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 9".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 3".into()),
-                            },
-                            Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & !Condition::atom("t > 9".into()) & Condition::atom("t > 8".into()),
-                                ordered_condition: Condition::atom("t > 10".into()) & Condition::atom("t > 8".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 2".into()),
-                            },
-                            Transition {
-                                unordered_condition: Condition::atom("t > 10".into()) & !Condition::atom("t > 9".into()) & !Condition::atom("t > 8".into()),
-                                ordered_condition: Condition::atom("t > 10".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("thing 5".into()),
-                            },
-                            Transition {
-                                unordered_condition: !Condition::atom("t > 10".into()),
+                                unordered_condition: !Condition::atom("t > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("thing 0".into()),
@@ -3032,28 +2862,6 @@ fn test_ext_blocks_1() {
                             },
                         ].into_iter().collect(),
                     }),
-                    ("thing 2".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
-                    ("thing 3".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                     ("thing 4".into(), State {
                         parent: None,
                         transitions: [
@@ -3065,20 +2873,10 @@ fn test_ext_blocks_1() {
                             },
                         ].into_iter().collect(),
                     }),
-                    ("thing 5".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["thing 0".into(), "thing 4".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3099,13 +2897,13 @@ fn test_ite_1() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t < 10".into()),
-                                ordered_condition: Condition::atom("t < 10".into()),
+                                unordered_condition: Condition::atom("t < 10".parse().unwrap()),
+                                ordered_condition: Condition::atom("t < 10".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("bar".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t < 10".into()),
+                                unordered_condition: !Condition::atom("t < 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("baz".into()),
@@ -3137,6 +2935,7 @@ fn test_ite_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["bar".into(), "baz".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3157,25 +2956,19 @@ fn test_ite_2() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("t < 10".into()) & Condition::atom("t < 9".into()),
-                                ordered_condition: Condition::atom("t < 10".into()) & Condition::atom("t < 9".into()),
+                                unordered_condition: Condition::atom("t < 10".parse().unwrap()) & Condition::atom("t < 9".parse().unwrap()),
+                                ordered_condition: Condition::atom("t < 10".parse().unwrap()) & Condition::atom("t < 9".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("bar1".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("t < 10".into()) & !Condition::atom("t < 9".into()),
-                                ordered_condition: Condition::atom("t < 10".into()),
+                                unordered_condition: Condition::atom("t < 10".parse().unwrap()) & !Condition::atom("t < 9".parse().unwrap()),
+                                ordered_condition: Condition::atom("t < 10".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("bar2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("t < 10".into()) & Condition::atom("t < 8".into()),
-                                ordered_condition: Condition::atom("t < 8".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("baz1".into()),
-                            },
-                            Transition {
-                                unordered_condition: !Condition::atom("t < 10".into()) & !Condition::atom("t < 8".into()),
+                                unordered_condition: !Condition::atom("t < 10".parse().unwrap()) & !Condition::atom("t < 8".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("baz2".into()),
@@ -3204,17 +2997,6 @@ fn test_ite_2() {
                             },
                         ].into_iter().collect(),
                     }),
-                    ("baz1".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                     ("baz2".into(), State {
                         parent: None,
                         transitions: [
@@ -3229,6 +3011,7 @@ fn test_ite_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["bar1".into(), "bar2".into(), "baz2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3244,8 +3027,8 @@ fn test_var_names_1() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("another_var".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("some_var".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("another_var".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("some_var".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first state".into(), State {
@@ -3255,7 +3038,7 @@ fn test_var_names_1() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "some_var = (some_var * another_var)".into(),
+                                    "some_var = (some_var * another_var)".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("second state".into()),
                             },
@@ -3275,6 +3058,7 @@ fn test_var_names_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["second state".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3327,6 +3111,7 @@ fn test_initial_state_1() {
                 ].into_iter().collect(),
                 initial_state: Some("foo 4".into()),
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3424,6 +3209,7 @@ fn test_initial_state_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3476,6 +3262,7 @@ fn test_initial_state_3() {
                 ].into_iter().collect(),
                 initial_state: Some("foo 3".into()),
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3528,6 +3315,7 @@ fn test_initial_state_4() {
                 ].into_iter().collect(),
                 initial_state: Some("barb".into()),
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3537,17 +3325,24 @@ fn test_initial_state_4() {
 #[test]
 fn test_unknown_blocks_1() {
     let err = Project::compile(include_str!("projects/unknown-blocks-1.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::UnsupportedBlock { state_machine: "thing".into(), state: "foo".into(), info: "CallRpc { host: None, service: \"CloudVariables\", rpc: \"deleteVariable\", args: [(\"name\", Expr { kind: Value(String(\"foo\")), info: BlockInfo { comment: None, location: None } }), (\"password\", Expr { kind: Value(String(\"bar\")), info: BlockInfo { comment: None, location: None } })] }".into() });
+    assert_eq!(err, CompileError::UnsupportedBlock { state_machine: "thing".into(), state: "foo".into(), info: "CallRpc { host: None, service: \"CloudVariables\", rpc: \"deleteVariable\", args: [(\"name\", Expr { kind: Value(String(\"foo\")), info: BlockInfo { comment: None, location: None } }), (\"password\", Expr { kind: Value(String(\"bar\")), info: BlockInfo { comment: None, location: None } })] }".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![3], collab_id: Some("item_26".into()) }) });
 
-    let proj = Project::compile(include_str!("projects/unknown-blocks-1.xml"), None, Settings { omit_unknown_blocks: true, ..Settings::default() }).unwrap();
+    let proj = Project::compile(include_str!("projects/unknown-blocks-1.xml"), None, Settings { omit_unknown_blocks: true }).unwrap();
+    let CompileDiagnostics { project, errors, warnings } = Project::compile_with_diagnostics(include_str!("projects/unknown-blocks-1.xml"), None, Settings { omit_unknown_blocks: true });
+    assert_eq!((project.as_ref(), errors), (Some(&proj), vec![]));
+    assert_eq!(warnings, [
+        CompileWarning::OmittedBlock { state_machine: "thing".into(), state: "foo".into(), kind: "TurnRight".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![1], collab_id: Some("item_20".into()) }) },
+        CompileWarning::OmittedBlock { state_machine: "thing".into(), state: "foo".into(), kind: "CallRpc".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![3], collab_id: Some("item_26".into()) }) },
+    ]);
+    assert_eq!(warnings[0].to_string(), "state 'foo' of machine 'thing' uses an unsupported TurnRight block, which was replaced by '?' (sprite 'Sprite', script 1, block 2)");
     assert_eq!(proj, Project {
         name: "untitled".into(),
         role: "myRole".into(),
         state_machines: [
             ("thing".into(), StateMachine {
                 variables: [
-                    ("derp".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("merp".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("derp".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("merp".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("foo".into(), State {
@@ -3557,11 +3352,11 @@ fn test_unknown_blocks_1() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "merp = 10".into(),
-                                    "?".into(),
-                                    "merp = 20".into(),
-                                    "derp = ?".into(),
-                                    "merp = 30".into(),
+                                    "merp = 10".parse().unwrap(),
+                                    "?".parse().unwrap(),
+                                    "merp = 20".parse().unwrap(),
+                                    "derp = ?".parse().unwrap(),
+                                    "merp = 30".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: None,
                             },
@@ -3570,6 +3365,7 @@ fn test_unknown_blocks_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3579,17 +3375,17 @@ fn test_unknown_blocks_1() {
 #[test]
 fn test_unknown_blocks_2() {
     let err = Project::compile(include_str!("projects/unknown-blocks-2.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::UnsupportedBlock { state_machine: "thing".into(), state: "foo".into(), info: "CallRpc { host: None, service: \"CloudVariables\", rpc: \"deleteVariable\", args: [(\"name\", Expr { kind: Value(String(\"foo\")), info: BlockInfo { comment: None, location: None } }), (\"password\", Expr { kind: Value(String(\"bar\")), info: BlockInfo { comment: None, location: None } })] }".into() });
+    assert_eq!(err, CompileError::UnsupportedBlock { state_machine: "thing".into(), state: "foo".into(), info: "CallRpc { host: None, service: \"CloudVariables\", rpc: \"deleteVariable\", args: [(\"name\", Expr { kind: Value(String(\"foo\")), info: BlockInfo { comment: None, location: None } }), (\"password\", Expr { kind: Value(String(\"bar\")), info: BlockInfo { comment: None, location: None } })] }".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![2], collab_id: Some("item_26".into()) }) });
 
-    let proj = Project::compile(include_str!("projects/unknown-blocks-2.xml"), None, Settings { omit_unknown_blocks: true, ..Settings::default() }).unwrap();
+    let proj = Project::compile(include_str!("projects/unknown-blocks-2.xml"), None, Settings { omit_unknown_blocks: true }).unwrap();
     assert_eq!(proj, Project {
         name: "untitled".into(),
         role: "myRole".into(),
         state_machines: [
             ("thing".into(), StateMachine {
                 variables: [
-                    ("derp".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("merp".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("derp".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("merp".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("foo".into(), State {
@@ -3599,10 +3395,10 @@ fn test_unknown_blocks_2() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "merp = 10".into(),
-                                    "merp = 20".into(),
-                                    "derp = ?".into(),
-                                    "merp = 30".into(),
+                                    "merp = 10".parse().unwrap(),
+                                    "merp = 20".parse().unwrap(),
+                                    "derp = ?".parse().unwrap(),
+                                    "merp = 30".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: None,
                             },
@@ -3611,6 +3407,7 @@ fn test_unknown_blocks_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3620,16 +3417,16 @@ fn test_unknown_blocks_2() {
 #[test]
 fn test_unknown_blocks_3() {
     let err = Project::compile(include_str!("projects/unknown-blocks-3.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::UnsupportedBlock { state_machine: "thing".into(), state: "foo".into(), info: "TurnRight { angle: Expr { kind: Value(String(\"15\")), info: BlockInfo { comment: None, location: None } } }".into() });
+    assert_eq!(err, CompileError::UnsupportedBlock { state_machine: "thing".into(), state: "foo".into(), info: "TurnRight { angle: Expr { kind: Value(String(\"15\")), info: BlockInfo { comment: None, location: None } } }".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![1], collab_id: Some("item_20".into()) }) });
 
-    let proj = Project::compile(include_str!("projects/unknown-blocks-3.xml"), None, Settings { omit_unknown_blocks: true, ..Settings::default() }).unwrap();
+    let proj = Project::compile(include_str!("projects/unknown-blocks-3.xml"), None, Settings { omit_unknown_blocks: true }).unwrap();
     assert_eq!(proj, Project {
         name: "untitled".into(),
         role: "myRole".into(),
         state_machines: [
             ("thing".into(), StateMachine {
                 variables: [
-                    ("merp".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("merp".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("foo".into(), State {
@@ -3639,10 +3436,10 @@ fn test_unknown_blocks_3() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "merp = 10".into(),
-                                    "?".into(),
-                                    "merp = 20".into(),
-                                    "merp = 30".into(),
+                                    "merp = 10".parse().unwrap(),
+                                    "?".parse().unwrap(),
+                                    "merp = 20".parse().unwrap(),
+                                    "merp = 30".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: None,
                             },
@@ -3651,6 +3448,7 @@ fn test_unknown_blocks_3() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3660,9 +3458,13 @@ fn test_unknown_blocks_3() {
 #[test]
 fn test_unknown_blocks_4() {
     let err = Project::compile(include_str!("projects/unknown-blocks-4.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::UnsupportedBlock { state_machine: "player state".into(), state: "me stop".into(), info: "KeyDown { key: Expr { kind: Value(String(\"space\")), info: BlockInfo { comment: None, location: None } } }".into() });
+    assert_eq!(err, CompileError::UnsupportedBlock { state_machine: "player state".into(), state: "me stop".into(), info: "KeyDown { key: Expr { kind: Value(String(\"space\")), info: BlockInfo { comment: None, location: None } } }".into(), location: Box::new(Location { entity: "Sprite".into(), script: 1, block_path: vec![0], collab_id: Some("item_20".into()) }) });
 
-    let proj = Project::compile(include_str!("projects/unknown-blocks-4.xml"), None, Settings { omit_unknown_blocks: true, ..Settings::default() }).unwrap();
+    let proj = Project::compile(include_str!("projects/unknown-blocks-4.xml"), None, Settings { omit_unknown_blocks: true }).unwrap();
+    assert_eq!(Project::compile_with_diagnostics(include_str!("projects/unknown-blocks-4.xml"), None, Settings { omit_unknown_blocks: true }).warnings, [
+        CompileWarning::OmittedBlock { state_machine: "player state".into(), state: "me stop".into(), kind: "KeyDown".into(), location: Box::new(Location { entity: "Sprite".into(), script: 1, block_path: vec![0], collab_id: Some("item_20".into()) }) },
+        CompileWarning::OmittedBlock { state_machine: "player state".into(), state: "me go".into(), kind: "Forward".into(), location: Box::new(Location { entity: "Sprite".into(), script: 2, block_path: vec![0], collab_id: Some("item_32".into()) }) },
+    ]);
     assert_eq!(proj, Project {
         name: "untitled".into(),
         role: "myRole".into(),
@@ -3674,13 +3476,13 @@ fn test_unknown_blocks_4() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("?".into()),
-                                ordered_condition: Condition::atom("?".into()),
+                                unordered_condition: Condition::atom("?".parse().unwrap()),
+                                ordered_condition: Condition::atom("?".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("me go".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("?".into()),
+                                unordered_condition: !Condition::atom("?".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -3694,7 +3496,7 @@ fn test_unknown_blocks_4() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "?".into(),
+                                    "?".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("me stop".into()),
                             },
@@ -3703,6 +3505,7 @@ fn test_unknown_blocks_4() {
                 ].into_iter().collect(),
                 initial_state: Some("me stop".into()),
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3718,8 +3521,8 @@ fn test_rand_1() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("b".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("b".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("rolling".into(), State {
@@ -3729,10 +3532,10 @@ fn test_rand_1() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "a = randi(6)".into(),
-                                    "a = randi(9)".into(),
-                                    "a = randi([2, 4])".into(),
-                                    "b = randi([(a - b), (a + b)])".into(),
+                                    "a = randi(6)".parse().unwrap(),
+                                    "a = randi(9)".parse().unwrap(),
+                                    "a = randi([2, 4])".parse().unwrap(),
+                                    "b = randi([(a - b), (a + b)])".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: None,
                             },
@@ -3741,6 +3544,7 @@ fn test_rand_1() {
                 ].into_iter().collect(),
                 initial_state: Some("rolling".into()),
                 current_state: Some("rolling".into()),
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3782,6 +3586,7 @@ fn test_current_state_1() {
                 ].into_iter().collect(),
                 initial_state: Some("a".into()),
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3835,6 +3640,7 @@ fn test_current_state_2() {
                 ].into_iter().collect(),
                 initial_state: Some("a".into()),
                 current_state: Some("a".into()),
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3888,6 +3694,7 @@ fn test_current_state_3() {
                 ].into_iter().collect(),
                 initial_state: Some("a".into()),
                 current_state: Some("b".into()),
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3915,7 +3722,7 @@ fn test_junctions_1() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("foo".into(), Variable { init: "43".into(), kind: VariableKind::Local }),
+                    ("foo".into(), Variable { init: "43".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("abc".into(), State {
@@ -3925,7 +3732,7 @@ fn test_junctions_1() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "foo = (2 * foo)".into(),
+                                    "foo = (2 * foo)".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
@@ -3935,13 +3742,13 @@ fn test_junctions_1() {
                         parent: Some("abc".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("foo > 1024".into()),
-                                ordered_condition: Condition::atom("foo > 1024".into()),
+                                unordered_condition: Condition::atom("foo > 1024".parse().unwrap()),
+                                ordered_condition: Condition::atom("foo > 1024".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("xyz".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("foo > 1024".into()),
+                                unordered_condition: !Condition::atom("foo > 1024".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("abc".into()),
@@ -3962,6 +3769,7 @@ fn test_junctions_1() {
                 ].into_iter().collect(),
                 initial_state: Some("abc".into()),
                 current_state: None,
+                placeholders: ["xyz".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -3977,8 +3785,8 @@ fn test_junctions_2() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("b".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("b".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("something".into(), State {
@@ -3988,8 +3796,8 @@ fn test_junctions_2() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "a = (a + 1)".into(),
-                                    "b = (a + b + 2)".into(),
+                                    "a = (a + 1)".parse().unwrap(),
+                                    "b = (a + b + 2)".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("::junction-1::".into()),
                             },
@@ -3999,17 +3807,17 @@ fn test_junctions_2() {
                         parent: Some("something".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("(a + b) > 10".into()),
-                                ordered_condition: Condition::atom("(a + b) > 10".into()),
+                                unordered_condition: Condition::atom("(a + b) > 10".parse().unwrap()),
+                                ordered_condition: Condition::atom("(a + b) > 10".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("x2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("(a + b) > 10".into()),
+                                unordered_condition: !Condition::atom("(a + b) > 10".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "a = (a ^ b)".into(),
-                                    "b = (a + b)".into(),
+                                    "a = (a ^ b)".parse().unwrap(),
+                                    "b = (a + b)".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("something".into()),
                             },
@@ -4019,17 +3827,17 @@ fn test_junctions_2() {
                         parent: Some("something".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("(a * b) > 100".into()),
-                                ordered_condition: Condition::atom("(a * b) > 100".into()),
+                                unordered_condition: Condition::atom("(a * b) > 100".parse().unwrap()),
+                                ordered_condition: Condition::atom("(a * b) > 100".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("x1".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("(a * b) > 100".into()),
+                                unordered_condition: !Condition::atom("(a * b) > 100".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "a = (a / b)".into(),
-                                    "b = (1 / b)".into(),
+                                    "a = (a / b)".parse().unwrap(),
+                                    "b = (1 / b)".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
@@ -4060,6 +3868,7 @@ fn test_junctions_2() {
                 ].into_iter().collect(),
                 initial_state: Some("something".into()),
                 current_state: None,
+                placeholders: ["x1".into(), "x2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4140,7 +3949,7 @@ d.Scope = "Local"
 #[test]
 fn test_double_trans() {
     let err = Project::compile(include_str!("projects/double-trans.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "thingy".into(), state: "first".into() });
+    assert_eq!(err, CompileError::NonTerminalTransition { state_machine: "thingy".into(), state: "first".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![0], collab_id: Some("item_9".into()) }) });
 }
 
 #[test]
@@ -4152,20 +3961,20 @@ fn test_tail_condition_1() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == 1".into()),
-                                ordered_condition: Condition::atom("a == 1".into()),
+                                unordered_condition: Condition::atom("a == 1".parse().unwrap()),
+                                ordered_condition: Condition::atom("a == 1".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("a == 1".into()),
+                                unordered_condition: !Condition::atom("a == 1".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("last".into()),
@@ -4197,6 +4006,7 @@ fn test_tail_condition_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into(), "mid".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4212,37 +4022,20 @@ fn test_tail_condition_2() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == 1".into()) & Condition::atom("a == 2".into()),
-                                ordered_condition: Condition::atom("a == 1".into()) & Condition::atom("a == 2".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("mid".into()),
-                            },
-                            Transition {
-                                unordered_condition: !(Condition::atom("a == 1".into()) & Condition::atom("a == 2".into())),
+                                unordered_condition: !(Condition::atom("a == 1".parse().unwrap()) & Condition::atom("a == 2".parse().unwrap())),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("last".into()),
                             },
                         ].into_iter().collect(),
                     }),
-                    ("mid".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                     ("last".into(), State {
                         parent: None,
                         transitions: [
@@ -4257,6 +4050,7 @@ fn test_tail_condition_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4272,7 +4066,7 @@ fn test_tail_condition_3() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
@@ -4300,6 +4094,7 @@ fn test_tail_condition_3() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4315,20 +4110,20 @@ fn test_tail_condition_4() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == 1".into()),
-                                ordered_condition: Condition::atom("a == 1".into()),
+                                unordered_condition: Condition::atom("a == 1".parse().unwrap()),
+                                ordered_condition: Condition::atom("a == 1".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 1".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("a == 1".into()),
+                                unordered_condition: !Condition::atom("a == 1".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 2".into()),
@@ -4360,6 +4155,7 @@ fn test_tail_condition_4() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["mid 1".into(), "mid 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4375,26 +4171,26 @@ fn test_tail_condition_5() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == 1".into()),
-                                ordered_condition: Condition::atom("a == 1".into()),
+                                unordered_condition: Condition::atom("a == 1".parse().unwrap()),
+                                ordered_condition: Condition::atom("a == 1".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 1".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("a == 1".into()) & Condition::atom("a == 2".into()),
-                                ordered_condition: Condition::atom("a == 2".into()),
+                                unordered_condition: !Condition::atom("a == 1".parse().unwrap()) & Condition::atom("a == 2".parse().unwrap()),
+                                ordered_condition: Condition::atom("a == 2".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("a == 1".into()) & !Condition::atom("a == 2".into()),
+                                unordered_condition: !Condition::atom("a == 1".parse().unwrap()) & !Condition::atom("a == 2".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("last".into()),
@@ -4437,6 +4233,7 @@ fn test_tail_condition_5() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into(), "mid 1".into(), "mid 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4452,20 +4249,20 @@ fn test_tail_condition_6() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == 1".into()),
-                                ordered_condition: Condition::atom("a == 1".into()),
+                                unordered_condition: Condition::atom("a == 1".parse().unwrap()),
+                                ordered_condition: Condition::atom("a == 1".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 1".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("a == 1".into()),
+                                unordered_condition: !Condition::atom("a == 1".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("last".into()),
@@ -4497,6 +4294,7 @@ fn test_tail_condition_6() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into(), "mid 1".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4512,43 +4310,26 @@ fn test_tail_condition_7() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == 1".into()) & Condition::atom("a == 2".into()),
-                                ordered_condition: Condition::atom("a == 1".into()) & Condition::atom("a == 2".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("mid 1".into()),
-                            },
-                            Transition {
-                                unordered_condition: !Condition::atom("a == 1".into()),
-                                ordered_condition: !Condition::atom("a == 1".into()),
+                                unordered_condition: !Condition::atom("a == 1".parse().unwrap()),
+                                ordered_condition: !Condition::atom("a == 1".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 2".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("a == 1".into()) & !Condition::atom("a == 2".into()),
+                                unordered_condition: Condition::atom("a == 1".parse().unwrap()) & !Condition::atom("a == 2".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("last".into()),
                             },
                         ].into_iter().collect(),
                     }),
-                    ("mid 1".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                     ("mid 2".into(), State {
                         parent: None,
                         transitions: [
@@ -4574,6 +4355,7 @@ fn test_tail_condition_7() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into(), "mid 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4589,43 +4371,26 @@ fn test_tail_condition_8() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == 1".into()) & Condition::atom("a == 2".into()),
-                                ordered_condition: Condition::atom("a == 1".into()) & Condition::atom("a == 2".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("mid 1".into()),
-                            },
-                            Transition {
-                                unordered_condition: !Condition::atom("a == 1".into()) & Condition::atom("a == 3".into()),
-                                ordered_condition: !Condition::atom("a == 1".into()) & Condition::atom("a == 3".into()),
+                                unordered_condition: !Condition::atom("a == 1".parse().unwrap()) & Condition::atom("a == 3".parse().unwrap()),
+                                ordered_condition: !Condition::atom("a == 1".parse().unwrap()) & Condition::atom("a == 3".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 2".into()),
                             },
                             Transition {
-                                unordered_condition: !(Condition::atom("a == 1".into()) & Condition::atom("a == 2".into())) & !(!Condition::atom("a == 1".into()) & Condition::atom("a == 3".into())),
+                                unordered_condition: !(Condition::atom("a == 1".parse().unwrap()) & Condition::atom("a == 2".parse().unwrap())) & !(!Condition::atom("a == 1".parse().unwrap()) & Condition::atom("a == 3".parse().unwrap())),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("last".into()),
                             },
                         ].into_iter().collect(),
                     }),
-                    ("mid 1".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                     ("mid 2".into(), State {
                         parent: None,
                         transitions: [
@@ -4651,6 +4416,7 @@ fn test_tail_condition_8() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into(), "mid 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4666,37 +4432,20 @@ fn test_tail_condition_9() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == 1".into()) & Condition::atom("a == 2".into()),
-                                ordered_condition: Condition::atom("a == 1".into()) & Condition::atom("a == 2".into()),
-                                actions: [].into_iter().collect(),
-                                new_state: Some("mid 1".into()),
-                            },
-                            Transition {
-                                unordered_condition: !(Condition::atom("a == 1".into()) & Condition::atom("a == 2".into())),
+                                unordered_condition: !(Condition::atom("a == 1".parse().unwrap()) & Condition::atom("a == 2".parse().unwrap())),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("last".into()),
                             },
                         ].into_iter().collect(),
                     }),
-                    ("mid 1".into(), State {
-                        parent: None,
-                        transitions: [
-                            Transition {
-                                ordered_condition: Condition::constant(true),
-                                unordered_condition: Condition::constant(true),
-                                actions: [].into_iter().collect(),
-                                new_state: None,
-                            },
-                        ].into_iter().collect(),
-                    }),
                     ("last".into(), State {
                         parent: None,
                         transitions: [
@@ -4711,6 +4460,7 @@ fn test_tail_condition_9() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4726,20 +4476,20 @@ fn test_tail_condition_10() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: !Condition::atom("a == 1".into()),
-                                ordered_condition: !Condition::atom("a == 1".into()),
+                                unordered_condition: !Condition::atom("a == 1".parse().unwrap()),
+                                ordered_condition: !Condition::atom("a == 1".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 2".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("a == 1".into()),
+                                unordered_condition: Condition::atom("a == 1".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("last".into()),
@@ -4771,6 +4521,7 @@ fn test_tail_condition_10() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into(), "mid 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4786,20 +4537,20 @@ fn test_tail_condition_11() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: !Condition::atom("a == 1".into()) & Condition::atom("a == 2".into()),
-                                ordered_condition: !Condition::atom("a == 1".into()) & Condition::atom("a == 2".into()),
+                                unordered_condition: !Condition::atom("a == 1".parse().unwrap()) & Condition::atom("a == 2".parse().unwrap()),
+                                ordered_condition: !Condition::atom("a == 1".parse().unwrap()) & Condition::atom("a == 2".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 2".into()),
                             },
                             Transition {
-                                unordered_condition: !(!Condition::atom("a == 1".into()) & Condition::atom("a == 2".into())),
+                                unordered_condition: !(!Condition::atom("a == 1".parse().unwrap()) & Condition::atom("a == 2".parse().unwrap())),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("last".into()),
@@ -4831,6 +4582,7 @@ fn test_tail_condition_11() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into(), "mid 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4846,7 +4598,7 @@ fn test_tail_condition_12() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
@@ -4874,6 +4626,7 @@ fn test_tail_condition_12() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4904,6 +4657,7 @@ fn test_completeness_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4945,6 +4699,7 @@ fn test_prune_1() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["mid".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -4960,8 +4715,8 @@ fn test_prune_2() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("b".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("b".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
@@ -4989,6 +4744,7 @@ fn test_prune_2() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["mid".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5030,6 +4786,7 @@ fn test_prune_3() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5045,8 +4802,8 @@ fn test_prune_4() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("b".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("b".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
@@ -5074,6 +4831,7 @@ fn test_prune_4() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5089,8 +4847,8 @@ fn test_prune_5() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("b".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("b".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
@@ -5100,7 +4858,7 @@ fn test_prune_5() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "t = 0".into(),
+                                    "t = 0".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("last".into()),
                             },
@@ -5120,6 +4878,7 @@ fn test_prune_5() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5135,8 +4894,8 @@ fn test_prune_6() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("b".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("b".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
@@ -5146,7 +4905,7 @@ fn test_prune_6() {
                                 unordered_condition: Condition::constant(true),
                                 ordered_condition: Condition::constant(true),
                                 actions: [
-                                    "t = 0".into(),
+                                    "t = 0".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: None,
                             },
@@ -5155,6 +4914,7 @@ fn test_prune_6() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5170,21 +4930,21 @@ fn test_prune_7() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("b".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("b".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == b".into()),
-                                ordered_condition: Condition::atom("a == b".into()),
+                                unordered_condition: Condition::atom("a == b".parse().unwrap()),
+                                ordered_condition: Condition::atom("a == b".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 1".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("a == b".into()),
+                                unordered_condition: !Condition::atom("a == b".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 2".into()),
@@ -5216,6 +4976,7 @@ fn test_prune_7() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["mid 1".into(), "mid 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5231,21 +4992,21 @@ fn test_prune_8() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("b".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("b".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == b".into()),
-                                ordered_condition: Condition::atom("a == b".into()),
+                                unordered_condition: Condition::atom("a == b".parse().unwrap()),
+                                ordered_condition: Condition::atom("a == b".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 1".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("a == b".into()),
+                                unordered_condition: !Condition::atom("a == b".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("last".into()),
@@ -5277,6 +5038,7 @@ fn test_prune_8() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into(), "mid 1".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5292,27 +5054,27 @@ fn test_prune_9() {
         state_machines: [
             ("thingy".into(), StateMachine {
                 variables: [
-                    ("a".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
-                    ("b".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("a".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                    ("b".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("first".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("a == b".into()) & Condition::atom("b == 4".into()),
-                                ordered_condition: Condition::atom("a == b".into()) & Condition::atom("b == 4".into()),
+                                unordered_condition: Condition::atom("a == b".parse().unwrap()) & Condition::atom("b == 4".parse().unwrap()),
+                                ordered_condition: Condition::atom("a == b".parse().unwrap()) & Condition::atom("b == 4".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 1".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("a == b".into()) & !Condition::atom("b == 4".into()),
-                                ordered_condition: Condition::atom("a == b".into()),
+                                unordered_condition: Condition::atom("a == b".parse().unwrap()) & !Condition::atom("b == 4".parse().unwrap()),
+                                ordered_condition: Condition::atom("a == b".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("mid 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("a == b".into()),
+                                unordered_condition: !Condition::atom("a == b".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("last".into()),
@@ -5355,6 +5117,7 @@ fn test_prune_9() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["last".into(), "mid 1".into(), "mid 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5396,6 +5159,7 @@ fn test_empty_condition() {
                 ].into_iter().collect(),
                 initial_state: None,
                 current_state: None,
+                placeholders: ["second".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5405,7 +5169,7 @@ fn test_empty_condition() {
 #[test]
 fn test_empty_trans() {
     let err = Project::compile(include_str!("projects/empty-trans.xml"), None, Settings::default()).unwrap_err();
-    assert_eq!(err, CompileError::TransitionEmptyTarget { state_machine: "my state".into(), state: "start".into() });
+    assert_eq!(err, CompileError::TransitionEmptyTarget { state_machine: "my state".into(), state: "start".into(), location: Box::new(Location { entity: "Sprite".into(), script: 1, block_path: vec![0], collab_id: Some("item_509".into()) }) });
 }
 
 #[test]
@@ -5422,13 +5186,13 @@ fn test_wait_1() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(3, sec)".into()),
-                                ordered_condition: Condition::atom("after(3, sec)".into()),
+                                unordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(3, sec)".into()),
+                                unordered_condition: !Condition::atom("after(3, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5449,6 +5213,7 @@ fn test_wait_1() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: ["stop".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5469,13 +5234,13 @@ fn test_wait_2() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(2, sec)".into()),
-                                ordered_condition: Condition::atom("after(2, sec)".into()),
+                                unordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(2, sec)".into()),
+                                unordered_condition: !Condition::atom("after(2, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5486,13 +5251,13 @@ fn test_wait_2() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(3, sec)".into()),
-                                ordered_condition: Condition::atom("after(3, sec)".into()),
+                                unordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(3, sec)".into()),
+                                unordered_condition: !Condition::atom("after(3, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5513,6 +5278,7 @@ fn test_wait_2() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: ["stop".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5533,13 +5299,13 @@ fn test_wait_3() {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(1, sec)".into()),
-                                ordered_condition: Condition::atom("after(1, sec)".into()),
+                                unordered_condition: Condition::atom("after(1, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(1, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-1::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(1, sec)".into()),
+                                unordered_condition: !Condition::atom("after(1, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5550,13 +5316,13 @@ fn test_wait_3() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(2, sec)".into()),
-                                ordered_condition: Condition::atom("after(2, sec)".into()),
+                                unordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(2, sec)".into()),
+                                unordered_condition: !Condition::atom("after(2, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5567,13 +5333,13 @@ fn test_wait_3() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(3, sec)".into()),
-                                ordered_condition: Condition::atom("after(3, sec)".into()),
+                                unordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(3, sec)".into()),
+                                unordered_condition: !Condition::atom("after(3, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5594,6 +5360,7 @@ fn test_wait_3() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: ["stop".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5609,20 +5376,20 @@ fn test_wait_4() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("x".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("x".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("start".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(3, sec)".into()),
-                                ordered_condition: Condition::atom("after(3, sec)".into()),
+                                unordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(3, sec)".into()),
+                                unordered_condition: !Condition::atom("after(3, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5644,13 +5411,13 @@ fn test_wait_4() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("x".into()),
-                                ordered_condition: Condition::atom("x".into()),
+                                unordered_condition: Condition::atom("x".parse().unwrap()),
+                                ordered_condition: Condition::atom("x".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("x".into()),
+                                unordered_condition: !Condition::atom("x".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("start".into()),
@@ -5660,6 +5427,7 @@ fn test_wait_4() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: ["stop".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5675,20 +5443,20 @@ fn test_wait_5() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("x".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("x".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("start".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(3, sec)".into()),
-                                ordered_condition: Condition::atom("after(3, sec)".into()),
+                                unordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-1::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(3, sec)".into()),
+                                unordered_condition: !Condition::atom("after(3, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5710,13 +5478,13 @@ fn test_wait_5() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("x".into()),
-                                ordered_condition: Condition::atom("x".into()),
+                                unordered_condition: Condition::atom("x".parse().unwrap()),
+                                ordered_condition: Condition::atom("x".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("x".into()),
+                                unordered_condition: !Condition::atom("x".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("start".into()),
@@ -5727,13 +5495,13 @@ fn test_wait_5() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(2, sec)".into()),
-                                ordered_condition: Condition::atom("after(2, sec)".into()),
+                                unordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(2, sec)".into()),
+                                unordered_condition: !Condition::atom("after(2, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5743,6 +5511,7 @@ fn test_wait_5() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: ["stop".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5758,20 +5527,20 @@ fn test_wait_6() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("x".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("x".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("start".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(3, sec)".into()),
-                                ordered_condition: Condition::atom("after(3, sec)".into()),
+                                unordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-1::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(3, sec)".into()),
+                                unordered_condition: !Condition::atom("after(3, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5793,13 +5562,13 @@ fn test_wait_6() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("x".into()),
-                                ordered_condition: Condition::atom("x".into()),
+                                unordered_condition: Condition::atom("x".parse().unwrap()),
+                                ordered_condition: Condition::atom("x".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("x".into()),
+                                unordered_condition: !Condition::atom("x".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("start".into()),
@@ -5810,13 +5579,13 @@ fn test_wait_6() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(2, sec)".into()),
-                                ordered_condition: Condition::atom("after(2, sec)".into()),
+                                unordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(2, sec)".into()),
+                                unordered_condition: !Condition::atom("after(2, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5826,6 +5595,7 @@ fn test_wait_6() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: ["stop".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5841,20 +5611,20 @@ fn test_wait_7() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("x".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("x".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("start".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(3, sec)".into()),
-                                ordered_condition: Condition::atom("after(3, sec)".into()),
+                                unordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-1::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(3, sec)".into()),
+                                unordered_condition: !Condition::atom("after(3, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5876,13 +5646,13 @@ fn test_wait_7() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: !Condition::atom("x".into()),
-                                ordered_condition: !Condition::atom("x".into()),
+                                unordered_condition: !Condition::atom("x".parse().unwrap()),
+                                ordered_condition: !Condition::atom("x".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
                             Transition {
-                                unordered_condition: Condition::atom("x".into()),
+                                unordered_condition: Condition::atom("x".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("start".into()),
@@ -5893,13 +5663,13 @@ fn test_wait_7() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(2, sec)".into()),
-                                ordered_condition: Condition::atom("after(2, sec)".into()),
+                                unordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(2, sec)".into()),
+                                unordered_condition: !Condition::atom("after(2, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5909,6 +5679,7 @@ fn test_wait_7() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: ["stop".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -5924,20 +5695,20 @@ fn test_wait_8() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("x".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("x".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("start".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(3, sec)".into()),
-                                ordered_condition: Condition::atom("after(3, sec)".into()),
+                                unordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-2::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(3, sec)".into()),
+                                unordered_condition: !Condition::atom("after(3, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -5970,13 +5741,13 @@ fn test_wait_8() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("x".into()),
-                                ordered_condition: Condition::atom("x".into()),
+                                unordered_condition: Condition::atom("x".parse().unwrap()),
+                                ordered_condition: Condition::atom("x".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("x".into()),
+                                unordered_condition: !Condition::atom("x".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-1::".into()),
@@ -5987,13 +5758,13 @@ fn test_wait_8() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(1, sec)".into()),
-                                ordered_condition: Condition::atom("after(1, sec)".into()),
+                                unordered_condition: Condition::atom("after(1, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(1, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop 1".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(1, sec)".into()),
+                                unordered_condition: !Condition::atom("after(1, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -6004,13 +5775,13 @@ fn test_wait_8() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(2, sec)".into()),
-                                ordered_condition: Condition::atom("after(2, sec)".into()),
+                                unordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(2, sec)".into()),
+                                unordered_condition: !Condition::atom("after(2, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -6020,6 +5791,7 @@ fn test_wait_8() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: ["stop 1".into(), "stop 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -6035,20 +5807,20 @@ fn test_wait_9() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("x".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("x".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("start".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(3, sec)".into()),
-                                ordered_condition: Condition::atom("after(3, sec)".into()),
+                                unordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-2::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(3, sec)".into()),
+                                unordered_condition: !Condition::atom("after(3, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -6081,15 +5853,15 @@ fn test_wait_9() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("x".into()),
-                                ordered_condition: Condition::atom("x".into()),
+                                unordered_condition: Condition::atom("x".parse().unwrap()),
+                                ordered_condition: Condition::atom("x".parse().unwrap()),
                                 actions: [
-                                    "x = 4".into(),
+                                    "x = 4".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("x".into()),
+                                unordered_condition: !Condition::atom("x".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-1::".into()),
@@ -6100,13 +5872,13 @@ fn test_wait_9() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(1, sec)".into()),
-                                ordered_condition: Condition::atom("after(1, sec)".into()),
+                                unordered_condition: Condition::atom("after(1, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(1, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop 1".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(1, sec)".into()),
+                                unordered_condition: !Condition::atom("after(1, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -6117,15 +5889,15 @@ fn test_wait_9() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(2, sec)".into()),
-                                ordered_condition: Condition::atom("after(2, sec)".into()),
+                                unordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(2, sec)".parse().unwrap()),
                                 actions: [
-                                    "x = 7".into(),
+                                    "x = 7".parse().unwrap(),
                                 ].into_iter().collect(),
                                 new_state: Some("stop 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(2, sec)".into()),
+                                unordered_condition: !Condition::atom("after(2, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -6135,6 +5907,7 @@ fn test_wait_9() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: ["stop 1".into(), "stop 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -6150,20 +5923,20 @@ fn test_wait_10() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("x".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("x".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("start".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("x".into()),
-                                ordered_condition: Condition::atom("x".into()),
+                                unordered_condition: Condition::atom("x".parse().unwrap()),
+                                ordered_condition: Condition::atom("x".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop 1".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("x".into()),
+                                unordered_condition: !Condition::atom("x".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
@@ -6196,13 +5969,13 @@ fn test_wait_10() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(3, sec)".into()),
-                                ordered_condition: Condition::atom("after(3, sec)".into()),
+                                unordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(3, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("stop 2".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(3, sec)".into()),
+                                unordered_condition: !Condition::atom("after(3, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -6212,6 +5985,7 @@ fn test_wait_10() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: ["stop 1".into(), "stop 2".into()].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
@@ -6227,20 +6001,20 @@ fn test_wait_11() {
         state_machines: [
             ("my state".into(), StateMachine {
                 variables: [
-                    ("x".into(), Variable { init: "0".into(), kind: VariableKind::Local }),
+                    ("x".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
                 ].into_iter().collect(),
                 states: [
                     ("start".into(), State {
                         parent: None,
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("after(1, sec)".into()),
-                                ordered_condition: Condition::atom("after(1, sec)".into()),
+                                unordered_condition: Condition::atom("after(1, sec)".parse().unwrap()),
+                                ordered_condition: Condition::atom("after(1, sec)".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("::junction-0::".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("after(1, sec)".into()),
+                                unordered_condition: !Condition::atom("after(1, sec)".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: None,
@@ -6273,13 +6047,13 @@ fn test_wait_11() {
                         parent: Some("start".into()),
                         transitions: [
                             Transition {
-                                unordered_condition: Condition::atom("x".into()),
-                                ordered_condition: Condition::atom("x".into()),
+                                unordered_condition: Condition::atom("x".parse().unwrap()),
+                                ordered_condition: Condition::atom("x".parse().unwrap()),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("a".into()),
                             },
                             Transition {
-                                unordered_condition: !Condition::atom("x".into()),
+                                unordered_condition: !Condition::atom("x".parse().unwrap()),
                                 ordered_condition: Condition::constant(true),
                                 actions: [].into_iter().collect(),
                                 new_state: Some("b".into()),
@@ -6289,8 +6063,1527 @@ fn test_wait_11() {
                 ].into_iter().collect(),
                 initial_state: Some("start".into()),
                 current_state: None,
+                placeholders: ["a".into(), "b".into()].into_iter().collect(),
+            }),
+        ].into_iter().collect(),
+    });
+    assert_complete(&proj);
+}
+
+#[test]
+fn test_wait_until_1() {
+    let proj = Project::compile(include_str!("projects/wait-until-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj, Project {
+        name: "wait".into(),
+        role: "myRole".into(),
+        state_machines: [
+            ("my state".into(), StateMachine {
+                variables: [
+                    ("x".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                ].into_iter().collect(),
+                states: [
+                    ("start".into(), State {
+                        parent: None,
+                        transitions: [
+                            Transition {
+                                unordered_condition: Condition::constant(true),
+                                ordered_condition: Condition::constant(true),
+                                actions: ["x = 1".parse().unwrap()].into_iter().collect(),
+                                new_state: Some("::junction-0::".into()),
+                            },
+                        ].into_iter().collect(),
+                    }),
+                    ("::junction-0::".into(), State {
+                        parent: Some("start".into()),
+                        transitions: [
+                            Transition {
+                                unordered_condition: Condition::atom("t > 3".parse().unwrap()),
+                                ordered_condition: Condition::atom("t > 3".parse().unwrap()),
+                                actions: ["x = 2".parse().unwrap()].into_iter().collect(),
+                                new_state: Some("stop".into()),
+                            },
+                            Transition {
+                                unordered_condition: !Condition::atom("t > 3".parse().unwrap()),
+                                ordered_condition: Condition::constant(true),
+                                actions: [].into_iter().collect(),
+                                new_state: None,
+                            },
+                        ].into_iter().collect(),
+                    }),
+                    ("stop".into(), State {
+                        parent: None,
+                        transitions: [
+                            Transition {
+                                unordered_condition: Condition::atom("x == 5".parse().unwrap()),
+                                ordered_condition: Condition::atom("x == 5".parse().unwrap()),
+                                actions: [].into_iter().collect(),
+                                new_state: Some("start".into()),
+                            },
+                            Transition {
+                                unordered_condition: !Condition::atom("x == 5".parse().unwrap()),
+                                ordered_condition: Condition::constant(true),
+                                actions: [].into_iter().collect(),
+                                new_state: None,
+                            },
+                        ].into_iter().collect(),
+                    }),
+                ].into_iter().collect(),
+                initial_state: Some("start".into()),
+                current_state: None,
+                placeholders: [].into_iter().collect(),
             }),
         ].into_iter().collect(),
     });
     assert_complete(&proj);
 }
+
+#[test]
+fn test_simulate_wait_2() {
+    let proj = Project::compile(include_str!("projects/wait-2.xml"), None, Settings::default()).unwrap();
+    let mut sim = Simulator::new(&proj.state_machines["my state"]).unwrap();
+    assert_eq!(sim.state(), "start");
+
+    let no_inputs: [(&str, f64); 0] = [];
+    for (dt, state) in [(1.0, "start"), (1.0, "::junction-0::"), (2.0, "::junction-0::"), (1.0, "stop"), (10.0, "stop")] {
+        assert_eq!(sim.step(no_inputs, dt).unwrap(), BTreeMap::new());
+        assert_eq!(sim.state(), state);
+    }
+    assert_eq!(sim.timer(), 15.0);
+}
+
+#[test]
+fn test_simulate_var_inits() {
+    let proj = Project::compile(include_str!("projects/var-inits.xml"), None, Settings::default()).unwrap();
+    let mut sim = Simulator::new(&proj.state_machines["thingy g"]).unwrap();
+    assert_eq!(sim.state(), "merp derp");
+    assert_eq!(*sim.variables(), [("foo_3".into(), 9.0), ("bar_5".into(), 16.0), ("baz_b".into(), 9.0)].into_iter().collect());
+
+    sim.step([("", 0.0); 0], 1.0).unwrap();
+    assert_eq!(sim.state(), "derp merp");
+    assert_eq!(*sim.variables(), [("foo_3".into(), 18.0), ("bar_5".into(), 18.0), ("baz_b".into(), 36.0)].into_iter().collect());
+
+    sim.step([("", 0.0); 0], 1.0).unwrap();
+    assert_eq!(sim.state(), "merp derp");
+    assert_eq!(*sim.variables(), [("foo_3".into(), 18.0 * 0.1), ("bar_5".into(), 15.0), ("baz_b".into(), 15.0 - 18.0 * 0.1)].into_iter().collect());
+}
+
+#[test]
+fn test_simulate_var_kinds_1() {
+    let proj = Project::compile(include_str!("projects/var-kinds-1.xml"), None, Settings::default()).unwrap();
+    let mut sim = Simulator::new(&proj.state_machines["my state"]).unwrap();
+    assert_eq!(sim.outputs(), [("baz".into(), 0.0)].into_iter().collect());
+
+    assert_eq!(sim.step([("foo", 5.0)], 0.1).unwrap(), [("baz".into(), 0.0)].into_iter().collect());
+    assert_eq!(sim.step([("foo", 7.0)], 0.1).unwrap(), [("baz".into(), 5.0)].into_iter().collect());
+    assert_eq!(sim.step([("foo", 1.0)], 0.1).unwrap(), [("baz".into(), 7.0)].into_iter().collect());
+    assert_eq!(sim.state(), "start");
+
+    assert_eq!(sim.step([("bar", 1.0)], 0.1).unwrap_err(), SimulationError::NotAnInput { variable: "bar".into() });
+    assert_eq!(sim.step([("baz", 1.0)], 0.1).unwrap_err(), SimulationError::NotAnInput { variable: "baz".into() });
+    assert_eq!(sim.step([("merp", 1.0)], 0.1).unwrap_err(), SimulationError::NotAnInput { variable: "merp".into() });
+    assert_eq!(sim.step([("foo", 3.0), ("bar", 1.0)], 0.1).unwrap_err(), SimulationError::NotAnInput { variable: "bar".into() });
+    assert_eq!(sim.variables()["foo"], 1.0);
+}
+
+#[test]
+fn test_simulate_errors() {
+    let proj = Project::compile(include_str!("projects/actions-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(Simulator::new(&proj.state_machines["state"]).err().unwrap(), SimulationError::NoInitialState);
+
+    let mut proj = Project::compile(include_str!("projects/unknown-blocks-1.xml"), None, Settings { omit_unknown_blocks: true }).unwrap();
+    proj.state_machines.get_mut("thing").unwrap().initial_state = Some("foo".into());
+    let mut sim = Simulator::new(&proj.state_machines["thing"]).unwrap();
+    assert_eq!(sim.step([("", 0.0); 0], 1.0).unwrap_err(), SimulationError::EvalError { src: "?".into(), error: EvalError::UnknownValue });
+    assert_eq!(sim.variables()["merp"], 10.0);
+}
+
+#[test]
+fn test_expr_round_trip() {
+    fn visit_atoms(cond: &RawCondition, f: &mut dyn FnMut(&Expr)) {
+        match cond {
+            RawCondition::Const(_) => (),
+            RawCondition::Atom(x) => f(x),
+            RawCondition::Not(x) => visit_atoms(x, f),
+            RawCondition::And(a, b) | RawCondition::Or(a, b) => { visit_atoms(a, f); visit_atoms(b, f); }
+        }
+    }
+
+    let mut count = 0;
+    for entry in std::fs::read_dir("tests/projects").unwrap() {
+        let Ok(proj) = Project::compile(&std::fs::read_to_string(entry.unwrap().path()).unwrap(), None, Settings { omit_unknown_blocks: true }) else { continue };
+        for state_machine in proj.state_machines.values() {
+            for variable in state_machine.variables.values() {
+                assert_eq!(variable.init.to_string().parse::<Expr>().unwrap(), variable.init);
+            }
+            for transition in state_machine.states.values().flat_map(|s| s.transitions.iter()) {
+                for action in transition.actions.iter() {
+                    assert_eq!(action.to_string().parse::<Stmt>().unwrap(), *action);
+                    count += 1;
+                }
+                for cond in [&transition.ordered_condition, &transition.unordered_condition] {
+                    visit_atoms(cond.raw(), &mut |atom| {
+                        assert_eq!(atom.to_string().parse::<Expr>().unwrap(), *atom);
+                        count += 1;
+                    });
+                }
+            }
+        }
+    }
+    assert!(count > 100);
+}
+
+#[test]
+fn test_scxml_wait_2() {
+    let proj = Project::compile(include_str!("projects/wait-2.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_scxml().unwrap(), r#"
+<?xml version="1.0" encoding="UTF-8"?>
+<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0" datamodel="ecmascript" name="wait">
+  <datamodel>
+    <data id="_entered_0" expr="0"/>
+  </datamodel>
+  <script>
+    function _now() { return Date.now() / 1000; }
+  </script>
+  <parallel id="wait">
+    <state id="my_state" initial="my_state_start">
+      <state id="my_state_junction_0">
+        <onentry>
+          <assign location="_entered_0" expr="_now()"/>
+        </onentry>
+        <transition cond="(_now() - _entered_0) &gt;= 3" target="my_state_stop"/>
+      </state>
+      <state id="my_state_start">
+        <onentry>
+          <assign location="_entered_0" expr="_now()"/>
+        </onentry>
+        <transition event="step" cond="(_now() - _entered_0) &gt;= 2" target="my_state_junction_0"/>
+        <transition event="step"/>
+      </state>
+      <state id="my_state_stop">
+        <onentry>
+          <assign location="_entered_0" expr="_now()"/>
+        </onentry>
+        <transition event="step"/>
+      </state>
+    </state>
+  </parallel>
+</scxml>
+    "#.trim());
+}
+
+#[test]
+fn test_scxml_var_kinds_1() {
+    let mut proj = Project::compile(include_str!("projects/var-kinds-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_scxml().unwrap(), r#"
+<?xml version="1.0" encoding="UTF-8"?>
+<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0" datamodel="ecmascript" name="untitled">
+  <datamodel>
+    <data id="bar"/>
+    <data id="baz"/>
+    <data id="foo"/>
+  </datamodel>
+  <parallel id="untitled">
+    <onentry>
+      <assign location="bar" expr="0"/>
+      <assign location="baz" expr="0"/>
+      <assign location="foo" expr="0"/>
+    </onentry>
+    <transition event="input.foo">
+      <assign location="foo" expr="_event.data"/>
+    </transition>
+    <state id="my_state" initial="my_state_start">
+      <state id="my_state_start">
+        <transition event="step">
+          <assign location="baz" expr="bar"/>
+          <assign location="bar" expr="foo"/>
+        </transition>
+      </state>
+    </state>
+  </parallel>
+</scxml>
+    "#.trim());
+
+    proj.state_machines.get_mut("my state").unwrap().variables.get_mut("bar").unwrap().init = "round(2.5)".parse().unwrap();
+    let scxml = proj.to_scxml().unwrap();
+    assert!(scxml.find("function _round(").unwrap() < scxml.find("<assign location=\"bar\" expr=\"_round(2.5)\"/>").unwrap());
+}
+
+#[test]
+fn test_scxml_if_timer_reset_1() {
+    let proj = Project::compile(include_str!("projects/if-timer-reset-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_scxml().unwrap(), r#"
+<?xml version="1.0" encoding="UTF-8"?>
+<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0" datamodel="ecmascript" name="untitled">
+  <datamodel>
+    <data id="_t0" expr="0"/>
+  </datamodel>
+  <script>
+    function _now() { return Date.now() / 1000; }
+  </script>
+  <parallel id="untitled">
+    <onentry>
+      <assign location="_t0" expr="_now()"/>
+    </onentry>
+    <state id="something">
+      <state id="something_thing_1">
+        <transition event="step" cond="(_now() - _t0) &gt; 10" target="something_thing_2">
+          <assign location="_t0" expr="_now()"/>
+        </transition>
+        <transition event="step"/>
+      </state>
+      <state id="something_thing_2">
+        <transition event="step" target="something_thing_1"/>
+      </state>
+    </state>
+  </parallel>
+</scxml>
+    "#.trim());
+}
+
+#[test]
+fn test_scxml_junctions_1() {
+    let proj = Project::compile(include_str!("projects/junctions-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_scxml().unwrap(), r#"
+<?xml version="1.0" encoding="UTF-8"?>
+<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0" datamodel="ecmascript" name="junctions">
+  <datamodel>
+    <data id="foo"/>
+  </datamodel>
+  <parallel id="junctions">
+    <onentry>
+      <assign location="foo" expr="43"/>
+    </onentry>
+    <state id="my_state" initial="my_state_abc">
+      <state id="my_state_junction_0">
+        <transition cond="foo &gt; 1024" target="my_state_xyz"/>
+        <transition cond="foo &lt;= 1024" target="my_state_abc"/>
+      </state>
+      <state id="my_state_abc">
+        <transition event="step" target="my_state_junction_0">
+          <assign location="foo" expr="2 * foo"/>
+        </transition>
+      </state>
+      <state id="my_state_xyz">
+        <transition event="step"/>
+      </state>
+    </state>
+  </parallel>
+</scxml>
+    "#.trim());
+}
+
+#[test]
+fn test_mermaid_junctions_1() {
+    let proj = Project::compile(include_str!("projects/junctions-1.xml"), None, Settings::default()).unwrap();
+    let diagrams = proj.to_mermaid().unwrap();
+    assert_eq!(diagrams.len(), 1);
+    assert_eq!(diagrams["my state"], r#"
+stateDiagram-v2
+    state junction_0 <<choice>>
+    state "abc" as abc
+    state "xyz" as xyz
+    [*] --> abc
+    junction_0 --> xyz : 1: [foo > 1024]
+    junction_0 --> abc : 2
+    abc --> junction_0 : / foo = (2 * foo)
+    "#.trim());
+}
+
+#[test]
+fn test_mermaid_current_state_3() {
+    let proj = Project::compile(include_str!("projects/current-state-3.xml"), None, Settings::default()).unwrap();
+    let diagrams = proj.to_mermaid().unwrap();
+    assert_eq!(diagrams.len(), 1);
+    assert_eq!(diagrams["thingy"], r#"
+stateDiagram-v2
+    state "a" as a
+    state "b" as b
+    [*] --> a
+    a --> b
+    b --> a
+    classDef current font-weight:bold,stroke-width:3px
+    class b current
+    "#.trim());
+}
+
+#[test]
+fn test_mermaid_multiple_machines_1() {
+    let proj = Project::compile(include_str!("projects/multiple-machines-1.xml"), None, Settings::default()).unwrap();
+    let diagrams = proj.to_mermaid().unwrap();
+    assert_eq!(diagrams.len(), 2);
+    assert_eq!(diagrams["machine 1"], r#"
+stateDiagram-v2
+    state "bar" as bar
+    state "buz" as buz
+    state "foo" as foo
+    [*] --> foo
+    bar --> buz
+    buz --> foo
+    foo --> bar
+    "#.trim());
+    assert_eq!(diagrams["machine 2"], r#"
+stateDiagram-v2
+    state "bar" as bar
+    state "baz" as baz
+    state "buzz" as buzz
+    [*] --> bar
+    bar --> baz
+    baz --> buzz
+    buzz --> bar
+    "#.trim());
+}
+
+#[test]
+fn test_plantuml_junctions_1() {
+    let proj = Project::compile(include_str!("projects/junctions-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_plantuml().unwrap(), r#"
+@startuml
+title junctions
+state "my state" as my_state {
+  state my_state_junction_0 <<choice>>
+  state "abc" as my_state_abc
+  state "xyz" as my_state_xyz
+  [*] --> my_state_abc
+  my_state_junction_0 --> my_state_xyz : 1: [foo > 1024]
+  my_state_junction_0 --> my_state_abc : 2
+  my_state_abc --> my_state_junction_0 : / foo = (2 * foo)
+}
+note right of my_state
+  foo : Local = 43
+end note
+@enduml
+    "#.trim());
+}
+
+#[test]
+fn test_plantuml_current_state_3() {
+    let proj = Project::compile(include_str!("projects/current-state-3.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_plantuml().unwrap(), r#"
+@startuml
+title untitled
+state "thingy" as thingy {
+  state "a" as thingy_a
+  state "b" as thingy_b #lightgray
+  [*] --> thingy_a
+  thingy_a --> thingy_b
+  thingy_b --> thingy_a
+}
+@enduml
+    "#.trim());
+}
+
+#[test]
+fn test_plantuml_var_kinds_1() {
+    let proj = Project::compile(include_str!("projects/var-kinds-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_plantuml().unwrap(), r#"
+@startuml
+title untitled
+state "my state" as my_state {
+  state "start" as my_state_start
+  [*] --> my_state_start
+  my_state_start --> my_state_start : / baz = bar; bar = foo
+}
+note right of my_state
+  bar : Local = 0
+  baz : Output = 0
+  foo : Input = 0
+end note
+@enduml
+    "#.trim());
+}
+
+#[test]
+fn test_plantuml_multiple_machines_1() {
+    let proj = Project::compile(include_str!("projects/multiple-machines-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_plantuml().unwrap(), r#"
+@startuml
+title untitled
+state "machine 1" as machine_1 {
+  state "bar" as machine_1_bar
+  state "buz" as machine_1_buz
+  state "foo" as machine_1_foo
+  [*] --> machine_1_foo
+  machine_1_bar --> machine_1_buz
+  machine_1_buz --> machine_1_foo
+  machine_1_foo --> machine_1_bar
+}
+state "machine 2" as machine_2 {
+  state "bar" as machine_2_bar
+  state "baz" as machine_2_baz
+  state "buzz" as machine_2_buzz
+  [*] --> machine_2_bar
+  machine_2_bar --> machine_2_baz
+  machine_2_baz --> machine_2_buzz
+  machine_2_buzz --> machine_2_bar
+}
+@enduml
+    "#.trim());
+}
+
+#[test]
+fn test_c_wait_2() {
+    let proj = Project::compile(include_str!("projects/wait-2.xml"), None, Settings::default()).unwrap();
+    let code = proj.to_c().unwrap();
+    assert_eq!(code.header_name, "wait.h");
+    assert_eq!(code.header.trim_end(), r#"
+#ifndef WAIT_H
+#define WAIT_H
+
+typedef enum {
+    MY_STATE_JUNCTION_0,
+    MY_STATE_START,
+    MY_STATE_STOP,
+} my_state_state_t;
+
+typedef struct {
+    my_state_state_t sf_state;
+    double sf_state_time;
+    double sf_timer;
+} my_state_t;
+
+void my_state_init(my_state_t *s);
+void my_state_step(my_state_t *s, double dt);
+
+#endif
+    "#.trim());
+    assert_eq!(code.source.trim_end(), r#"
+#include <math.h>
+#include <stdlib.h>
+#include "wait.h"
+
+void my_state_init(my_state_t *s) {
+    *s = (my_state_t){0};
+    s->sf_state = MY_STATE_START;
+}
+
+void my_state_step(my_state_t *s, double dt) {
+    s->sf_timer += dt;
+    s->sf_state_time += dt;
+    for (int junctions = 0; junctions <= 3; ++junctions) {
+        switch (s->sf_state) {
+            case MY_STATE_JUNCTION_0:
+                if (s->sf_state_time >= 3.0) {
+                    s->sf_state = MY_STATE_STOP;
+                    s->sf_state_time = 0.0;
+                    return;
+                }
+                return;
+            case MY_STATE_START:
+                if (s->sf_state_time >= 2.0) {
+                    s->sf_state = MY_STATE_JUNCTION_0;
+                    s->sf_state_time = 0.0;
+                    continue;
+                }
+                return;
+            case MY_STATE_STOP:
+                return;
+        }
+        return;
+    }
+}
+    "#.trim());
+}
+
+#[test]
+fn test_c_var_kinds_1() {
+    let proj = Project::compile(include_str!("projects/var-kinds-1.xml"), None, Settings::default()).unwrap();
+    let code = proj.to_c().unwrap();
+    assert_eq!(code.header_name, "untitled.h");
+    assert_eq!(code.header.trim_end(), r#"
+#ifndef UNTITLED_H
+#define UNTITLED_H
+
+typedef enum {
+    MY_STATE_START,
+} my_state_state_t;
+
+typedef struct {
+    my_state_state_t sf_state;
+    double sf_state_time;
+    double sf_timer;
+    double bar; /* Local */
+    double baz; /* Output */
+    double foo; /* Input */
+} my_state_t;
+
+void my_state_init(my_state_t *s);
+void my_state_step(my_state_t *s, double dt);
+
+#endif
+    "#.trim());
+    assert_eq!(code.source.trim_end(), r#"
+#include <math.h>
+#include <stdlib.h>
+#include "untitled.h"
+
+void my_state_init(my_state_t *s) {
+    *s = (my_state_t){0};
+    s->sf_state = MY_STATE_START;
+    s->bar = 0.0;
+    s->baz = 0.0;
+    s->foo = 0.0;
+}
+
+void my_state_step(my_state_t *s, double dt) {
+    s->sf_timer += dt;
+    s->sf_state_time += dt;
+    for (int junctions = 0; junctions <= 1; ++junctions) {
+        switch (s->sf_state) {
+            case MY_STATE_START:
+                s->baz = s->bar;
+                s->bar = s->foo;
+                return;
+        }
+        return;
+    }
+}
+    "#.trim());
+}
+
+#[test]
+fn test_c_rand_1() {
+    let proj = Project::compile(include_str!("projects/rand-1.xml"), None, Settings::default()).unwrap();
+    let code = proj.to_c().unwrap();
+    assert_eq!(code.header_name, "state_machine_dice.h");
+    assert_eq!(code.header.trim_end(), r#"
+#ifndef STATE_MACHINE_DICE_H
+#define STATE_MACHINE_DICE_H
+
+typedef enum {
+    MY_STATE_ROLLING,
+} my_state_state_t;
+
+typedef struct {
+    my_state_state_t sf_state;
+    double sf_state_time;
+    double sf_timer;
+    double a; /* Local */
+    double b; /* Local */
+} my_state_t;
+
+void my_state_init(my_state_t *s);
+void my_state_step(my_state_t *s, double dt);
+
+#endif
+    "#.trim());
+    assert_eq!(code.source.trim_end(), r#"
+#include <math.h>
+#include <stdlib.h>
+#include "state_machine_dice.h"
+
+static double sf_randi(double a, double b) { a = ceil(a); b = floor(b); return a + floor((double)rand() / ((double)RAND_MAX + 1.0) * (b - a + 1.0)); }
+
+void my_state_init(my_state_t *s) {
+    *s = (my_state_t){0};
+    s->sf_state = MY_STATE_ROLLING;
+    s->a = 0.0;
+    s->b = 0.0;
+}
+
+void my_state_step(my_state_t *s, double dt) {
+    s->sf_timer += dt;
+    s->sf_state_time += dt;
+    for (int junctions = 0; junctions <= 1; ++junctions) {
+        switch (s->sf_state) {
+            case MY_STATE_ROLLING:
+                s->a = sf_randi(1.0, 6.0);
+                s->a = sf_randi(1.0, 9.0);
+                s->a = sf_randi(2.0, 4.0);
+                s->b = sf_randi(s->a - s->b, s->a + s->b);
+                return;
+        }
+        return;
+    }
+}
+    "#.trim());
+}
+
+#[test]
+fn test_c_unsupported_exprs_1() {
+    let proj = Project::compile(include_str!("projects/unknown-blocks-1.xml"), None, Settings { omit_unknown_blocks: true }).unwrap();
+    assert_eq!(proj.to_c().unwrap_err(), CompileError::UnsupportedExport { state_machine: "thing".into(), state: "foo".into(), info: "an unknown block".into() });
+
+    let mut proj = Project::compile(include_str!("projects/var-kinds-1.xml"), None, Settings::default()).unwrap();
+    proj.state_machines.get_mut("my state").unwrap().variables.get_mut("bar").unwrap().init = Expr::String("hello".into());
+    assert_eq!(proj.to_c().unwrap_err(), CompileError::UnsupportedExport { state_machine: "my state".into(), state: "<init>".into(), info: "the non-numeric value \"hello\"".into() });
+
+    proj.state_machines.get_mut("my state").unwrap().variables.get_mut("bar").unwrap().init = Expr::String("12".into());
+    assert!(proj.to_c().unwrap().source.contains("s->bar = 12.0;"));
+
+    proj.state_machines.get_mut("my state").unwrap().states.values_mut().next().unwrap().transitions[0].actions.push_back(Stmt::Unknown);
+    assert_eq!(proj.to_c().unwrap_err(), CompileError::UnsupportedExport { state_machine: "my state".into(), state: "start".into(), info: "an unknown block".into() });
+}
+
+#[test]
+fn test_c_state_id_conflict() {
+    let mut proj = Project::compile(include_str!("projects/var-kinds-1.xml"), None, Settings::default()).unwrap();
+    let states = &mut proj.state_machines.get_mut("my state").unwrap().states;
+    states.insert("a b".into(), State { parent: None, transitions: VecDeque::new() });
+    states.insert("A b".into(), State { parent: None, transitions: VecDeque::new() });
+    assert_eq!(proj.to_c().unwrap_err(), CompileError::RenameConflict { before: ("my state a b".into(), "my state A b".into()), after: "MY_STATE_A_B".into() });
+}
+
+#[test]
+fn test_rust_wait_2() {
+    let proj = Project::compile(include_str!("projects/wait-2.xml"), None, Settings::default()).unwrap();
+    let modules = proj.to_rust().unwrap();
+    assert_eq!(modules.len(), 1);
+    assert_eq!(modules["my state"].trim_end(), r#"
+// generated from state machine "my state"
+#![allow(non_snake_case)]
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Junction0,
+    Start,
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Variables {
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateMachine {
+    pub state: State,
+    pub state_time: f64,
+    pub timer: f64,
+    pub rng: u64,
+    pub variables: Variables,
+}
+
+impl StateMachine {
+    pub fn new() -> Self {
+        Self { state: State::Start, state_time: 0.0, timer: 0.0, rng: 0x853c49e6748fea9b, variables: Variables::default() }
+    }
+    pub fn step(&mut self, dt: f64) {
+        self.timer += dt;
+        self.state_time += dt;
+        for _ in 0..=3 {
+            match self.state {
+                State::Junction0 => {
+                    if self.state_time >= 3.0 {
+                        self.state = State::Stop;
+                        self.state_time = 0.0;
+                        return;
+                    }
+                    return;
+                }
+                State::Start => {
+                    if self.state_time >= 2.0 {
+                        self.state = State::Junction0;
+                        self.state_time = 0.0;
+                        continue;
+                    }
+                    return;
+                }
+                State::Stop => {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Default for StateMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+    "#.trim());
+}
+
+#[test]
+fn test_rust_var_kinds_1() {
+    let proj = Project::compile(include_str!("projects/var-kinds-1.xml"), None, Settings::default()).unwrap();
+    let modules = proj.to_rust().unwrap();
+    assert_eq!(modules.len(), 1);
+    assert_eq!(modules["my state"].trim_end(), r#"
+// generated from state machine "my state"
+#![allow(non_snake_case)]
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Start,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Variables {
+    pub bar: f64, // Local
+    pub baz: f64, // Output
+    pub foo: f64, // Input
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateMachine {
+    pub state: State,
+    pub state_time: f64,
+    pub timer: f64,
+    pub rng: u64,
+    pub variables: Variables,
+}
+
+impl StateMachine {
+    pub fn new() -> Self {
+        let mut res = Self { state: State::Start, state_time: 0.0, timer: 0.0, rng: 0x853c49e6748fea9b, variables: Variables::default() };
+        res.variables.bar = 0.0;
+        res.variables.baz = 0.0;
+        res.variables.foo = 0.0;
+        res
+    }
+    pub fn step(&mut self, dt: f64) {
+        self.timer += dt;
+        self.state_time += dt;
+        for _ in 0..=1 {
+            match self.state {
+                State::Start => {
+                    self.variables.baz = self.variables.bar;
+                    self.variables.bar = self.variables.foo;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Default for StateMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+    "#.trim());
+}
+
+#[test]
+fn test_rust_unsupported_exprs_1() {
+    let proj = Project::compile(include_str!("projects/unknown-blocks-1.xml"), None, Settings { omit_unknown_blocks: true }).unwrap();
+    assert_eq!(proj.to_rust().unwrap_err(), CompileError::UnsupportedExport { state_machine: "thing".into(), state: "foo".into(), info: "an unknown block".into() });
+
+    let mut proj = Project::compile(include_str!("projects/var-kinds-1.xml"), None, Settings::default()).unwrap();
+    proj.state_machines.get_mut("my state").unwrap().variables.get_mut("bar").unwrap().init = Expr::String("hello".into());
+    assert_eq!(proj.to_rust().unwrap_err(), CompileError::UnsupportedExport { state_machine: "my state".into(), state: "<init>".into(), info: "the non-numeric value \"hello\"".into() });
+
+    proj.state_machines.get_mut("my state").unwrap().variables.get_mut("bar").unwrap().init = Expr::String("12".into());
+    assert!(proj.to_rust().unwrap()["my state"].contains("res.variables.bar = 12.0;"));
+
+    proj.state_machines.get_mut("my state").unwrap().states.values_mut().next().unwrap().transitions[0].actions.push_back(Stmt::Unknown);
+    assert_eq!(proj.to_rust().unwrap_err(), CompileError::UnsupportedExport { state_machine: "my state".into(), state: "start".into(), info: "an unknown block".into() });
+
+    let state_machine = proj.state_machines.get_mut("my state").unwrap();
+    state_machine.states.clear();
+    state_machine.initial_state = None;
+    state_machine.current_state = None;
+    assert_eq!(proj.to_rust().unwrap(), BTreeMap::new());
+}
+
+#[test]
+fn test_python_wait_2() {
+    let proj = Project::compile(include_str!("projects/wait-2.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_python().unwrap(), r#"
+# generated from project "wait"
+import enum
+
+
+class MyState:
+    class State(enum.Enum):
+        JUNCTION_0 = "::junction-0::"
+        START = "start"
+        STOP = "stop"
+
+    INPUTS = []
+    OUTPUTS = []
+
+    def __init__(self):
+        self.state = MyState.State.START
+        self._state_time = 0
+        self._timer = 0
+
+    def step(self, inputs, dt):
+        for name, value in inputs.items():
+            if name not in MyState.INPUTS:
+                raise KeyError(f'{name!r} is not an input variable')
+            setattr(self, name, value)
+        self._timer += dt
+        self._state_time += dt
+        for _ in range(4):
+            if self.state == MyState.State.JUNCTION_0:
+                if self._state_time >= 3:
+                    self.state = MyState.State.STOP
+                    self._state_time = 0
+                    break
+                break
+            elif self.state == MyState.State.START:
+                if self._state_time >= 2:
+                    self.state = MyState.State.JUNCTION_0
+                    self._state_time = 0
+                    continue
+                break
+            elif self.state == MyState.State.STOP:
+                break
+        return self.outputs()
+
+    def outputs(self):
+        return {name: getattr(self, name) for name in MyState.OUTPUTS}
+    "#.trim());
+}
+
+#[test]
+fn test_python_var_kinds_1() {
+    let proj = Project::compile(include_str!("projects/var-kinds-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_python().unwrap(), r#"
+# generated from project "untitled"
+import enum
+
+
+class MyState:
+    class State(enum.Enum):
+        START = "start"
+
+    INPUTS = ['foo']
+    OUTPUTS = ['baz']
+
+    def __init__(self):
+        self.state = MyState.State.START
+        self._state_time = 0
+        self._timer = 0
+        self.bar = 0
+        self.baz = 0
+        self.foo = 0
+
+    def step(self, inputs, dt):
+        for name, value in inputs.items():
+            if name not in MyState.INPUTS:
+                raise KeyError(f'{name!r} is not an input variable')
+            setattr(self, name, value)
+        self._timer += dt
+        self._state_time += dt
+        for _ in range(2):
+            if self.state == MyState.State.START:
+                self.baz = self.bar
+                self.bar = self.foo
+                break
+        return self.outputs()
+
+    def outputs(self):
+        return {name: getattr(self, name) for name in MyState.OUTPUTS}
+    "#.trim());
+}
+
+#[test]
+fn test_python_rand_1() {
+    let proj = Project::compile(include_str!("projects/rand-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_python().unwrap(), r#"
+# generated from project "state-machine-dice"
+import enum
+import math
+import random
+
+
+def _sf_randi(a, b):
+    return random.randint(math.ceil(min(a, b)), math.floor(max(a, b)))
+
+
+class MyState:
+    class State(enum.Enum):
+        ROLLING = "rolling"
+
+    INPUTS = []
+    OUTPUTS = []
+
+    def __init__(self):
+        self.state = MyState.State.ROLLING
+        self._state_time = 0
+        self._timer = 0
+        self.a = 0
+        self.b = 0
+
+    def step(self, inputs, dt):
+        for name, value in inputs.items():
+            if name not in MyState.INPUTS:
+                raise KeyError(f'{name!r} is not an input variable')
+            setattr(self, name, value)
+        self._timer += dt
+        self._state_time += dt
+        for _ in range(2):
+            if self.state == MyState.State.ROLLING:
+                self.a = _sf_randi(1, 6)
+                self.a = _sf_randi(1, 9)
+                self.a = _sf_randi(2, 4)
+                self.b = _sf_randi(self.a - self.b, self.a + self.b)
+                break
+        return self.outputs()
+
+    def outputs(self):
+        return {name: getattr(self, name) for name in MyState.OUTPUTS}
+    "#.trim());
+}
+
+#[test]
+fn test_python_unsupported_exprs_1() {
+    let proj = Project::compile(include_str!("projects/unknown-blocks-1.xml"), None, Settings { omit_unknown_blocks: true }).unwrap();
+    assert_eq!(proj.to_python().unwrap_err(), CompileError::UnsupportedExport { state_machine: "thing".into(), state: "foo".into(), info: "an unknown block".into() });
+
+    let mut proj = Project::compile(include_str!("projects/var-kinds-1.xml"), None, Settings::default()).unwrap();
+    proj.state_machines.get_mut("my state").unwrap().variables.get_mut("bar").unwrap().init = Expr::String("hello".into());
+    assert_eq!(proj.to_python().unwrap_err(), CompileError::UnsupportedExport { state_machine: "my state".into(), state: "<init>".into(), info: "the non-numeric value \"hello\"".into() });
+
+    proj.state_machines.get_mut("my state").unwrap().variables.get_mut("bar").unwrap().init = Expr::String("12".into());
+    assert!(proj.to_python().unwrap().contains("self.bar = 12.0"));
+
+    proj.state_machines.get_mut("my state").unwrap().states.values_mut().next().unwrap().transitions[0].actions.push_back(Stmt::Unknown);
+    assert_eq!(proj.to_python().unwrap_err(), CompileError::UnsupportedExport { state_machine: "my state".into(), state: "start".into(), info: "an unknown block".into() });
+}
+
+#[test]
+fn test_python_helpers() {
+    let proj = Project::compile(include_str!("projects/operators.xml"), None, Settings::default()).unwrap();
+    let code = proj.to_python().unwrap();
+    for helper in ["def _sf_round(x):", "def _sf_floor(x):", "def _sf_abs(x):", "self.foo = _sf_round(3.14159)", "self.foo = _sf_floor(3.1415)", "self.foo = _sf_abs(3.1415)"] {
+        assert!(code.contains(helper), "{helper}");
+    }
+    assert!(!code.contains("def round(") && !code.contains("def floor("));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() {
+    let mut count = 0;
+    for entry in std::fs::read_dir("tests/projects").unwrap() {
+        let Ok(proj) = Project::compile(&std::fs::read_to_string(entry.unwrap().path()).unwrap(), None, Settings { omit_unknown_blocks: true }) else { continue };
+        let json = serde_json::to_string(&proj).unwrap();
+        assert_eq!(serde_json::from_str::<Project>(&json).unwrap(), proj);
+        count += 1;
+    }
+    assert!(count > 50);
+
+    let cond = Condition::atom("x < 10".parse().unwrap()) & !Condition::atom("after(2, sec)".parse().unwrap());
+    assert_eq!(serde_json::to_value(&cond).unwrap(), serde_json::json!({
+        "And": [
+            { "Atom": { "Binary": { "op": "Less", "left": { "Variable": "x" }, "right": { "Number": "10" } } } },
+            { "Not": { "Atom": { "Call": { "func": "After", "args": [{ "Number": "2" }] } } } },
+        ],
+    }));
+    assert_eq!(serde_json::from_value::<Condition>(serde_json::json!({ "Or": [{ "Const": false }, { "Atom": { "Variable": "x" } }] })).unwrap(), Condition::atom("x".parse().unwrap()));
+}
+
+#[test]
+fn test_stateflow_connective_junctions_1() {
+    let proj = Project::compile(include_str!("projects/junctions-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_stateflow_with(StateflowSettings { connective_junctions: true, ..StateflowSettings::default() }).unwrap(), r#"
+sfnew junctions
+chart = find(sfroot, "-isa", "Stateflow.Chart")
+chart.Name = "my state"
+chart.UserSpecifiedStateTransitionExecutionOrder = true
+s0 = Stateflow.Junction(chart)
+s0.Position.Center = [50, 210]
+s0.Position.Radius = 7
+s1 = Stateflow.State(chart)
+s1.LabelString = "abc" + newline + "exit: foo = (2 * foo);"
+s1.Position = [0, 0, 100, 100]
+s2 = Stateflow.State(chart)
+s2.LabelString = "xyz"
+s2.Position = [200, 0, 100, 100]
+t = Stateflow.Transition(chart)
+t.Source = s0
+t.Destination = s2
+t.LabelString = "[foo > 1024]"
+t.ExecutionOrder = 1
+t = Stateflow.Transition(chart)
+t.Source = s0
+t.Destination = s1
+t.LabelString = ""
+t.ExecutionOrder = 2
+t = Stateflow.Transition(chart)
+t.Source = s1
+t.Destination = s0
+t.LabelString = ""
+t.ExecutionOrder = 1
+t = Stateflow.Transition(chart)
+t.Destination = s1
+t.DestinationOClock = 0
+t.SourceEndpoint = t.DestinationEndpoint - [0 30]
+t.Midpoint = t.DestinationEndpoint - [0 15]
+d = Stateflow.Data(chart)
+d.Name = "foo"
+d.Props.InitialValue = "43"
+d.Scope = "Local"
+    "#.trim());
+}
+
+#[test]
+fn test_stateflow_connective_junctions_wait_until_1() {
+    let proj = Project::compile(include_str!("projects/wait-until-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_stateflow_with(StateflowSettings { connective_junctions: true, ..StateflowSettings::default() }).unwrap(), r#"
+sfnew wait
+chart = find(sfroot, "-isa", "Stateflow.Chart")
+chart.Name = "my state"
+chart.UserSpecifiedStateTransitionExecutionOrder = true
+s0 = Stateflow.State(chart)
+s0.LabelString = "start_1"
+s0.Position = [0, 200, 100, 20]
+s1 = Stateflow.State(chart)
+s1.LabelString = "start" + newline + "exit: x = 1;"
+s1.Position = [0, 0, 100, 100]
+s2 = Stateflow.State(chart)
+s2.LabelString = "stop" + newline + "entry: x = 2;"
+s2.Position = [200, 0, 100, 100]
+t = Stateflow.Transition(chart)
+t.Source = s0
+t.Destination = s2
+t.LabelString = "[t > 3]"
+t.ExecutionOrder = 1
+t = Stateflow.Transition(chart)
+t.Source = s1
+t.Destination = s0
+t.LabelString = ""
+t.ExecutionOrder = 1
+t = Stateflow.Transition(chart)
+t.Source = s2
+t.Destination = s1
+t.LabelString = "[x == 5]"
+t.ExecutionOrder = 1
+t = Stateflow.Transition(chart)
+t.Destination = s1
+t.DestinationOClock = 0
+t.SourceEndpoint = t.DestinationEndpoint - [0 30]
+t.Midpoint = t.DestinationEndpoint - [0 15]
+d = Stateflow.Data(chart)
+d.Name = "x"
+d.Props.InitialValue = "0"
+d.Scope = "Local"
+    "#.trim());
+}
+
+#[test]
+fn test_layout_junctions_2() {
+    let mut proj = Project::compile(include_str!("projects/junctions-2.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.state_machines["thingy"].layout(LayoutSettings::default()), Layout {
+        states: [
+            ("::junction-0::".into(), (200, 440, 100, 20)),
+            ("::junction-1::".into(), (100, 240, 100, 20)),
+            ("something".into(), (100, 0, 100, 100)),
+            ("x1".into(), (0, 400, 100, 100)),
+            ("x2".into(), (100, 600, 100, 100)),
+        ].into_iter().collect(),
+        transitions: [
+            (("::junction-0::".into(), 0), (200.0, 550.0)),
+            (("::junction-0::".into(), 1), (297.0, 226.0)),
+            (("::junction-1::".into(), 0), (100.0, 350.0)),
+            (("::junction-1::".into(), 1), (200.0, 350.0)),
+            (("something".into(), 0), (150.0, 150.0)),
+        ].into_iter().collect(),
+    });
+
+    let state_machine = proj.state_machines.get_mut("thingy").unwrap();
+    state_machine.initial_state = Some("nowhere".into());
+    state_machine.states.get_mut("something").unwrap().transitions.push_back(Transition { ordered_condition: Condition::atom("a".parse().unwrap()), unordered_condition: Condition::atom("a".parse().unwrap()), actions: VecDeque::new(), new_state: Some("nowhere".into()) });
+    let layout = state_machine.layout(LayoutSettings::default());
+    assert_eq!(layout.states.len(), 5);
+    assert!(!layout.transitions.contains_key(&("something".into(), 1)));
+}
+
+#[test]
+fn test_stateflow_layout_multiple_machines_1() {
+    let proj = Project::compile(include_str!("projects/multiple-machines-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_stateflow_with(StateflowSettings { layout: Some(LayoutSettings::default()), ..StateflowSettings::default() }).unwrap(), r#"
+sfnew untitled
+chart = find(sfroot, "-isa", "Stateflow.Chart")
+chart.Name = "machine 1"
+s0 = Stateflow.State(chart)
+s0.LabelString = "bar"
+s0.Position = [0, 200, 100, 100]
+s1 = Stateflow.State(chart)
+s1.LabelString = "buz"
+s1.Position = [0, 400, 100, 100]
+s2 = Stateflow.State(chart)
+s2.LabelString = "foo"
+s2.Position = [0, 0, 100, 100]
+t = Stateflow.Transition(chart)
+t.Source = s0
+t.Destination = s1
+t.LabelString = ""
+t.Midpoint = [50, 350]
+t = Stateflow.Transition(chart)
+t.Source = s1
+t.Destination = s2
+t.LabelString = ""
+t.Midpoint = [150, 250]
+t = Stateflow.Transition(chart)
+t.Source = s2
+t.Destination = s0
+t.LabelString = ""
+t.Midpoint = [50, 150]
+t = Stateflow.Transition(chart)
+t.Destination = s2
+t.DestinationOClock = 0
+t.SourceEndpoint = t.DestinationEndpoint - [0 30]
+t.Midpoint = t.DestinationEndpoint - [0 15]
+chart = add_block("sflib/Chart", "untitled/machine 2")
+s0 = Stateflow.State(chart)
+s0.LabelString = "bar"
+s0.Position = [0, 0, 100, 100]
+s1 = Stateflow.State(chart)
+s1.LabelString = "baz"
+s1.Position = [0, 200, 100, 100]
+s2 = Stateflow.State(chart)
+s2.LabelString = "buzz"
+s2.Position = [0, 400, 100, 100]
+t = Stateflow.Transition(chart)
+t.Source = s0
+t.Destination = s1
+t.LabelString = ""
+t.Midpoint = [50, 150]
+t = Stateflow.Transition(chart)
+t.Source = s1
+t.Destination = s2
+t.LabelString = ""
+t.Midpoint = [50, 350]
+t = Stateflow.Transition(chart)
+t.Source = s2
+t.Destination = s0
+t.LabelString = ""
+t.Midpoint = [150, 250]
+t = Stateflow.Transition(chart)
+t.Destination = s0
+t.DestinationOClock = 0
+t.SourceEndpoint = t.DestinationEndpoint - [0 30]
+t.Midpoint = t.DestinationEndpoint - [0 15]
+    "#.trim());
+}
+
+#[test]
+fn test_graphviz_layout_multiple_machines_1() {
+    use graphviz_rust::dot_structures as dot;
+
+    let text = |id: &dot::Id| match id {
+        dot::Id::Plain(x) | dot::Id::Escaped(x) => x.clone(),
+        x => panic!("{x:?}"),
+    };
+
+    let proj = Project::compile(include_str!("projects/multiple-machines-1.xml"), None, Settings::default()).unwrap();
+    let dot::Graph::DiGraph { stmts, .. } = proj.to_graphviz_with_layout(LayoutSettings::default()) else { panic!() };
+    let mut stmts = stmts.into_iter();
+    assert_eq!(stmts.next(), Some(dot::Stmt::Attribute(dot::Attribute(dot::Id::Plain("layout".into()), dot::Id::Plain("neato".into())))));
+    let mut positions = vec![];
+    for stmt in stmts {
+        let dot::Stmt::Subgraph(subgraph) = stmt else { panic!() };
+        for stmt in subgraph.stmts {
+            let (id, attributes) = match stmt {
+                dot::Stmt::Node(node) => (text(&node.id.0), node.attributes),
+                dot::Stmt::Edge(dot::Edge { ty: dot::EdgeTy::Pair(dot::Vertex::N(a), dot::Vertex::N(b)), attributes }) => (format!("{} -> {}", text(&a.0), text(&b.0)), attributes),
+                x => panic!("{x:?}"),
+            };
+            for attribute in attributes {
+                if matches!(text(&attribute.0).as_str(), "pos" | "lp") {
+                    positions.push(format!("{id} {}", text(&attribute.1)));
+                }
+            }
+        }
+    }
+    assert_eq!(positions.join("\n"), r#"
+"machine 1" "50,30!"
+"machine 1 bar" "50,-250!"
+"machine 1 buz" "50,-450!"
+"machine 1 foo" "50,-50!"
+"machine 1 bar" -> "machine 1 buz" "50,-350"
+"machine 1 buz" -> "machine 1 foo" "150,-250"
+"machine 1 foo" -> "machine 1 bar" "50,-150"
+"machine 2" "300,30!"
+"machine 2 bar" "300,-50!"
+"machine 2 baz" "300,-250!"
+"machine 2 buzz" "300,-450!"
+"machine 2 bar" -> "machine 2 baz" "300,-150"
+"machine 2 baz" -> "machine 2 buzz" "300,-350"
+"machine 2 buzz" -> "machine 2 bar" "400,-250"
+    "#.trim());
+}
+
+#[test]
+fn test_netsblox_simple_if_timer() {
+    let proj = Project::compile(include_str!("projects/simple-if-timer.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.to_netsblox().unwrap(), r#"
+<room name="untitled" app="netsblox-stateflow">
+  <role name="myRole">
+    <project name="myRole" app="netsblox-stateflow" version="2.4.8">
+      <stage name="Stage" width="480" height="360" costume="0" color="255,255,255,1" tempo="60" threadsafe="false" penlog="false" volume="100" pan="0" lines="round" ternary="false" hyperops="true" codify="false" inheritance="false" sublistIDs="false" scheduled="false">
+        <sprites>
+          <sprite name="Sprite" idx="1" x="0" y="0" heading="90" scale="1" volume="100" pan="0" rotation="1" draggable="true" costume="0" color="80,80,80,1" pen="tip">
+            <scripts>
+              <script>
+                <block s="receiveGo"/>
+              </script>
+              <script>
+                <block s="receiveCondition">
+                  <block s="reportEquals">
+                    <block var="something"/>
+                    <l>thing 1</l>
+                  </block>
+                </block>
+                <block s="doIf">
+                  <block s="reportGreaterThan">
+                    <block s="getTimer"/>
+                    <l>10</l>
+                  </block>
+                  <script>
+                    <block s="doSetVar">
+                      <l>something</l>
+                      <l>thing 2</l>
+                    </block>
+                  </script>
+                </block>
+              </script>
+              <script>
+                <block s="receiveCondition">
+                  <block s="reportEquals">
+                    <block var="something"/>
+                    <l>thing 2</l>
+                  </block>
+                </block>
+                <block s="doSetVar">
+                  <l>something</l>
+                  <l>thing 1</l>
+                </block>
+              </script>
+            </scripts>
+          </sprite>
+        </sprites>
+      </stage>
+      <variables>
+        <variable name="something">
+          <l>0</l>
+        </variable>
+      </variables>
+    </project>
+  </role>
+</room>
+    "#.trim());
+}
+#[test]
+fn test_netsblox_round_trip() {
+    let mut count = 0;
+    for entry in std::fs::read_dir("tests/projects").unwrap() {
+        let path = entry.unwrap().path();
+        let Ok(proj) = Project::compile(&std::fs::read_to_string(&path).unwrap(), None, Settings::default()) else { continue };
+        let round = Project::compile(&proj.to_netsblox().unwrap(), None, Settings::default()).unwrap();
+        assert_eq!(round, proj, "{path:?}");
+        count += 1;
+    }
+    assert!(count > 50);
+}
+
+#[test]
+fn test_check_completeness() {
+    let a = Condition::atom("a".parse().unwrap());
+    let b = Condition::atom("b".parse().unwrap());
+    let transition = |ordered_condition: Condition, unordered_condition: Condition| Transition { ordered_condition, unordered_condition, actions: VecDeque::new(), new_state: None };
+    let state_machine = StateMachine {
+        variables: BTreeMap::new(),
+        states: [
+            ("good".into(), State { parent: None, transitions: [
+                transition(a.clone(), a.clone()),
+                transition(Condition::constant(true), !a.clone()),
+            ].into_iter().collect() }),
+            ("partial".into(), State { parent: None, transitions: [
+                transition(a.clone(), a.clone()),
+                transition(b.clone(), !a.clone() & b.clone()),
+            ].into_iter().collect() }),
+            ("overlap".into(), State { parent: None, transitions: [
+                transition(a.clone(), a.clone()),
+                transition(Condition::constant(true), b.clone() | !a.clone()),
+            ].into_iter().collect() }),
+            ("mismatch".into(), State { parent: None, transitions: [
+                transition(a.clone(), a.clone() & b.clone()),
+                transition(Condition::constant(true), !a.clone() | !b.clone()),
+            ].into_iter().collect() }),
+            ("empty".into(), State { parent: None, transitions: VecDeque::new() }),
+        ].into_iter().collect(),
+        initial_state: None,
+        current_state: None,
+        placeholders: BTreeSet::new(),
+    };
+    let assignment = |values: &[(&str, bool)]| values.iter().map(|(k, v)| (k.parse().unwrap(), *v)).collect::<Assignment>();
+    assert_eq!(state_machine.check_completeness(), vec![
+        Finding::NonTotal { state: "empty".into(), counterexample: assignment(&[]) },
+        Finding::Mismatch { state: "mismatch".into(), transition: 0, counterexample: assignment(&[("a", true), ("b", false)]) },
+        Finding::Overlap { state: "overlap".into(), transitions: (0, 1), counterexample: assignment(&[("a", true), ("b", true)]) },
+        Finding::NonTotal { state: "partial".into(), counterexample: assignment(&[("a", false), ("b", false)]) },
+    ]);
+}
+
+#[test]
+fn test_check_completeness_many_atoms() {
+    let atoms = (0..64).map(|i| Condition::atom(format!("x{i}").parse().unwrap())).collect::<Vec<_>>();
+    let mut transitions = VecDeque::new();
+    let mut rest = Condition::constant(true);
+    for atom in atoms.iter() {
+        transitions.push_back(Transition { ordered_condition: atom.clone(), unordered_condition: rest.clone() & atom.clone(), actions: VecDeque::new(), new_state: None });
+        rest = rest & !atom.clone();
+    }
+    transitions.push_back(Transition { ordered_condition: Condition::constant(true), unordered_condition: rest, actions: VecDeque::new(), new_state: None });
+
+    let mut state_machine = StateMachine {
+        variables: BTreeMap::new(),
+        states: [("state".into(), State { parent: None, transitions })].into_iter().collect(),
+        initial_state: None,
+        current_state: None,
+        placeholders: BTreeSet::new(),
+    };
+    assert_eq!(state_machine.check_completeness(), vec![]);
+
+    let transitions = &mut state_machine.states.get_mut("state").unwrap().transitions;
+    transitions.pop_back();
+    assert_eq!(state_machine.check_completeness(), vec![
+        Finding::NonTotal { state: "state".into(), counterexample: (0..64).map(|i| (format!("x{i}").parse().unwrap(), false)).collect() },
+    ]);
+}
+
+#[test]
+fn test_analysis_1() {
+    let proj = Project::compile(include_str!("projects/analysis-1.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.state_machines["machine"].analyze(), Analysis {
+        unreachable: ["orphan".into()].into_iter().collect(),
+        dead_ends: ["end".into(), "midle".into()].into_iter().collect(),
+        placeholders: ["midle".into()].into_iter().collect(),
+    });
+}
+
+#[test]
+fn test_analysis_wait_2() {
+    let proj = Project::compile(include_str!("projects/wait-2.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.state_machines["my state"].analyze(), Analysis {
+        unreachable: BTreeSet::new(),
+        dead_ends: ["stop".into()].into_iter().collect(),
+        placeholders: ["stop".into()].into_iter().collect(),
+    });
+}
+
+#[test]
+fn test_analysis_empty_handler() {
+    let mut proj = Project::compile(include_str!("projects/no-transitions-2.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.state_machines["something"].analyze(), Analysis {
+        unreachable: BTreeSet::new(),
+        dead_ends: ["thing 2".into()].into_iter().collect(),
+        placeholders: BTreeSet::new(),
+    });
+
+    proj.state_machines.get_mut("something").unwrap().states.remove("thing 2");
+    assert_eq!(proj.state_machines["something"].analyze(), Analysis::default());
+}
+
+#[test]
+fn test_analysis_no_initial_state() {
+    let proj = Project::compile(include_str!("projects/initial-state-2.xml"), None, Settings::default()).unwrap();
+    assert_eq!(proj.state_machines["something"].analyze(), Analysis::default());
+}
+
+#[test]
+fn test_diagnostics_1() {
+    let err = Project::compile(include_str!("projects/diagnostics-1.xml"), None, Settings::default()).unwrap_err();
+    assert_eq!(err, CompileError::UnsupportedBlock { state_machine: "thing".into(), state: "foo".into(), info: "TurnRight { angle: Expr { kind: Value(String(\"15\")), info: BlockInfo { comment: None, location: None } } }".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![1], collab_id: None }) });
+
+    let CompileDiagnostics { project, errors, warnings } = Project::compile_with_diagnostics(include_str!("projects/diagnostics-1.xml"), None, Settings::default());
+    assert_eq!(warnings, []);
+    assert_eq!(errors, [
+        CompileError::UnsupportedBlock { state_machine: "thing".into(), state: "foo".into(), info: "TurnRight { angle: Expr { kind: Value(String(\"15\")), info: BlockInfo { comment: None, location: None } } }".into(), location: Box::new(Location { entity: "Sprite".into(), script: 0, block_path: vec![1], collab_id: None }) },
+        CompileError::NonTerminalTransition { state_machine: "thing".into(), state: "bar".into(), location: Box::new(Location { entity: "Sprite".into(), script: 1, block_path: vec![0], collab_id: None }) },
+        CompileError::MultipleHandlers { state_machine: "thing".into(), state: "baz".into(), location: Box::new(Location { entity: "Sprite".into(), script: 3, block_path: vec![], collab_id: None }) },
+    ]);
+    assert_eq!(project, Some(Project {
+        name: "untitled".into(),
+        role: "myRole".into(),
+        state_machines: [
+            ("other".into(), StateMachine {
+                variables: [
+                    ("derp".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                ].into_iter().collect(),
+                states: [
+                    ("busy".into(), State {
+                        parent: None,
+                        transitions: [
+                            Transition {
+                                ordered_condition: Condition::constant(true),
+                                unordered_condition: Condition::constant(true),
+                                actions: [].into_iter().collect(),
+                                new_state: None,
+                            },
+                        ].into_iter().collect(),
+                    }),
+                    ("idle".into(), State {
+                        parent: None,
+                        transitions: [
+                            Transition {
+                                ordered_condition: Condition::constant(true),
+                                unordered_condition: Condition::constant(true),
+                                actions: ["derp = 2".parse().unwrap()].into_iter().collect(),
+                                new_state: Some("busy".into()),
+                            },
+                        ].into_iter().collect(),
+                    }),
+                ].into_iter().collect(),
+                initial_state: None,
+                current_state: None,
+                placeholders: ["busy".into()].into_iter().collect(),
+            }),
+            ("thing".into(), StateMachine {
+                variables: [
+                    ("merp".into(), Variable { init: "0".parse().unwrap(), kind: VariableKind::Local }),
+                ].into_iter().collect(),
+                states: [
+                    ("baz".into(), State {
+                        parent: None,
+                        transitions: [
+                            Transition {
+                                ordered_condition: Condition::constant(true),
+                                unordered_condition: Condition::constant(true),
+                                actions: ["merp = 1".parse().unwrap()].into_iter().collect(),
+                                new_state: Some("foo".into()),
+                            },
+                        ].into_iter().collect(),
+                    }),
+                    ("foo".into(), State {
+                        parent: None,
+                        transitions: [
+                            Transition {
+                                ordered_condition: Condition::constant(true),
+                                unordered_condition: Condition::constant(true),
+                                actions: [].into_iter().collect(),
+                                new_state: None,
+                            },
+                        ].into_iter().collect(),
+                    }),
+                ].into_iter().collect(),
+                initial_state: None,
+                current_state: None,
+                placeholders: ["foo".into()].into_iter().collect(),
+            }),
+        ].into_iter().collect(),
+    }));
+
+    let CompileDiagnostics { project, errors, warnings } = Project::compile_with_diagnostics("<not a project>", None, Settings::default());
+    assert_eq!(warnings, []);
+    assert_eq!(project, None);
+    assert!(matches!(errors.as_slice(), [CompileError::ParseError(_)]));
+}
+
+
+#[test]
+fn test_error_messages() {
+    let err = Project::compile(include_str!("projects/if-else-3.xml"), None, Settings::default()).unwrap_err();
+    assert_eq!(err.to_string(), "state 'thing 1' of machine 'something' changes state before the end of its script (sprite 'Sprite', script 1, block 1.2.1)");
+
+    let errors = Project::compile_with_diagnostics(include_str!("projects/diagnostics-1.xml"), None, Settings::default()).errors;
+    assert_eq!(errors.iter().map(|x| x.to_string()).collect::<Vec<_>>(), [
+        "state 'foo' of machine 'thing' uses an unsupported TurnRight block (sprite 'Sprite', script 1, block 2)",
+        "state 'bar' of machine 'thing' changes state before the end of its script (sprite 'Sprite', script 2, block 1)",
+        "state 'baz' of machine 'thing' is handled by more than one script (sprite 'Sprite', script 4)",
+    ]);
+
+    assert_eq!(CompileError::VariableOverlap { state_machines: ("door".into(), "light".into()), variable: "open".into() }.to_string(), "variable 'open' is used by both machine 'door' and machine 'light'");
+    assert_eq!(CompileError::UnknownRole { name: "player".into() }.to_string(), "the project has no role named 'player'");
+    assert_eq!(CompileError::UnsupportedExport { state_machine: "thing".into(), state: "foo".into(), info: "an unknown block".into() }.to_string(), "state 'foo' of machine 'thing' cannot be exported because it uses an unknown block");
+
+    let err = Project::compile("<not a project>", None, Settings::default()).unwrap_err();
+    assert_eq!(err.to_string(), "the project could not be parsed: no project was found");
+    let err = Project::compile(&include_str!("projects/diagnostics-1.xml").replace("<l>merp</l>", "<l>nope</l>"), None, Settings::default()).unwrap_err();
+    assert_eq!(err.to_string(), "the project could not be parsed: variable 'nope' is not defined (sprite 'Sprite')");
+}